@@ -53,6 +53,10 @@ extern "C" {}
 #[link(name = "aaudio")]
 extern "C" {}
 
+#[cfg(all(feature = "nnapi", target_os = "android"))]
+#[link(name = "neuralnetworks")]
+extern "C" {}
+
 #[cfg(all(feature = "sync", target_os = "android"))]
 #[link(name = "sync")]
 extern "C" {}
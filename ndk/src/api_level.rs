@@ -0,0 +1,213 @@
+//! Bindings for [`<android/api-level.h>`] and runtime resolution of newer NDK functions
+//!
+//! The `api-level-NN` cargo features of this crate decide at compile time which NDK functions are
+//! available, and by default every one of them is linked directly. A binary built with
+//! `api-level-33` therefore fails to load on a device running an older Android version, even if
+//! the newer functions are never called.
+//!
+//! With the opt-in `weak-linking` feature, every NDK function that is gated on an `api-level-NN`
+//! feature is instead resolved with [`dlsym()`] on first use, and the methods that call it return
+//! an [`Unsupported`] error on devices where it does not exist. This allows a binary built with
+//! for example `api-level-33` to still load on an API level 24 device, and to use the newer
+//! functions only where they are available. Query [`device_api_level()`] to decide up front which
+//! code path to take.
+//!
+//! Methods that already return an [`std::io::Result`] report this as
+//! [`std::io::ErrorKind::Unsupported`], those returning a module-specific error report its closest
+//! variant (such as `MediaError::ErrorUnsupported`), and those returning an [`Option`] return
+//! [`None`]. Options of the `AudioStreamBuilder` are reported when opening the stream. Methods on
+//! an object that only exists when the functions of its API level do, never fail this way.
+//!
+//! The functions that a module requires regardless of `api-level-NN` features, such as those to
+//! create streams in the [`audio`][crate::audio] module, are still linked directly. Such modules
+//! enable the `api-level-NN` feature of their own minimum API level, which the device must meet.
+//!
+//! [`<android/api-level.h>`]: https://developer.android.com/ndk/reference/group/apilevels
+//! [`dlsym()`]: https://man7.org/linux/man-pages/man3/dlsym.3.html
+
+use std::{
+    ffi::c_void,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Returned by functions that are resolved at runtime, when the function does not exist on the
+/// current device.
+///
+/// This applies to all functions gated on an `api-level-NN` feature with the `weak-linking`
+/// feature, and to a few functions that are exported by the platform but not declared in the
+/// public NDK headers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, thiserror::Error)]
+#[error(
+    "`{symbol}` is not available on this device (API level {})",
+    device_api_level()
+)]
+pub struct Unsupported {
+    symbol: &'static str,
+}
+
+impl Unsupported {
+    /// The name of the NDK function that could not be resolved.
+    pub fn symbol(&self) -> &'static str {
+        self.symbol
+    }
+}
+
+impl From<Unsupported> for std::io::Error {
+    fn from(value: Unsupported) -> Self {
+        Self::new(std::io::ErrorKind::Unsupported, value)
+    }
+}
+
+/// Returns the API level of the device this process is running on, or `-1` on failure.
+///
+/// This is not the same as the API level the binary was compiled for through the
+/// `api-level-NN` features, which is only the minimum required by the bindings in use.
+#[doc(alias = "android_get_device_api_level")]
+pub fn device_api_level() -> i32 {
+    #[cfg(all(feature = "api-level-29", not(feature = "weak-linking")))]
+    {
+        unsafe { ffi::android_get_device_api_level() }
+    }
+    #[cfg(any(not(feature = "api-level-29"), feature = "weak-linking"))]
+    {
+        // `android_get_device_api_level()` is only exported from API level 29; before that the NDK
+        // implements it inline by reading the same system property.
+        extern "C" {
+            fn __system_property_get(name: *const c_char, value: *mut c_char) -> c_int;
+        }
+        use std::ffi::{c_char, c_int, CStr};
+
+        // PROP_VALUE_MAX
+        let mut value = [0 as c_char; 92];
+        let len = unsafe {
+            __system_property_get(
+                b"ro.build.version.sdk\0".as_ptr().cast(),
+                value.as_mut_ptr(),
+            )
+        };
+        if len < 1 {
+            return -1;
+        }
+        unsafe { CStr::from_ptr(value.as_ptr()) }
+            .to_str()
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(-1)
+    }
+}
+
+/// A function pointer that is looked up with `dlsym()` on first use, and cached afterwards.
+// Unused when none of the modules that resolve functions at runtime are enabled
+#[allow(dead_code)]
+pub(crate) struct WeakSymbol {
+    /// NUL-terminated symbol name.
+    name: &'static str,
+    /// `0` when not yet resolved, `1` when missing, the address otherwise.
+    addr: AtomicUsize,
+}
+
+#[allow(dead_code)]
+impl WeakSymbol {
+    const UNRESOLVED: usize = 0;
+    const MISSING: usize = 1;
+
+    pub(crate) const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            addr: AtomicUsize::new(Self::UNRESOLVED),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Result<*mut c_void, Unsupported> {
+        extern "C" {
+            fn dlsym(handle: *mut c_void, symbol: *const std::ffi::c_char) -> *mut c_void;
+        }
+        #[cfg(target_pointer_width = "64")]
+        const RTLD_DEFAULT: *mut c_void = std::ptr::null_mut();
+        #[cfg(target_pointer_width = "32")]
+        const RTLD_DEFAULT: *mut c_void = 0xffffffff as *mut c_void;
+
+        let mut addr = self.addr.load(Ordering::Relaxed);
+        if addr == Self::UNRESOLVED {
+            debug_assert!(self.name.ends_with('\0'));
+            let ptr = unsafe { dlsym(RTLD_DEFAULT, self.name.as_ptr().cast()) };
+            addr = if ptr.is_null() {
+                Self::MISSING
+            } else {
+                ptr as usize
+            };
+            // Racing threads resolve to the same value, so a plain store suffices
+            self.addr.store(addr, Ordering::Relaxed);
+        }
+        if addr == Self::MISSING {
+            Err(Unsupported {
+                symbol: self.name.trim_end_matches('\0'),
+            })
+        } else {
+            Ok(addr as *mut c_void)
+        }
+    }
+}
+
+/// Declares module-local wrappers around NDK functions that may not exist on older devices.
+///
+/// Every declared function returns a [`Result`] with an [`Unsupported`] error. Without the
+/// `weak-linking` feature the wrapper calls the `ffi` function directly and always succeeds; with
+/// it, the function is resolved through [`WeakSymbol`] instead so that it is not referenced by the
+/// binary at link time.
+#[allow(unused_macros)]
+macro_rules! weak_fn {
+    ($($(#[$meta:meta])* fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {$(
+        $(#[$meta])*
+        #[allow(non_snake_case)]
+        unsafe fn $name(
+            $($arg: $ty),*
+        ) -> ::std::result::Result<$crate::api_level::weak_fn!(@ret $($ret)?), $crate::api_level::Unsupported> {
+            #[cfg(not(feature = "weak-linking"))]
+            {
+                Ok(ffi::$name($($arg),*))
+            }
+            #[cfg(feature = "weak-linking")]
+            {
+                static SYMBOL: $crate::api_level::WeakSymbol =
+                    $crate::api_level::WeakSymbol::new(concat!(stringify!($name), "\0"));
+                let f: unsafe extern "C" fn($($ty),*) $(-> $ret)? =
+                    ::std::mem::transmute(SYMBOL.get()?);
+                Ok(f($($arg),*))
+            }
+        }
+    )*};
+    (@ret) => { () };
+    (@ret $ret:ty) => { $ret };
+}
+#[allow(unused_imports)]
+pub(crate) use weak_fn;
+
+/// Declares module-local wrappers around NDK functions that are newer than the bindings in `ffi`.
+///
+/// Like [`weak_fn!`], but the function is always resolved through [`WeakSymbol`] regardless of
+/// the `weak-linking` feature, as there is no `ffi` declaration to link against directly.
+#[allow(unused_macros)]
+macro_rules! runtime_fn {
+    ($(fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {$(
+        #[allow(non_snake_case)]
+        unsafe fn $name(
+            $($arg: $ty),*
+        ) -> ::std::result::Result<$crate::api_level::weak_fn!(@ret $($ret)?), $crate::api_level::Unsupported> {
+            static SYMBOL: $crate::api_level::WeakSymbol =
+                $crate::api_level::WeakSymbol::new(concat!(stringify!($name), "\0"));
+            let f: unsafe extern "C" fn($($ty),*) $(-> $ret)? =
+                ::std::mem::transmute(SYMBOL.get()?);
+            Ok(f($($arg),*))
+        }
+    )*};
+}
+#[allow(unused_imports)]
+pub(crate) use runtime_fn;
+
+/// Panic message for functions that are called on an object of the same API level.
+///
+/// The object can only be obtained through functions of that API level, so these functions are
+/// guaranteed to exist even with the `weak-linking` feature.
+#[allow(dead_code)]
+pub(crate) const SAME_API_LEVEL: &str = "Function should exist at the API level of its object";
@@ -0,0 +1,348 @@
+//! Enumeration of audio devices through [`android.media.AudioManager`], to pick a
+//! [device id][super::AudioStreamBuilder::device_id()] from Rust.
+//!
+//! [`android.media.AudioManager`]: https://developer.android.com/reference/android/media/AudioManager
+#![cfg(feature = "jni")]
+
+use jni::{
+    objects::{JIntArray, JObject, JObjectArray, JString, JValue},
+    JNIEnv,
+};
+use num_enum::{FromPrimitive, IntoPrimitive};
+
+use super::AudioDirection;
+
+/// The kind of an audio device, such as a built-in speaker or a Bluetooth headset.
+///
+/// Note that these match the `TYPE_*` values of [`android.media.AudioDeviceInfo`] in the Android
+/// Java API.
+///
+/// [`android.media.AudioDeviceInfo`]: https://developer.android.com/reference/android/media/AudioDeviceInfo
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[non_exhaustive]
+pub enum AudioDeviceType {
+    /// A device type that is not known to the system.
+    #[doc(alias = "TYPE_UNKNOWN")]
+    Unknown = 0,
+    /// The attached earphone speaker.
+    #[doc(alias = "TYPE_BUILTIN_EARPIECE")]
+    BuiltinEarpiece = 1,
+    /// The speaker system (i.e. a mono speaker or stereo speakers) built in a device.
+    #[doc(alias = "TYPE_BUILTIN_SPEAKER")]
+    BuiltinSpeaker = 2,
+    /// A headset, which is the combination of a headphones and microphone.
+    #[doc(alias = "TYPE_WIRED_HEADSET")]
+    WiredHeadset = 3,
+    /// A pair of wired headphones.
+    #[doc(alias = "TYPE_WIRED_HEADPHONES")]
+    WiredHeadphones = 4,
+    /// An analog line-level connection.
+    #[doc(alias = "TYPE_LINE_ANALOG")]
+    LineAnalog = 5,
+    /// A digital line connection (e.g. SPDIF).
+    #[doc(alias = "TYPE_LINE_DIGITAL")]
+    LineDigital = 6,
+    /// A Bluetooth device typically used for telephony.
+    #[doc(alias = "TYPE_BLUETOOTH_SCO")]
+    BluetoothSco = 7,
+    /// A Bluetooth device supporting the A2DP profile.
+    #[doc(alias = "TYPE_BLUETOOTH_A2DP")]
+    BluetoothA2dp = 8,
+    /// An HDMI connection.
+    #[doc(alias = "TYPE_HDMI")]
+    Hdmi = 9,
+    /// The Audio Return Channel of an HDMI connection.
+    #[doc(alias = "TYPE_HDMI_ARC")]
+    HdmiArc = 10,
+    /// A USB audio device.
+    #[doc(alias = "TYPE_USB_DEVICE")]
+    UsbDevice = 11,
+    /// A USB audio device in accessory mode.
+    #[doc(alias = "TYPE_USB_ACCESSORY")]
+    UsbAccessory = 12,
+    /// The audio device associated with a dock.
+    #[doc(alias = "TYPE_DOCK")]
+    Dock = 13,
+    /// The FM transmission device.
+    #[doc(alias = "TYPE_FM")]
+    Fm = 14,
+    /// The microphone(s) built in a device.
+    #[doc(alias = "TYPE_BUILTIN_MIC")]
+    BuiltinMic = 15,
+    /// An FM reception device.
+    #[doc(alias = "TYPE_FM_TUNER")]
+    FmTuner = 16,
+    /// A TV reception device.
+    #[doc(alias = "TYPE_TV_TUNER")]
+    TvTuner = 17,
+    /// The transmission of audio signals over the telephony network.
+    #[doc(alias = "TYPE_TELEPHONY")]
+    Telephony = 18,
+    /// The auxiliary line-level connectors.
+    #[doc(alias = "TYPE_AUX_LINE")]
+    AuxLine = 19,
+    /// A device connected over IP.
+    #[doc(alias = "TYPE_IP")]
+    Ip = 20,
+    /// A type-agnostic device used for communication with external audio systems.
+    #[doc(alias = "TYPE_BUS")]
+    Bus = 21,
+    /// A USB audio headset.
+    #[doc(alias = "TYPE_USB_HEADSET")]
+    UsbHeadset = 22,
+    /// A hearing aid.
+    #[doc(alias = "TYPE_HEARING_AID")]
+    HearingAid = 23,
+    /// The speaker system built in a device, tuned for outputting sounds like notifications and
+    /// alarms (i.e. sounds the user couldn't necessarily anticipate).
+    #[doc(alias = "TYPE_BUILTIN_SPEAKER_SAFE")]
+    BuiltinSpeakerSafe = 24,
+    /// A rerouting of audio to or from another application, for example for screen recording.
+    #[doc(alias = "TYPE_REMOTE_SUBMIX")]
+    RemoteSubmix = 25,
+    /// A Bluetooth Low Energy (BLE) audio headset.
+    #[doc(alias = "TYPE_BLE_HEADSET")]
+    BleHeadset = 26,
+    /// A Bluetooth Low Energy (BLE) audio speaker.
+    #[doc(alias = "TYPE_BLE_SPEAKER")]
+    BleSpeaker = 27,
+    /// The Enhanced Audio Return Channel of an HDMI connection.
+    #[doc(alias = "TYPE_HDMI_EARC")]
+    HdmiEarc = 29,
+    /// A Bluetooth Low Energy (BLE) broadcast group.
+    #[doc(alias = "TYPE_BLE_BROADCAST")]
+    BleBroadcast = 30,
+    /// The audio device associated with a dock using an analog connection.
+    #[doc(alias = "TYPE_DOCK_ANALOG")]
+    DockAnalog = 31,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// A snapshot of the properties of an [`android.media.AudioDeviceInfo`], as returned by
+/// [`devices()`].
+///
+/// [`android.media.AudioDeviceInfo`]: https://developer.android.com/reference/android/media/AudioDeviceInfo
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AudioDeviceInfo {
+    /// The device id, to be passed to [`AudioStreamBuilder::device_id()`].
+    ///
+    /// [`AudioStreamBuilder::device_id()`]: super::AudioStreamBuilder::device_id()
+    pub id: i32,
+    pub device_type: AudioDeviceType,
+    /// The human-readable name of the device, as reported by the manufacturer.
+    pub product_name: String,
+    /// Whether the device can record audio, and is thus usable for
+    /// [`AudioDirection::Input`] streams.
+    pub is_source: bool,
+    /// Whether the device can play audio, and is thus usable for [`AudioDirection::Output`]
+    /// streams.
+    pub is_sink: bool,
+    /// The supported sample rates. Empty if the device supports arbitrary rates.
+    pub sample_rates: Vec<i32>,
+    /// The supported channel counts. Empty if the device supports arbitrary channel counts.
+    pub channel_counts: Vec<i32>,
+    /// The supported encodings, as `ENCODING_*` values of [`android.media.AudioFormat`]. Empty if
+    /// the device supports arbitrary encodings.
+    ///
+    /// [`android.media.AudioFormat`]: https://developer.android.com/reference/android/media/AudioFormat
+    pub encodings: Vec<i32>,
+}
+
+impl AudioDeviceInfo {
+    fn from_java(env: &mut JNIEnv<'_>, device: &JObject<'_>) -> jni::errors::Result<Self> {
+        let product_name = env
+            .call_method(device, "getProductName", "()Ljava/lang/CharSequence;", &[])?
+            .l()?;
+        let product_name = JString::from(
+            env.call_method(&product_name, "toString", "()Ljava/lang/String;", &[])?
+                .l()?,
+        );
+        let product_name = env.get_string(&product_name)?.into();
+
+        Ok(Self {
+            id: env.call_method(device, "getId", "()I", &[])?.i()?,
+            device_type: env.call_method(device, "getType", "()I", &[])?.i()?.into(),
+            product_name,
+            is_source: env.call_method(device, "isSource", "()Z", &[])?.z()?,
+            is_sink: env.call_method(device, "isSink", "()Z", &[])?.z()?,
+            sample_rates: int_array(env, device, "getSampleRates")?,
+            channel_counts: int_array(env, device, "getChannelCounts")?,
+            encodings: int_array(env, device, "getEncodings")?,
+        })
+    }
+}
+
+/// Calls an `int[]`-returning getter on `obj`.
+fn int_array(
+    env: &mut JNIEnv<'_>,
+    obj: &JObject<'_>,
+    method: &str,
+) -> jni::errors::Result<Vec<i32>> {
+    let array = JIntArray::from(env.call_method(obj, method, "()[I", &[])?.l()?);
+    let mut values = vec![0; env.get_array_length(&array)? as usize];
+    env.get_int_array_region(&array, 0, &mut values)?;
+    Ok(values)
+}
+
+/// Lists the audio devices that are currently connected, through
+/// [`AudioManager#getDevices(int)`].
+///
+/// Only devices usable for streams of the given `direction` are returned, or all devices for
+/// an unknown direction. `context` is an [`android.content.Context`] such as the
+/// [`NativeActivity`][crate::native_activity::NativeActivity::activity()].
+///
+/// [`AudioManager#getDevices(int)`]: https://developer.android.com/reference/android/media/AudioManager#getDevices(int)
+/// [`android.content.Context`]: https://developer.android.com/reference/android/content/Context
+///
+/// ```no_run
+/// # use ndk::audio::{devices, AudioDeviceType, AudioDirection, AudioStreamBuilder};
+/// # fn example(env: &mut jni::JNIEnv<'_>, context: &jni::objects::JObject<'_>) {
+/// let speaker = devices(env, context, AudioDirection::Output)
+///     .unwrap()
+///     .into_iter()
+///     .find(|d| d.device_type == AudioDeviceType::BuiltinSpeaker);
+/// let mut builder = AudioStreamBuilder::new().unwrap();
+/// if let Some(speaker) = speaker {
+///     builder = builder.device_id(speaker.id);
+/// }
+/// # }
+/// ```
+#[doc(alias = "getDevices")]
+pub fn devices(
+    env: &mut JNIEnv<'_>,
+    context: &JObject<'_>,
+    direction: AudioDirection,
+) -> jni::errors::Result<Vec<AudioDeviceInfo>> {
+    // AudioManager.GET_DEVICES_INPUTS, GET_DEVICES_OUTPUTS and GET_DEVICES_ALL
+    let flags = match direction {
+        AudioDirection::Input => 1,
+        AudioDirection::Output => 2,
+        AudioDirection::__Unknown(_) => 3,
+    };
+
+    env.with_local_frame(16, |env| {
+        // Context.AUDIO_SERVICE
+        let service = env.new_string("audio")?;
+        let audio_manager = env
+            .call_method(
+                context,
+                "getSystemService",
+                "(Ljava/lang/String;)Ljava/lang/Object;",
+                &[JValue::from(&service)],
+            )?
+            .l()?;
+        let array = JObjectArray::from(
+            env.call_method(
+                &audio_manager,
+                "getDevices",
+                "(I)[Landroid/media/AudioDeviceInfo;",
+                &[JValue::Int(flags)],
+            )?
+            .l()?,
+        );
+
+        let len = env.get_array_length(&array)?;
+        (0..len)
+            .map(|i| {
+                env.with_local_frame(16, |env| {
+                    let device = env.get_object_array_element(&array, i)?;
+                    AudioDeviceInfo::from_java(env, &device)
+                })
+            })
+            .collect()
+    })
+}
+
+/// The changes between two device lists, as reported by [`AudioDeviceWatcher`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioDeviceChanges {
+    /// The devices that were connected since the previous [`AudioDeviceWatcher::poll()`].
+    pub added: Vec<AudioDeviceInfo>,
+    /// The devices that were disconnected since the previous [`AudioDeviceWatcher::poll()`], as
+    /// they were last seen.
+    pub removed: Vec<AudioDeviceInfo>,
+}
+
+impl AudioDeviceChanges {
+    /// Returns `true` when no device was added or removed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Reports audio devices that were connected or disconnected, by polling.
+///
+/// This does not register with Java's [`AudioManager#registerAudioDeviceCallback()`], which
+/// requires a subclass of `AudioDeviceCallback` that cannot be defined from native code. Instead
+/// the watcher compares the result of [`devices()`] on every [`poll()`][Self::poll()], and does
+/// not run any thread of its own: a change is only noticed by the next call to
+/// [`poll()`][Self::poll()], so the caller's polling interval is the latency with which changes
+/// are reported. Every poll lists all devices through several JNI calls per device, so an
+/// interval of about one second is a reasonable trade-off. Additionally poll right away when an
+/// [`AudioError::Disconnected`][super::AudioError::Disconnected] is reported to the error
+/// callback of a stream.
+///
+/// [`AudioManager#registerAudioDeviceCallback()`]: https://developer.android.com/reference/android/media/AudioManager#registerAudioDeviceCallback(android.media.AudioDeviceCallback,%20android.os.Handler)
+///
+/// ```no_run
+/// # use ndk::audio::{AudioDeviceWatcher, AudioDirection};
+/// # fn example(env: &mut jni::JNIEnv<'_>, context: &jni::objects::JObject<'_>) {
+/// let mut watcher = AudioDeviceWatcher::new(AudioDirection::Output);
+/// let changes = watcher.poll(env, context).unwrap();
+/// for device in &changes.removed {
+///     println!("Disconnected: {}", device.product_name);
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AudioDeviceWatcher {
+    direction: AudioDirection,
+    devices: Vec<AudioDeviceInfo>,
+}
+
+impl AudioDeviceWatcher {
+    /// Creates a watcher for changes to the devices of the given `direction`.
+    ///
+    /// The first [`poll()`][Self::poll()] reports all currently connected devices as added.
+    pub fn new(direction: AudioDirection) -> Self {
+        Self {
+            direction,
+            devices: Vec::new(),
+        }
+    }
+
+    /// Lists the devices with [`devices()`], and returns the devices that were added or removed
+    /// since the previous call.
+    pub fn poll(
+        &mut self,
+        env: &mut JNIEnv<'_>,
+        context: &JObject<'_>,
+    ) -> jni::errors::Result<AudioDeviceChanges> {
+        let devices = devices(env, context, self.direction)?;
+        let changes = AudioDeviceChanges {
+            added: devices
+                .iter()
+                .filter(|d| !self.devices.iter().any(|old| old.id == d.id))
+                .cloned()
+                .collect(),
+            removed: self
+                .devices
+                .iter()
+                .filter(|old| !devices.iter().any(|d| d.id == old.id))
+                .cloned()
+                .collect(),
+        };
+        self.devices = devices;
+        Ok(changes)
+    }
+
+    /// The devices that were connected at the last [`poll()`][Self::poll()].
+    pub fn devices(&self) -> &[AudioDeviceInfo] {
+        &self.devices
+    }
+}
@@ -0,0 +1,204 @@
+//! Latency estimation from [`AudioStream::timestamp()`] and the frame counters of a stream.
+
+use std::{mem::MaybeUninit, time::Duration};
+
+use super::{AudioDirection, AudioError, AudioStream, Clockid, Result, Timestamp};
+
+/// Computes the latency of a stream from a [`Timestamp`] and the number of frames the
+/// application has transferred, without calling into AAudio.
+///
+/// For an [`AudioDirection::Output`] stream, `frames_transferred` is
+/// [`AudioStream::frames_written()`] and the latency is the time until the last written frame
+/// will be presented. For an [`AudioDirection::Input`] stream, it is
+/// [`AudioStream::frames_read()`] and the latency is the time since the next frame to be read
+/// was captured.
+///
+/// `now_nanoseconds` must be taken from the same clock as the [`Timestamp`]. Returns [`None`]
+/// for an invalid `sample_rate` or direction, and clamps negative estimates (that occur due
+/// to jitter) to [`Duration::ZERO`].
+///
+/// ```
+/// # use std::time::Duration;
+/// # use ndk::audio::{latency_from_timestamp, AudioDirection, Timestamp};
+/// // Frame 48000 was presented at t=1s; 4800 frames (100ms at 48kHz) more were written since
+/// let timestamp = Timestamp {
+///     frame_position: 48_000,
+///     time_nanoseconds: 1_000_000_000,
+/// };
+/// let latency =
+///     latency_from_timestamp(AudioDirection::Output, 48_000, 52_800, timestamp, 1_000_000_000);
+/// assert_eq!(latency, Some(Duration::from_millis(100)));
+///
+/// // The same timestamp observed 20ms later
+/// let latency =
+///     latency_from_timestamp(AudioDirection::Output, 48_000, 52_800, timestamp, 1_020_000_000);
+/// assert_eq!(latency, Some(Duration::from_millis(80)));
+///
+/// // 960 frames (20ms) after the captured frame have not been read yet
+/// let latency =
+///     latency_from_timestamp(AudioDirection::Input, 48_000, 48_000, timestamp, 1_020_000_000);
+/// assert_eq!(latency, Some(Duration::from_millis(20)));
+/// ```
+pub fn latency_from_timestamp(
+    direction: AudioDirection,
+    sample_rate: i32,
+    frames_transferred: i64,
+    timestamp: Timestamp,
+    now_nanoseconds: i64,
+) -> Option<Duration> {
+    if sample_rate <= 0 {
+        return None;
+    }
+    let frame_delta = (frames_transferred - timestamp.frame_position) as i128;
+    // Time at which the frame at `frames_transferred` is (or was) presented or captured
+    let frame_time =
+        timestamp.time_nanoseconds as i128 + frame_delta * 1_000_000_000 / sample_rate as i128;
+    let latency = match direction {
+        AudioDirection::Output => frame_time - now_nanoseconds as i128,
+        AudioDirection::Input => now_nanoseconds as i128 - frame_time,
+        AudioDirection::__Unknown(_) => return None,
+    };
+    Some(Duration::from_nanos(
+        latency.clamp(0, u64::MAX as i128) as u64
+    ))
+}
+
+impl AudioStream {
+    /// Estimates the current latency of the stream, from the presentation timestamp and the
+    /// frames written (for output streams) or read (for input streams).
+    ///
+    /// This is an instantaneous estimate that jitters with the timing of the audio hardware, use
+    /// a [`LatencyTracker`] to smooth it over time. See [`latency_from_timestamp()`] for the
+    /// underlying computation.
+    ///
+    /// Fails with [`AudioError::InvalidState`] when the stream is not started, see
+    /// [`AudioStream::timestamp()`].
+    pub fn estimate_latency(&self) -> Result<Duration> {
+        let timestamp = self.timestamp(Clockid::Monotonic)?;
+        let now = unsafe {
+            let mut now = MaybeUninit::uninit();
+            if ffi::clock_gettime(ffi::CLOCK_MONOTONIC as ffi::clockid_t, now.as_mut_ptr()) != 0 {
+                return Err(AudioError::Internal);
+            }
+            now.assume_init()
+        };
+        let now_nanoseconds = now.tv_sec as i64 * 1_000_000_000 + now.tv_nsec as i64;
+        let direction = self.direction();
+        let frames_transferred = match direction {
+            AudioDirection::Input => self.frames_read(),
+            _ => self.frames_written(),
+        };
+        latency_from_timestamp(
+            direction,
+            self.sample_rate(),
+            frames_transferred,
+            timestamp,
+            now_nanoseconds,
+        )
+        .ok_or(AudioError::InvalidState)
+    }
+}
+
+/// Smooths successive latency estimates with an exponential moving average, and keeps track of
+/// the observed range.
+///
+/// Call [`poll()`][Self::poll()] periodically, for example once per frame of a game loop, and
+/// read the smoothed value with [`latency()`][Self::latency()]. Estimates can also be fed in
+/// manually with [`update()`][Self::update()].
+///
+/// ```
+/// # use std::time::Duration;
+/// # use ndk::audio::LatencyTracker;
+/// let mut tracker = LatencyTracker::new(0.5);
+/// assert_eq!(tracker.update(Duration::from_millis(20)), Duration::from_millis(20));
+/// assert_eq!(tracker.update(Duration::from_millis(40)), Duration::from_millis(30));
+/// assert_eq!(tracker.min(), Some(Duration::from_millis(20)));
+/// assert_eq!(tracker.max(), Some(Duration::from_millis(40)));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LatencyTracker {
+    smoothing: f64,
+    smoothed_nanoseconds: Option<f64>,
+    min: Option<Duration>,
+    max: Option<Duration>,
+    sample_count: u64,
+}
+
+impl Default for LatencyTracker {
+    /// Creates a tracker with a smoothing factor of `0.1`.
+    fn default() -> Self {
+        Self::new(0.1)
+    }
+}
+
+impl LatencyTracker {
+    /// Creates a tracker with the given `smoothing` factor in the range `(0, 1]`, the weight of
+    /// every new estimate in the moving average. Lower values smooth more but react slower.
+    ///
+    /// # Panics
+    /// Panics if `smoothing` is not in the range `(0, 1]`.
+    pub fn new(smoothing: f64) -> Self {
+        assert!(
+            smoothing > 0.0 && smoothing <= 1.0,
+            "smoothing factor must be in the range (0, 1]"
+        );
+        Self {
+            smoothing,
+            smoothed_nanoseconds: None,
+            min: None,
+            max: None,
+            sample_count: 0,
+        }
+    }
+
+    /// Adds a latency estimate, and returns the new smoothed latency.
+    pub fn update(&mut self, latency: Duration) -> Duration {
+        let nanoseconds = latency.as_nanos() as f64;
+        let smoothed = match self.smoothed_nanoseconds {
+            Some(previous) => previous + self.smoothing * (nanoseconds - previous),
+            None => nanoseconds,
+        };
+        self.smoothed_nanoseconds = Some(smoothed);
+        self.min = Some(self.min.map_or(latency, |min| min.min(latency)));
+        self.max = Some(self.max.map_or(latency, |max| max.max(latency)));
+        self.sample_count += 1;
+        Duration::from_nanos(smoothed.round() as u64)
+    }
+
+    /// Adds the current [`AudioStream::estimate_latency()`] of `stream`, and returns the new
+    /// smoothed latency.
+    ///
+    /// Errors (such as [`AudioError::InvalidState`] while the stream is starting) leave the
+    /// tracker unchanged.
+    pub fn poll(&mut self, stream: &AudioStream) -> Result<Duration> {
+        stream
+            .estimate_latency()
+            .map(|latency| self.update(latency))
+    }
+
+    /// The smoothed latency, or [`None`] if no estimate has been added yet.
+    pub fn latency(&self) -> Option<Duration> {
+        self.smoothed_nanoseconds
+            .map(|nanoseconds| Duration::from_nanos(nanoseconds.round() as u64))
+    }
+
+    /// The lowest latency estimate that was added.
+    pub fn min(&self) -> Option<Duration> {
+        self.min
+    }
+
+    /// The highest latency estimate that was added.
+    pub fn max(&self) -> Option<Duration> {
+        self.max
+    }
+
+    /// The number of latency estimates that were added.
+    pub fn sample_count(&self) -> u64 {
+        self.sample_count
+    }
+
+    /// Forgets all estimates, for example after the stream was restarted on another device.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.smoothing);
+    }
+}
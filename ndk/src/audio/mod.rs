@@ -19,7 +19,122 @@ use std::{
 
 use num_enum::{FromPrimitive, IntoPrimitive};
 
-use crate::utils::abort_on_panic;
+mod device;
+mod latency;
+mod realtime_bridge;
+#[cfg(feature = "jni")]
+pub use device::{
+    devices, AudioDeviceChanges, AudioDeviceInfo, AudioDeviceType, AudioDeviceWatcher,
+};
+pub use latency::{latency_from_timestamp, LatencyTracker};
+pub use realtime_bridge::{RealtimeBridge, RealtimeConsumer, RealtimeProducer};
+
+use crate::{
+    api_level::{runtime_fn, weak_fn, Unsupported, WeakSymbol},
+    utils::abort_on_panic,
+};
+
+// Introduced in API level 36, which the bindings in `ffi` do not cover yet
+#[allow(non_camel_case_types)]
+type AAudioStream_presentationEndCallback =
+    Option<unsafe extern "C" fn(stream: *mut ffi::AAudioStream, user_data: *mut c_void)>;
+
+/// Resolved separately from the functions below, so that its availability can be checked without
+/// calling it, see [`AudioStreamBuilder::is_presentation_end_callback_supported()`].
+static SET_PRESENTATION_END_CALLBACK: WeakSymbol =
+    WeakSymbol::new("AAudioStreamBuilder_setPresentationEndCallback\0");
+
+runtime_fn! {
+    fn AAudioStream_setOffloadDelayPadding(
+        stream: *mut ffi::AAudioStream,
+        delay_in_frames: i32,
+        padding_in_frames: i32,
+    ) -> ffi::aaudio_result_t;
+    fn AAudioStream_getOffloadDelay(stream: *mut ffi::AAudioStream) -> i32;
+    fn AAudioStream_getOffloadPadding(stream: *mut ffi::AAudioStream) -> i32;
+    fn AAudioStream_setOffloadEndOfStream(stream: *mut ffi::AAudioStream) -> ffi::aaudio_result_t;
+}
+
+weak_fn! {
+    #[cfg(feature = "api-level-29")]
+    fn AAudioStreamBuilder_setAllowedCapturePolicy(
+        builder: *mut ffi::AAudioStreamBuilder,
+        capture_policy: ffi::aaudio_allowed_capture_policy_t,
+    );
+    #[cfg(feature = "api-level-31")]
+    fn AAudioStreamBuilder_setAttributionTag(
+        builder: *mut ffi::AAudioStreamBuilder,
+        attribution_tag: *const std::ffi::c_char,
+    );
+    #[cfg(feature = "api-level-32")]
+    fn AAudioStreamBuilder_setChannelMask(
+        builder: *mut ffi::AAudioStreamBuilder,
+        channel_mask: ffi::aaudio_channel_mask_t,
+    );
+    #[cfg(feature = "api-level-28")]
+    fn AAudioStreamBuilder_setContentType(
+        builder: *mut ffi::AAudioStreamBuilder,
+        content_type: ffi::aaudio_content_type_t,
+    );
+    #[cfg(feature = "api-level-28")]
+    fn AAudioStreamBuilder_setInputPreset(
+        builder: *mut ffi::AAudioStreamBuilder,
+        input_preset: ffi::aaudio_input_preset_t,
+    );
+    #[cfg(feature = "api-level-32")]
+    fn AAudioStreamBuilder_setIsContentSpatialized(
+        builder: *mut ffi::AAudioStreamBuilder,
+        is_spatialized: bool,
+    );
+    #[cfg(feature = "api-level-30")]
+    fn AAudioStreamBuilder_setPrivacySensitive(
+        builder: *mut ffi::AAudioStreamBuilder,
+        privacy_sensitive: bool,
+    );
+    #[cfg(feature = "api-level-28")]
+    fn AAudioStreamBuilder_setSessionId(
+        builder: *mut ffi::AAudioStreamBuilder,
+        session_id: ffi::aaudio_session_id_t,
+    );
+    #[cfg(feature = "api-level-32")]
+    fn AAudioStreamBuilder_setSpatializationBehavior(
+        builder: *mut ffi::AAudioStreamBuilder,
+        spatialization_behavior: ffi::aaudio_spatialization_behavior_t,
+    );
+    #[cfg(feature = "api-level-28")]
+    fn AAudioStreamBuilder_setUsage(
+        builder: *mut ffi::AAudioStreamBuilder,
+        usage: ffi::aaudio_usage_t,
+    );
+    #[cfg(feature = "api-level-29")]
+    fn AAudioStream_getAllowedCapturePolicy(
+        stream: *mut ffi::AAudioStream,
+    ) -> ffi::aaudio_allowed_capture_policy_t;
+    #[cfg(feature = "api-level-32")]
+    fn AAudioStream_getChannelMask(stream: *mut ffi::AAudioStream) -> ffi::aaudio_channel_mask_t;
+    #[cfg(feature = "api-level-28")]
+    fn AAudioStream_getContentType(stream: *mut ffi::AAudioStream) -> ffi::aaudio_content_type_t;
+    #[cfg(feature = "api-level-34")]
+    fn AAudioStream_getHardwareChannelCount(stream: *mut ffi::AAudioStream) -> i32;
+    #[cfg(feature = "api-level-34")]
+    fn AAudioStream_getHardwareFormat(stream: *mut ffi::AAudioStream) -> ffi::aaudio_format_t;
+    #[cfg(feature = "api-level-34")]
+    fn AAudioStream_getHardwareSampleRate(stream: *mut ffi::AAudioStream) -> i32;
+    #[cfg(feature = "api-level-28")]
+    fn AAudioStream_getInputPreset(stream: *mut ffi::AAudioStream) -> ffi::aaudio_input_preset_t;
+    #[cfg(feature = "api-level-28")]
+    fn AAudioStream_getSessionId(stream: *mut ffi::AAudioStream) -> ffi::aaudio_session_id_t;
+    #[cfg(feature = "api-level-32")]
+    fn AAudioStream_getSpatializationBehavior(
+        stream: *mut ffi::AAudioStream,
+    ) -> ffi::aaudio_spatialization_behavior_t;
+    #[cfg(feature = "api-level-28")]
+    fn AAudioStream_getUsage(stream: *mut ffi::AAudioStream) -> ffi::aaudio_usage_t;
+    #[cfg(feature = "api-level-32")]
+    fn AAudioStream_isContentSpatialized(stream: *mut ffi::AAudioStream) -> bool;
+    #[cfg(feature = "api-level-30")]
+    fn AAudioStream_isPrivacySensitive(stream: *mut ffi::AAudioStream) -> bool;
+}
 
 /// Specifying if audio may or may not be captured by other apps or the system.
 ///
@@ -146,12 +261,194 @@ pub enum AudioFormat {
     /// <a href="https://developer.android.com/reference/android/media/AudioTrack#write(float[], int, int, int)"><code>AudioTrack#write(float[], int, int, int)</code></a>.
     #[doc(alias = "AAUDIO_FORMAT_PCM_FLOAT")]
     PCM_Float = ffi::AAUDIO_FORMAT_PCM_FLOAT as ffi::aaudio_format_t,
+    /// This format uses 24-bit samples packed into 3 bytes.
+    /// The bytes are in little-endian order, so the least significant byte
+    /// comes first in the byte array.
+    ///
+    /// The maximum range of the data is -8388608 (0x800000)
+    /// to 8388607 (0x7FFFFF).
+    ///
+    /// Note that the lower precision bits may be ignored by the device.
+    #[cfg(feature = "api-level-31")]
+    #[doc(alias = "AAUDIO_FORMAT_PCM_I24_PACKED")]
+    PCM_I24_Packed = ffi::AAUDIO_FORMAT_PCM_I24_PACKED as ffi::aaudio_format_t,
+    /// This format uses 32-bit samples stored in an i32 data type.
+    /// The maximum range of the data is -2147483648 (0x80000000)
+    /// to 2147483647 (0x7FFFFFFF).
+    ///
+    /// Note that the lower precision bits may be ignored by the device.
+    #[cfg(feature = "api-level-31")]
+    #[doc(alias = "AAUDIO_FORMAT_PCM_I32")]
+    PCM_I32 = ffi::AAUDIO_FORMAT_PCM_I32 as ffi::aaudio_format_t,
+    /// This format is used for compressed audio wrapped in IEC61937 for HDMI or S/PDIF passthrough.
+    ///
+    /// Unlike PCM playback, the Android framework is not able to do format conversion for IEC61937.
+    /// In that case, when IEC61937 is requested, sampling rate and channel count or channel mask
+    /// must be specified. Otherwise, it may fail when opening the stream. Apps are able to get the
+    /// correct configuration for the playback by calling `AudioManager#getDevices(int)`.
+    #[cfg(feature = "api-level-34")]
+    #[doc(alias = "AAUDIO_FORMAT_IEC61937")]
+    IEC61937 = ffi::AAUDIO_FORMAT_IEC61937 as ffi::aaudio_format_t,
+    /// This format is used for audio compressed in MP3 format, for offload playback with
+    /// [`AudioPerformanceMode::PowerSavingOffloaded`].
+    ///
+    /// Available since API level 36.
+    #[doc(alias = "AAUDIO_FORMAT_MP3")]
+    MP3 = 6,
+    /// This format is used for audio compressed in AAC LC format, see [`AudioFormat::MP3`].
+    #[doc(alias = "AAUDIO_FORMAT_AAC_LC")]
+    AAC_LC = 7,
+    /// This format is used for audio compressed in AAC HE V1 format, see [`AudioFormat::MP3`].
+    #[doc(alias = "AAUDIO_FORMAT_AAC_HE_V1")]
+    AAC_HE_V1 = 8,
+    /// This format is used for audio compressed in AAC HE V2 format, see [`AudioFormat::MP3`].
+    #[doc(alias = "AAUDIO_FORMAT_AAC_HE_V2")]
+    AAC_HE_V2 = 9,
+    /// This format is used for audio compressed in AAC ELD format, see [`AudioFormat::MP3`].
+    #[doc(alias = "AAUDIO_FORMAT_AAC_ELD")]
+    AAC_ELD = 10,
+    /// This format is used for audio compressed in AAC XHE format, see [`AudioFormat::MP3`].
+    #[doc(alias = "AAUDIO_FORMAT_AAC_XHE")]
+    AAC_XHE = 11,
+    /// This format is used for audio compressed in OPUS format, see [`AudioFormat::MP3`].
+    #[doc(alias = "AAUDIO_FORMAT_OPUS")]
+    OPUS = 12,
 
     #[doc(hidden)]
     #[num_enum(catch_all)]
     __Unknown(i32),
 }
 
+bitflags::bitflags! {
+    /// Channel masks select the speaker layout of a stream, and are an alternative to a plain
+    /// [channel count][AudioStreamBuilder::channel_count()].
+    ///
+    /// Besides the individual channel positions, common layouts such as [`Self::STEREO`] and
+    /// [`Self::_5POINT1`] are defined as combinations of them.
+    ///
+    /// Note that these match the equivalent values in [`android.media.AudioFormat`] in the
+    /// Android Java API.
+    ///
+    /// [`android.media.AudioFormat`]: https://developer.android.com/reference/android/media/AudioFormat
+    #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+    #[doc(alias = "aaudio_channel_mask_t")]
+    pub struct AudioChannelMask : u32 {
+        #[doc(alias = "AAUDIO_CHANNEL_FRONT_LEFT")]
+        const FRONT_LEFT = ffi::AAUDIO_CHANNEL_FRONT_LEFT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_FRONT_RIGHT")]
+        const FRONT_RIGHT = ffi::AAUDIO_CHANNEL_FRONT_RIGHT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_FRONT_CENTER")]
+        const FRONT_CENTER = ffi::AAUDIO_CHANNEL_FRONT_CENTER as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_LOW_FREQUENCY")]
+        const LOW_FREQUENCY = ffi::AAUDIO_CHANNEL_LOW_FREQUENCY as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_BACK_LEFT")]
+        const BACK_LEFT = ffi::AAUDIO_CHANNEL_BACK_LEFT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_BACK_RIGHT")]
+        const BACK_RIGHT = ffi::AAUDIO_CHANNEL_BACK_RIGHT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_FRONT_LEFT_OF_CENTER")]
+        const FRONT_LEFT_OF_CENTER = ffi::AAUDIO_CHANNEL_FRONT_LEFT_OF_CENTER as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_FRONT_RIGHT_OF_CENTER")]
+        const FRONT_RIGHT_OF_CENTER = ffi::AAUDIO_CHANNEL_FRONT_RIGHT_OF_CENTER as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_BACK_CENTER")]
+        const BACK_CENTER = ffi::AAUDIO_CHANNEL_BACK_CENTER as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_SIDE_LEFT")]
+        const SIDE_LEFT = ffi::AAUDIO_CHANNEL_SIDE_LEFT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_SIDE_RIGHT")]
+        const SIDE_RIGHT = ffi::AAUDIO_CHANNEL_SIDE_RIGHT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TOP_CENTER")]
+        const TOP_CENTER = ffi::AAUDIO_CHANNEL_TOP_CENTER as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TOP_FRONT_LEFT")]
+        const TOP_FRONT_LEFT = ffi::AAUDIO_CHANNEL_TOP_FRONT_LEFT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TOP_FRONT_CENTER")]
+        const TOP_FRONT_CENTER = ffi::AAUDIO_CHANNEL_TOP_FRONT_CENTER as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TOP_FRONT_RIGHT")]
+        const TOP_FRONT_RIGHT = ffi::AAUDIO_CHANNEL_TOP_FRONT_RIGHT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TOP_BACK_LEFT")]
+        const TOP_BACK_LEFT = ffi::AAUDIO_CHANNEL_TOP_BACK_LEFT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TOP_BACK_CENTER")]
+        const TOP_BACK_CENTER = ffi::AAUDIO_CHANNEL_TOP_BACK_CENTER as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TOP_BACK_RIGHT")]
+        const TOP_BACK_RIGHT = ffi::AAUDIO_CHANNEL_TOP_BACK_RIGHT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TOP_SIDE_LEFT")]
+        const TOP_SIDE_LEFT = ffi::AAUDIO_CHANNEL_TOP_SIDE_LEFT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TOP_SIDE_RIGHT")]
+        const TOP_SIDE_RIGHT = ffi::AAUDIO_CHANNEL_TOP_SIDE_RIGHT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_BOTTOM_FRONT_LEFT")]
+        const BOTTOM_FRONT_LEFT = ffi::AAUDIO_CHANNEL_BOTTOM_FRONT_LEFT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_BOTTOM_FRONT_CENTER")]
+        const BOTTOM_FRONT_CENTER = ffi::AAUDIO_CHANNEL_BOTTOM_FRONT_CENTER as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_BOTTOM_FRONT_RIGHT")]
+        const BOTTOM_FRONT_RIGHT = ffi::AAUDIO_CHANNEL_BOTTOM_FRONT_RIGHT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_LOW_FREQUENCY_2")]
+        const LOW_FREQUENCY_2 = ffi::AAUDIO_CHANNEL_LOW_FREQUENCY_2 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_FRONT_WIDE_LEFT")]
+        const FRONT_WIDE_LEFT = ffi::AAUDIO_CHANNEL_FRONT_WIDE_LEFT as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_FRONT_WIDE_RIGHT")]
+        const FRONT_WIDE_RIGHT = ffi::AAUDIO_CHANNEL_FRONT_WIDE_RIGHT as u32;
+
+        #[doc(alias = "AAUDIO_CHANNEL_MONO")]
+        const MONO = ffi::AAUDIO_CHANNEL_MONO as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_STEREO")]
+        const STEREO = ffi::AAUDIO_CHANNEL_STEREO as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_2POINT1")]
+        const _2POINT1 = ffi::AAUDIO_CHANNEL_2POINT1 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TRI")]
+        const TRI = ffi::AAUDIO_CHANNEL_TRI as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_TRI_BACK")]
+        const TRI_BACK = ffi::AAUDIO_CHANNEL_TRI_BACK as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_3POINT1")]
+        const _3POINT1 = ffi::AAUDIO_CHANNEL_3POINT1 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_2POINT0POINT2")]
+        const _2POINT0POINT2 = ffi::AAUDIO_CHANNEL_2POINT0POINT2 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_2POINT1POINT2")]
+        const _2POINT1POINT2 = ffi::AAUDIO_CHANNEL_2POINT1POINT2 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_3POINT0POINT2")]
+        const _3POINT0POINT2 = ffi::AAUDIO_CHANNEL_3POINT0POINT2 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_3POINT1POINT2")]
+        const _3POINT1POINT2 = ffi::AAUDIO_CHANNEL_3POINT1POINT2 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_QUAD")]
+        const QUAD = ffi::AAUDIO_CHANNEL_QUAD as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_QUAD_SIDE")]
+        const QUAD_SIDE = ffi::AAUDIO_CHANNEL_QUAD_SIDE as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_SURROUND")]
+        const SURROUND = ffi::AAUDIO_CHANNEL_SURROUND as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_PENTA")]
+        const PENTA = ffi::AAUDIO_CHANNEL_PENTA as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_5POINT1")]
+        const _5POINT1 = ffi::AAUDIO_CHANNEL_5POINT1 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_5POINT1_SIDE")]
+        const _5POINT1_SIDE = ffi::AAUDIO_CHANNEL_5POINT1_SIDE as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_6POINT1")]
+        const _6POINT1 = ffi::AAUDIO_CHANNEL_6POINT1 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_7POINT1")]
+        const _7POINT1 = ffi::AAUDIO_CHANNEL_7POINT1 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_5POINT1POINT2")]
+        const _5POINT1POINT2 = ffi::AAUDIO_CHANNEL_5POINT1POINT2 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_5POINT1POINT4")]
+        const _5POINT1POINT4 = ffi::AAUDIO_CHANNEL_5POINT1POINT4 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_7POINT1POINT2")]
+        const _7POINT1POINT2 = ffi::AAUDIO_CHANNEL_7POINT1POINT2 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_7POINT1POINT4")]
+        const _7POINT1POINT4 = ffi::AAUDIO_CHANNEL_7POINT1POINT4 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_9POINT1POINT4")]
+        const _9POINT1POINT4 = ffi::AAUDIO_CHANNEL_9POINT1POINT4 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_9POINT1POINT6")]
+        const _9POINT1POINT6 = ffi::AAUDIO_CHANNEL_9POINT1POINT6 as u32;
+        #[doc(alias = "AAUDIO_CHANNEL_FRONT_BACK")]
+        const FRONT_BACK = ffi::AAUDIO_CHANNEL_FRONT_BACK as u32;
+
+        // https://docs.rs/bitflags/latest/bitflags/#externally-defined-flags
+        const _ = !0;
+    }
+}
+
+impl AudioChannelMask {
+    /// Returned by [`AudioStream::channel_mask()`] when the channel mask of the stream is not
+    /// valid, for example because it was opened with only a channel count.
+    #[doc(alias = "AAUDIO_CHANNEL_INVALID")]
+    pub const INVALID: Self = Self::from_bits_retain(ffi::AAUDIO_CHANNEL_INVALID as u32);
+}
+
 /// Defines the audio source.
 /// An audio source defines both a default physical source of audio signal, and a recording
 /// configuration.
@@ -209,6 +506,36 @@ pub enum AudioPerformanceMode {
     /// Reducing latency is more important than battery life.
     #[doc(alias = "AAUDIO_PERFORMANCE_MODE_LOW_LATENCY")]
     LowLatency = ffi::AAUDIO_PERFORMANCE_MODE_LOW_LATENCY as ffi::aaudio_performance_mode_t,
+    /// Extending battery life is more important than low latency, and the compressed or PCM audio
+    /// data is played back by a hardware decoder or DSP ("offload"). Compressed formats such as
+    /// [`AudioFormat::MP3`] require this mode.
+    ///
+    /// This mode is only supported for output streams, and is available since API level 36. Use
+    /// [`AudioStream::set_offload_delay_padding()`] and [`AudioStream::set_offload_end_of_stream()`]
+    /// for gapless playback, and [`AudioStreamBuilder::presentation_end_callback()`] to be
+    /// notified when all data was played.
+    #[doc(alias = "AAUDIO_PERFORMANCE_MODE_POWER_SAVING_OFFLOADED")]
+    PowerSavingOffloaded = 13,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// Specifies whether audio content should be spatialized (rendered on virtual speakers around the
+/// listener) when a spatializer is available on the output device.
+#[cfg(feature = "api-level-32")]
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[doc(alias = "aaudio_spatialization_behavior_t")]
+#[non_exhaustive]
+pub enum AudioSpatializationBehavior {
+    /// Content can be spatialized, the default behavior.
+    #[doc(alias = "AAUDIO_SPATIALIZATION_BEHAVIOR_AUTO")]
+    Auto = ffi::AAUDIO_SPATIALIZATION_BEHAVIOR_AUTO as ffi::aaudio_spatialization_behavior_t,
+    /// Content is never spatialized.
+    #[doc(alias = "AAUDIO_SPATIALIZATION_BEHAVIOR_NEVER")]
+    Never = ffi::AAUDIO_SPATIALIZATION_BEHAVIOR_NEVER as ffi::aaudio_spatialization_behavior_t,
 
     #[doc(hidden)]
     #[num_enum(catch_all)]
@@ -472,6 +799,14 @@ impl fmt::Display for AudioError {
 
 impl std::error::Error for AudioError {}
 
+impl From<Unsupported> for AudioError {
+    /// Functions that do not exist on the current device are reported as
+    /// [`AudioError::Unimplemented`].
+    fn from(_: Unsupported) -> Self {
+        Self::Unimplemented
+    }
+}
+
 impl AudioError {
     #[doc(alias = "AAudio_convertStreamStateToText")]
     pub fn to_text(self) -> Cow<'static, str> {
@@ -506,6 +841,10 @@ pub struct AudioStreamBuilder {
     inner: NonNull<ffi::AAudioStreamBuilder>,
     data_callback: Option<AudioStreamDataCallback>,
     error_callback: Option<AudioStreamErrorCallback>,
+    presentation_end_callback: Option<AudioStreamPresentationEndCallback>,
+    /// The first option that is not supported by the current device, returned by
+    /// [`AudioStreamBuilder::open_stream()`].
+    unsupported: Option<Unsupported>,
 }
 
 impl fmt::Debug for AudioStreamBuilder {
@@ -526,6 +865,14 @@ impl fmt::Debug for AudioStreamBuilder {
                     None => &"None",
                 },
             )
+            .field(
+                "presentation_end_callback",
+                match &self.presentation_end_callback {
+                    Some(_) => &"Some(_)",
+                    None => &"None",
+                },
+            )
+            .field("unsupported", &self.unsupported)
             .finish()
     }
 }
@@ -535,6 +882,118 @@ pub type AudioStreamDataCallback =
     Box<dyn FnMut(&AudioStream, *mut c_void, i32) -> AudioCallbackResult + Send>;
 #[doc(alias = "AAudioStream_errorCallback")]
 pub type AudioStreamErrorCallback = Box<dyn FnMut(&AudioStream, AudioError) + Send>;
+#[doc(alias = "AAudioStream_presentationEndCallback")]
+pub type AudioStreamPresentationEndCallback = Box<dyn FnMut(&AudioStream) + Send>;
+/// Safe variant of [`AudioStreamDataCallback`], see [`AudioStreamBuilder::typed_data_callback()`].
+#[doc(alias = "AAudioStream_dataCallback")]
+pub type AudioStreamTypedDataCallback =
+    Box<dyn FnMut(&AudioStream, AudioBuffer<'_>) -> AudioCallbackResult + Send>;
+
+/// The audio data of a data callback, matched to the [`AudioFormat`] of the stream.
+///
+/// Every variant holds `num_frames * channel_count` samples, with the samples of all channels of
+/// a frame stored next to each other. For output streams the callback fills the buffer, for
+/// input streams it contains the recorded data.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AudioBuffer<'a> {
+    /// Samples of an [`AudioFormat::PCM_I16`] stream.
+    I16(&'a mut [i16]),
+    /// Samples of an [`AudioFormat::PCM_Float`] stream.
+    F32(&'a mut [f32]),
+    /// Samples of an [`AudioFormat::PCM_I24_Packed`] stream, each as 3 little-endian bytes.
+    #[cfg(feature = "api-level-31")]
+    I24Packed(&'a mut [[u8; 3]]),
+    /// Samples of an [`AudioFormat::PCM_I32`] stream.
+    #[cfg(feature = "api-level-31")]
+    I32(&'a mut [i32]),
+}
+
+impl<'a> AudioBuffer<'a> {
+    /// Wraps `len` samples at `data` in the variant for `format`, or returns [`None`] when
+    /// `format` does not describe PCM samples.
+    ///
+    /// # Safety
+    /// `data` must point to `len` valid samples of `format`, that are not accessed through any
+    /// other pointer for the lifetime `'a`.
+    unsafe fn from_raw(format: AudioFormat, data: *mut c_void, len: usize) -> Option<Self> {
+        use std::slice::from_raw_parts_mut;
+        Some(match format {
+            AudioFormat::PCM_I16 => Self::I16(from_raw_parts_mut(data.cast(), len)),
+            AudioFormat::PCM_Float => Self::F32(from_raw_parts_mut(data.cast(), len)),
+            #[cfg(feature = "api-level-31")]
+            AudioFormat::PCM_I24_Packed => Self::I24Packed(from_raw_parts_mut(data.cast(), len)),
+            #[cfg(feature = "api-level-31")]
+            AudioFormat::PCM_I32 => Self::I32(from_raw_parts_mut(data.cast(), len)),
+            _ => return None,
+        })
+    }
+
+    /// The [`AudioFormat`] of the samples in this buffer.
+    pub fn format(&self) -> AudioFormat {
+        match self {
+            Self::I16(_) => AudioFormat::PCM_I16,
+            Self::F32(_) => AudioFormat::PCM_Float,
+            #[cfg(feature = "api-level-31")]
+            Self::I24Packed(_) => AudioFormat::PCM_I24_Packed,
+            #[cfg(feature = "api-level-31")]
+            Self::I32(_) => AudioFormat::PCM_I32,
+        }
+    }
+
+    /// The number of samples (not frames) in this buffer.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::I16(b) => b.len(),
+            Self::F32(b) => b.len(),
+            #[cfg(feature = "api-level-31")]
+            Self::I24Packed(b) => b.len(),
+            #[cfg(feature = "api-level-31")]
+            Self::I32(b) => b.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A sample type that can be read from or written to an [`AudioStream`] with
+/// [`AudioStream::read_frames()`] and [`AudioStream::write_frames()`].
+///
+/// This trait is sealed and implemented for every PCM [`AudioFormat`].
+pub trait AudioSample: Copy + Default + Send + Sync + 'static + private::Sealed {
+    /// The [`AudioFormat`] of streams that carry this sample type.
+    const FORMAT: AudioFormat;
+}
+
+impl private::Sealed for i16 {}
+impl AudioSample for i16 {
+    const FORMAT: AudioFormat = AudioFormat::PCM_I16;
+}
+
+impl private::Sealed for f32 {}
+impl AudioSample for f32 {
+    const FORMAT: AudioFormat = AudioFormat::PCM_Float;
+}
+
+#[cfg(feature = "api-level-31")]
+impl private::Sealed for [u8; 3] {}
+#[cfg(feature = "api-level-31")]
+impl AudioSample for [u8; 3] {
+    const FORMAT: AudioFormat = AudioFormat::PCM_I24_Packed;
+}
+
+#[cfg(feature = "api-level-31")]
+impl private::Sealed for i32 {}
+#[cfg(feature = "api-level-31")]
+impl AudioSample for i32 {
+    const FORMAT: AudioFormat = AudioFormat::PCM_I32;
+}
 
 impl AudioStreamBuilder {
     fn from_ptr(inner: NonNull<ffi::AAudioStreamBuilder>) -> Self {
@@ -542,6 +1001,8 @@ impl AudioStreamBuilder {
             inner,
             data_callback: None,
             error_callback: None,
+            presentation_end_callback: None,
+            unsupported: None,
         }
     }
 
@@ -549,6 +1010,17 @@ impl AudioStreamBuilder {
         self.inner.as_ptr()
     }
 
+    /// Remembers the first option that is not supported by the current device, which can only
+    /// happen with the `weak-linking` feature, to fail [`open_stream()`][Self::open_stream()].
+    // Unused below api-level-28
+    #[allow(dead_code)]
+    fn record_unsupported(mut self, result: std::result::Result<(), Unsupported>) -> Self {
+        if let Err(unsupported) = result {
+            self.unsupported.get_or_insert(unsupported);
+        }
+        self
+    }
+
     #[doc(alias = "AAudio_createStreamBuilder")]
     pub fn new() -> Result<Self> {
         unsafe {
@@ -572,10 +1044,28 @@ impl AudioStreamBuilder {
     #[cfg(feature = "api-level-29")]
     #[doc(alias = "AAudioStreamBuilder_setAllowedCapturePolicy")]
     pub fn allowed_capture_policy(self, capture_policy: AudioAllowedCapturePolicy) -> Self {
-        unsafe {
-            ffi::AAudioStreamBuilder_setAllowedCapturePolicy(self.as_ptr(), capture_policy.into())
+        let result = unsafe {
+            AAudioStreamBuilder_setAllowedCapturePolicy(self.as_ptr(), capture_policy.into())
         };
-        self
+        self.record_unsupported(result)
+    }
+
+    /// Set the attribution tag of the context that is used to create the stream, which is used
+    /// for attributing the audio to a specific part of the app (for example for permission
+    /// checks and audio usage statistics).
+    ///
+    /// The default, if you do not call this function, is [`None`].
+    ///
+    /// # Parameters
+    ///
+    /// - `attribution_tag`: the attribution tag of the context. The string is copied.
+    #[cfg(feature = "api-level-31")]
+    #[doc(alias = "AAudioStreamBuilder_setAttributionTag")]
+    pub fn attribution_tag(self, attribution_tag: &CStr) -> Self {
+        let result = unsafe {
+            AAudioStreamBuilder_setAttributionTag(self.as_ptr(), attribution_tag.as_ptr())
+        };
+        self.record_unsupported(result)
     }
 
     /// Set the requested buffer capacity in frames.
@@ -611,6 +1101,30 @@ impl AudioStreamBuilder {
         self
     }
 
+    /// Request a channel mask for the stream.
+    ///
+    /// The default, if you do not call this function, is unspecified.
+    /// An optimal value will then be chosen when the stream is opened.
+    /// After opening a stream with an unspecified value, the application must
+    /// query for the actual value, which may vary by device.
+    ///
+    /// If an exact value is specified then an opened stream will use that value.
+    /// If a stream cannot be opened with the specified value then the open will fail.
+    ///
+    /// As the channel count can also be derived from the channel mask, the last call to either
+    /// this function or [`channel_count()`][Self::channel_count()] takes precedence.
+    ///
+    /// # Parameters
+    ///
+    /// - `channel_mask`: Audio channel mask desired.
+    #[cfg(feature = "api-level-32")]
+    #[doc(alias = "AAudioStreamBuilder_setChannelMask")]
+    pub fn channel_mask(self, channel_mask: AudioChannelMask) -> Self {
+        let result =
+            unsafe { AAudioStreamBuilder_setChannelMask(self.as_ptr(), channel_mask.bits()) };
+        self.record_unsupported(result)
+    }
+
     /// Set the type of audio data that the stream will carry.
     ///
     /// The AAudio system will use this information to optimize the
@@ -625,8 +1139,30 @@ impl AudioStreamBuilder {
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AAudioStreamBuilder_setContentType")]
     pub fn content_type(self, content_type: AudioContentType) -> Self {
-        unsafe { ffi::AAudioStreamBuilder_setContentType(self.as_ptr(), content_type.into()) };
-        self
+        let result =
+            unsafe { AAudioStreamBuilder_setContentType(self.as_ptr(), content_type.into()) };
+        self.record_unsupported(result)
+    }
+
+    /// Specifies whether the audio data of this output stream has already been processed for
+    /// spatialization.
+    ///
+    /// If the stream has been processed for spatialization, setting this to [`true`] will prevent
+    /// issues such as double-processing on platforms that will spatialize audio data.
+    ///
+    /// The default, if you do not call this function, is [`false`].
+    ///
+    /// # Parameters
+    ///
+    /// - `is_content_spatialized`: [`true`] if the content is already processed for
+    ///   spatialization.
+    #[cfg(feature = "api-level-32")]
+    #[doc(alias = "AAudioStreamBuilder_setIsContentSpatialized")]
+    pub fn content_spatialized(self, is_content_spatialized: bool) -> Self {
+        let result = unsafe {
+            AAudioStreamBuilder_setIsContentSpatialized(self.as_ptr(), is_content_spatialized)
+        };
+        self.record_unsupported(result)
     }
 
     /// Request that AAudio call the `data_callback` when the stream is running.
@@ -680,6 +1216,7 @@ impl AudioStreamBuilder {
                     inner: NonNull::new_unchecked(stream),
                     data_callback: None,
                     error_callback: None,
+                    presentation_end_callback: None,
                 };
                 let result = (*callback)(&stream, audio_data, num_frames);
                 std::mem::forget(stream);
@@ -700,6 +1237,26 @@ impl AudioStreamBuilder {
         self
     }
 
+    /// Request that AAudio call the `data_callback` when the stream is running, with the audio
+    /// data wrapped in an [`AudioBuffer`] that matches the [`AudioFormat`] of the stream.
+    ///
+    /// This is a safe alternative to [`data_callback()`][Self::data_callback()], and the same
+    /// real-time restrictions apply. If the stream was opened with a format that does not map
+    /// to an [`AudioBuffer`] variant, the callback is not invoked and
+    /// [`AudioCallbackResult::Stop`] is returned instead.
+    #[doc(alias = "AAudioStreamBuilder_setDataCallback")]
+    pub fn typed_data_callback(self, mut callback: AudioStreamTypedDataCallback) -> Self {
+        self.data_callback(Box::new(move |stream, audio_data, num_frames| {
+            let len = num_frames as usize * stream.channel_count() as usize;
+            // SAFETY: AAudio provides num_frames * channel_count samples of the stream format,
+            // that are only valid for the duration of this callback.
+            match unsafe { AudioBuffer::from_raw(stream.format(), audio_data, len) } {
+                Some(buffer) => callback(stream, buffer),
+                None => AudioCallbackResult::Stop,
+            }
+        }))
+    }
+
     /// Request an audio device identified device using an ID.
     /// On Android, for example, the ID could be obtained from the Java AudioManager.
     ///
@@ -759,6 +1316,7 @@ impl AudioStreamBuilder {
                     inner: NonNull::new_unchecked(stream),
                     data_callback: None,
                     error_callback: None,
+                    presentation_end_callback: None,
                 };
                 let err = AudioError::from_result(error).unwrap_err();
                 (*callback)(&stream, err);
@@ -779,6 +1337,62 @@ impl AudioStreamBuilder {
         self
     }
 
+    /// Request that AAudio call the `callback` when all the data of an offloaded stream, up to
+    /// [`AudioStream::set_offload_end_of_stream()`], has been played.
+    ///
+    /// This is only used for output streams with
+    /// [`AudioPerformanceMode::PowerSavingOffloaded`], where the application writes its data
+    /// well ahead of playback. In response the application can write the data of the next track,
+    /// or stop the stream.
+    ///
+    /// The callback is never called on devices below API level 36, see
+    /// [`is_presentation_end_callback_supported()`][Self::is_presentation_end_callback_supported()].
+    #[doc(alias = "AAudioStreamBuilder_setPresentationEndCallback")]
+    pub fn presentation_end_callback(
+        mut self,
+        callback: AudioStreamPresentationEndCallback,
+    ) -> Self {
+        let Ok(set_presentation_end_callback) = SET_PRESENTATION_END_CALLBACK.get() else {
+            return self;
+        };
+        let set_presentation_end_callback: unsafe extern "C" fn(
+            *mut ffi::AAudioStreamBuilder,
+            AAudioStream_presentationEndCallback,
+            *mut c_void,
+        ) = unsafe { std::mem::transmute(set_presentation_end_callback) };
+
+        let mut boxed = Box::new(callback);
+        let ptr: *mut AudioStreamPresentationEndCallback = &mut *boxed;
+
+        unsafe extern "C" fn ffi_callback(stream: *mut ffi::AAudioStream, user_data: *mut c_void) {
+            abort_on_panic(|| {
+                let callback = user_data as *mut AudioStreamPresentationEndCallback;
+                let stream = AudioStream {
+                    inner: NonNull::new_unchecked(stream),
+                    data_callback: None,
+                    error_callback: None,
+                    presentation_end_callback: None,
+                };
+                (*callback)(&stream);
+                std::mem::forget(stream);
+            })
+        }
+
+        unsafe {
+            set_presentation_end_callback(self.as_ptr(), Some(ffi_callback), ptr as *mut c_void)
+        };
+
+        self.presentation_end_callback = Some(boxed);
+
+        self
+    }
+
+    /// Returns whether [`presentation_end_callback()`][Self::presentation_end_callback()] is
+    /// supported on this device, which requires API level 36.
+    pub fn is_presentation_end_callback_supported() -> bool {
+        SET_PRESENTATION_END_CALLBACK.get().is_ok()
+    }
+
     /// Request a sample data format, for example `Format::I16`.
     ///
     /// The default, if you do not call this function, is [`Unspecified`][AudioFormat::Unspecified].
@@ -841,8 +1455,9 @@ impl AudioStreamBuilder {
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AAudioStreamBuilder_setInputPreset")]
     pub fn input_preset(self, input_preset: AudioInputPreset) -> Self {
-        unsafe { ffi::AAudioStreamBuilder_setInputPreset(self.as_ptr(), input_preset.into()) };
-        self
+        let result =
+            unsafe { AAudioStreamBuilder_setInputPreset(self.as_ptr(), input_preset.into()) };
+        self.record_unsupported(result)
     }
 
     /// Set the requested performance mode.
@@ -864,6 +1479,30 @@ impl AudioStreamBuilder {
         self
     }
 
+    /// Indicates whether this input stream must be marked as privacy sensitive or not.
+    ///
+    /// When [`true`], this input stream is privacy sensitive and any concurrent capture is not
+    /// permitted.
+    ///
+    /// This is off (`false`) by default except when the input preset is
+    /// [`AudioInputPreset::VoiceCommunication`] or [`AudioInputPreset::Camcorder`].
+    ///
+    /// Always takes precedence over the default from the input preset when set explicitly.
+    ///
+    /// Only relevant if the stream direction is [`AudioDirection::Input`].
+    ///
+    /// # Parameters
+    ///
+    /// - `privacy_sensitive`: [`true`] if capture from this stream must be marked as privacy
+    ///   sensitive, [`false`] otherwise.
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "AAudioStreamBuilder_setPrivacySensitive")]
+    pub fn privacy_sensitive(self, privacy_sensitive: bool) -> Self {
+        let result =
+            unsafe { AAudioStreamBuilder_setPrivacySensitive(self.as_ptr(), privacy_sensitive) };
+        self.record_unsupported(result)
+    }
+
     /// Request a sample rate in Hertz.
     ///
     /// The default, if you do not call this function, is 0 (unspecified).
@@ -920,8 +1559,8 @@ impl AudioStreamBuilder {
             Some(SessionId::Allocated(value)) => value.get(),
         };
 
-        unsafe { ffi::AAudioStreamBuilder_setSessionId(self.as_ptr(), session_id) };
-        self
+        let result = unsafe { AAudioStreamBuilder_setSessionId(self.as_ptr(), session_id) };
+        self.record_unsupported(result)
     }
 
     /// Request a mode for sharing the device.
@@ -940,6 +1579,32 @@ impl AudioStreamBuilder {
         self
     }
 
+    /// Sets the behavior affecting whether spatialization will be used.
+    ///
+    /// The AAudio system will use this information to select whether the stream will go through a
+    /// spatializer effect or not when the effect is supported and enabled.
+    ///
+    /// The default, if you do not call this function, is [`AudioSpatializationBehavior::Auto`].
+    ///
+    /// # Parameters
+    ///
+    /// - `spatialization_behavior`: the desired behavior with regards to spatialization, eg.
+    ///   [`AudioSpatializationBehavior::Auto`]
+    #[cfg(feature = "api-level-32")]
+    #[doc(alias = "AAudioStreamBuilder_setSpatializationBehavior")]
+    pub fn spatialization_behavior(
+        self,
+        spatialization_behavior: AudioSpatializationBehavior,
+    ) -> Self {
+        let result = unsafe {
+            AAudioStreamBuilder_setSpatializationBehavior(
+                self.as_ptr(),
+                spatialization_behavior.into(),
+            )
+        };
+        self.record_unsupported(result)
+    }
+
     /// Set the intended use case for the stream.
     ///
     /// The AAudio system will use this information to optimize the
@@ -952,13 +1617,19 @@ impl AudioStreamBuilder {
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AAudioStreamBuilder_setUsage")]
     pub fn usage(self, usage: AudioUsage) -> Self {
-        unsafe { ffi::AAudioStreamBuilder_setUsage(self.as_ptr(), usage.into()) };
-        self
+        let result = unsafe { AAudioStreamBuilder_setUsage(self.as_ptr(), usage.into()) };
+        self.record_unsupported(result)
     }
 
     /// Open a stream based on the options in the AAudioStreamBuilder.
+    ///
+    /// Fails with [`AudioError::Unimplemented`] when the `weak-linking` feature is enabled and
+    /// one of the options is not supported by the current device.
     #[doc(alias = "AAudioStreamBuilder_openStream")]
     pub fn open_stream(mut self) -> Result<AudioStream> {
+        if let Some(unsupported) = self.unsupported {
+            return Err(unsupported.into());
+        }
         unsafe {
             let ptr = construct(|res| ffi::AAudioStreamBuilder_openStream(self.as_ptr(), res))?;
 
@@ -966,6 +1637,7 @@ impl AudioStreamBuilder {
                 inner: NonNull::new_unchecked(ptr),
                 data_callback: self.data_callback.take(),
                 error_callback: self.error_callback.take(),
+                presentation_end_callback: self.presentation_end_callback.take(),
             })
         }
     }
@@ -987,8 +1659,14 @@ pub struct AudioStream {
     inner: NonNull<ffi::AAudioStream>,
     data_callback: Option<AudioStreamDataCallback>,
     error_callback: Option<AudioStreamErrorCallback>,
+    presentation_end_callback: Option<AudioStreamPresentationEndCallback>,
 }
 
+// SAFETY: AAudio streams may be used and closed from any thread, as long as they are not closed
+// while another thread is still using them, which the borrow checker enforces. The callbacks are
+// `Send` already.
+unsafe impl Send for AudioStream {}
+
 impl fmt::Debug for AudioStream {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("AAudioStream")
@@ -1007,6 +1685,13 @@ impl fmt::Debug for AudioStream {
                     None => &"None",
                 },
             )
+            .field(
+                "presentation_end_callback",
+                match &self.presentation_end_callback {
+                    Some(_) => &"Some(_)",
+                    None => &"None",
+                },
+            )
             .finish()
     }
 }
@@ -1020,8 +1705,10 @@ impl AudioStream {
     /// may not be captured by other apps or the system.
     #[cfg(feature = "api-level-29")]
     #[doc(alias = "AAudioStream_getAllowedCapturePolicy")]
-    pub fn allowed_capture_policy(self) -> AudioAllowedCapturePolicy {
-        unsafe { ffi::AAudioStream_getAllowedCapturePolicy(self.as_ptr()) }.into()
+    pub fn allowed_capture_policy(
+        self,
+    ) -> std::result::Result<AudioAllowedCapturePolicy, Unsupported> {
+        Ok(unsafe { AAudioStream_getAllowedCapturePolicy(self.as_ptr())? }.into())
     }
 
     /// Query maximum buffer capacity in frames.
@@ -1043,10 +1730,20 @@ impl AudioStream {
         unsafe { ffi::AAudioStream_getChannelCount(self.as_ptr()) }
     }
 
+    /// Returns the channel mask of the stream, or [`AudioChannelMask::INVALID`] if the stream
+    /// was not opened with a valid channel mask.
+    #[cfg(feature = "api-level-32")]
+    #[doc(alias = "AAudioStream_getChannelMask")]
+    pub fn channel_mask(&self) -> std::result::Result<AudioChannelMask, Unsupported> {
+        Ok(AudioChannelMask::from_bits_retain(unsafe {
+            AAudioStream_getChannelMask(self.as_ptr())?
+        }))
+    }
+
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AAudioStream_getContentType")]
-    pub fn content_type(&self) -> AudioContentType {
-        unsafe { ffi::AAudioStream_getContentType(self.as_ptr()) }.into()
+    pub fn content_type(&self) -> std::result::Result<AudioContentType, Unsupported> {
+        Ok(unsafe { AAudioStream_getContentType(self.as_ptr())? }.into())
     }
 
     /// Returns the actual device ID.
@@ -1123,10 +1820,124 @@ impl AudioStream {
         unsafe { ffi::AAudioStream_getFramesWritten(self.as_ptr()) }
     }
 
+    /// Returns the channel count of the data that is actually sent to or received from the
+    /// hardware, which may differ from [`channel_count()`][Self::channel_count()] when the
+    /// framework converts the data.
+    #[cfg(feature = "api-level-34")]
+    #[doc(alias = "AAudioStream_getHardwareChannelCount")]
+    pub fn hardware_channel_count(&self) -> std::result::Result<i32, Unsupported> {
+        Ok(unsafe { AAudioStream_getHardwareChannelCount(self.as_ptr())? })
+    }
+
+    /// Returns the data format of the data that is actually sent to or received from the
+    /// hardware, which may differ from [`format()`][Self::format()] when the framework converts
+    /// the data.
+    #[cfg(feature = "api-level-34")]
+    #[doc(alias = "AAudioStream_getHardwareFormat")]
+    pub fn hardware_format(&self) -> std::result::Result<AudioFormat, Unsupported> {
+        Ok(unsafe { AAudioStream_getHardwareFormat(self.as_ptr())? }.into())
+    }
+
+    /// Returns the sample rate of the data that is actually sent to or received from the
+    /// hardware, which may differ from [`sample_rate()`][Self::sample_rate()] when the framework
+    /// resamples the data.
+    #[cfg(feature = "api-level-34")]
+    #[doc(alias = "AAudioStream_getHardwareSampleRate")]
+    pub fn hardware_sample_rate(&self) -> std::result::Result<i32, Unsupported> {
+        Ok(unsafe { AAudioStream_getHardwareSampleRate(self.as_ptr())? })
+    }
+
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AAudioStream_getInputPreset")]
-    pub fn input_preset(&self) -> AudioInputPreset {
-        unsafe { ffi::AAudioStream_getInputPreset(self.as_ptr()) }.into()
+    pub fn input_preset(&self) -> std::result::Result<AudioInputPreset, Unsupported> {
+        Ok(unsafe { AAudioStream_getInputPreset(self.as_ptr())? }.into())
+    }
+
+    /// Returns whether the content of the stream is already spatialized, as set by
+    /// [`AudioStreamBuilder::content_spatialized()`].
+    #[cfg(feature = "api-level-32")]
+    #[doc(alias = "AAudioStream_isContentSpatialized")]
+    pub fn is_content_spatialized(&self) -> std::result::Result<bool, Unsupported> {
+        Ok(unsafe { AAudioStream_isContentSpatialized(self.as_ptr())? })
+    }
+
+    /// Returns whether the stream uses the low-latency MMAP data path, in which the application
+    /// shares a buffer directly with the audio hardware.
+    ///
+    /// Combined with [`AudioSharingMode::Exclusive`] from [`sharing_mode()`][Self::sharing_mode()]
+    /// this confirms that the stream runs on the lowest-latency path the device offers.
+    ///
+    /// This function is exported by `libaaudio` (since API level 28) but is not part of the public
+    /// NDK headers, hence it is always resolved at runtime and returns [`Unsupported`] when the
+    /// device does not provide it.
+    #[doc(alias = "AAudioStream_isMMapUsed")]
+    pub fn is_mmap_used(&self) -> std::result::Result<bool, Unsupported> {
+        static SYMBOL: WeakSymbol = WeakSymbol::new("AAudioStream_isMMapUsed\0");
+        let is_mmap_used: unsafe extern "C" fn(*mut ffi::AAudioStream) -> bool =
+            unsafe { std::mem::transmute(SYMBOL.get()?) };
+        Ok(unsafe { is_mmap_used(self.as_ptr()) })
+    }
+
+    /// Returns whether this input stream is marked as privacy sensitive, see
+    /// [`AudioStreamBuilder::privacy_sensitive()`].
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "AAudioStream_isPrivacySensitive")]
+    pub fn is_privacy_sensitive(&self) -> std::result::Result<bool, Unsupported> {
+        Ok(unsafe { AAudioStream_isPrivacySensitive(self.as_ptr())? })
+    }
+
+    /// Set the delay and padding of the compressed data of an offloaded stream, in frames.
+    ///
+    /// The delay is the number of frames at the start of the data that are not played (such as
+    /// encoder delay), and the padding is the number of frames at the end that are not played.
+    /// Setting these for every track allows gapless playback. This is only valid for output
+    /// streams with [`AudioPerformanceMode::PowerSavingOffloaded`].
+    ///
+    /// Fails with [`AudioError::Unimplemented`] on devices below API level 36.
+    #[doc(alias = "AAudioStream_setOffloadDelayPadding")]
+    pub fn set_offload_delay_padding(
+        &self,
+        delay_in_frames: i32,
+        padding_in_frames: i32,
+    ) -> Result<()> {
+        let result = unsafe {
+            AAudioStream_setOffloadDelayPadding(self.as_ptr(), delay_in_frames, padding_in_frames)
+        }?;
+        AudioError::from_result(result)
+    }
+
+    /// Returns the offload delay in frames, as set by
+    /// [`set_offload_delay_padding()`][Self::set_offload_delay_padding()].
+    ///
+    /// Fails with [`AudioError::Unimplemented`] on devices below API level 36.
+    #[doc(alias = "AAudioStream_getOffloadDelay")]
+    pub fn offload_delay(&self) -> Result<i32> {
+        let result = unsafe { AAudioStream_getOffloadDelay(self.as_ptr()) }?;
+        AudioError::from_result(result.min(0)).map(|()| result)
+    }
+
+    /// Returns the offload padding in frames, as set by
+    /// [`set_offload_delay_padding()`][Self::set_offload_delay_padding()].
+    ///
+    /// Fails with [`AudioError::Unimplemented`] on devices below API level 36.
+    #[doc(alias = "AAudioStream_getOffloadPadding")]
+    pub fn offload_padding(&self) -> Result<i32> {
+        let result = unsafe { AAudioStream_getOffloadPadding(self.as_ptr()) }?;
+        AudioError::from_result(result.min(0)).map(|()| result)
+    }
+
+    /// Indicate that all the data of the current track has been written to an offloaded stream.
+    ///
+    /// Once the written data has been played, the
+    /// [presentation end callback][AudioStreamBuilder::presentation_end_callback()] is called.
+    /// The application may then write the data of the next track, after updating the
+    /// [delay and padding][Self::set_offload_delay_padding()] for it.
+    ///
+    /// Fails with [`AudioError::Unimplemented`] on devices below API level 36.
+    #[doc(alias = "AAudioStream_setOffloadEndOfStream")]
+    pub fn set_offload_end_of_stream(&self) -> Result<()> {
+        let result = unsafe { AAudioStream_setOffloadEndOfStream(self.as_ptr()) }?;
+        AudioError::from_result(result)
     }
 
     /// Get the performance mode used by the stream.
@@ -1163,12 +1974,12 @@ impl AudioStream {
     /// The sessionID for a stream should not change once the stream has been opened.
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AAudioStream_getSessionId")]
-    pub fn session_id(&self) -> SessionId {
-        let value = unsafe { ffi::AAudioStream_getSessionId(self.as_ptr()) };
-        match value {
+    pub fn session_id(&self) -> std::result::Result<SessionId, Unsupported> {
+        let value = unsafe { AAudioStream_getSessionId(self.as_ptr())? };
+        Ok(match value {
             ffi::AAUDIO_SESSION_ID_NONE => SessionId::None,
             allocated => SessionId::Allocated(NonZeroI32::new(allocated).unwrap()),
-        }
+        })
     }
 
     /// Provide actual sharing mode.
@@ -1177,6 +1988,15 @@ impl AudioStream {
         unsafe { ffi::AAudioStream_getSharingMode(self.as_ptr()) }.into()
     }
 
+    /// Returns the behavior affecting whether spatialization will be used.
+    #[cfg(feature = "api-level-32")]
+    #[doc(alias = "AAudioStream_getSpatializationBehavior")]
+    pub fn spatialization_behavior(
+        &self,
+    ) -> std::result::Result<AudioSpatializationBehavior, Unsupported> {
+        Ok(unsafe { AAudioStream_getSpatializationBehavior(self.as_ptr())? }.into())
+    }
+
     /// Query the current state of the client, eg. [`Pausing`][AudioStreamState::Pausing].
     ///
     /// This function will immediately return the state without updating the state.
@@ -1229,8 +2049,8 @@ impl AudioStream {
 
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AAudioStream_getUsage")]
-    pub fn usage(&self) -> AudioUsage {
-        unsafe { ffi::AAudioStream_getUsage(self.as_ptr()) }.into()
+    pub fn usage(&self) -> std::result::Result<AudioUsage, Unsupported> {
+        Ok(unsafe { AAudioStream_getUsage(self.as_ptr())? }.into())
     }
 
     /// An XRun is an Underrun or an Overrun.
@@ -1408,6 +2228,59 @@ impl AudioStream {
 
         AudioError::from_result(result).map(|()| result as u32)
     }
+
+    /// Returns the number of frames in `len` samples, after checking that `T` matches the
+    /// [`format()`][Self::format()] of the stream.
+    fn frames_for<T: AudioSample>(&self, len: usize) -> Result<i32> {
+        if self.format() != T::FORMAT {
+            return Err(AudioError::InvalidFormat);
+        }
+        let channel_count = self.channel_count() as usize;
+        if channel_count == 0 || len % channel_count != 0 {
+            return Err(AudioError::IllegalArgument);
+        }
+        (len / channel_count)
+            .try_into()
+            .map_err(|_| AudioError::OutOfRange)
+    }
+
+    /// Safe variant of [`read()`][Self::read()], reading as many frames as fit in `buffer`.
+    ///
+    /// Returns the number of frames actually read.
+    ///
+    /// # Errors
+    /// - [`AudioError::InvalidFormat`] if `T` does not match the [`format()`][Self::format()] of
+    ///   the stream.
+    /// - [`AudioError::IllegalArgument`] if the length of `buffer` is not a multiple of the
+    ///   [`channel_count()`][Self::channel_count()].
+    #[doc(alias = "AAudioStream_read")]
+    pub fn read_frames<T: AudioSample>(
+        &self,
+        buffer: &mut [T],
+        timeout_nanoseconds: i64,
+    ) -> Result<u32> {
+        let num_frames = self.frames_for::<T>(buffer.len())?;
+        unsafe { self.read(buffer.as_mut_ptr().cast(), num_frames, timeout_nanoseconds) }
+    }
+
+    /// Safe variant of [`write()`][Self::write()], writing all frames in `buffer`.
+    ///
+    /// Returns the number of frames actually written.
+    ///
+    /// # Errors
+    /// - [`AudioError::InvalidFormat`] if `T` does not match the [`format()`][Self::format()] of
+    ///   the stream.
+    /// - [`AudioError::IllegalArgument`] if the length of `buffer` is not a multiple of the
+    ///   [`channel_count()`][Self::channel_count()].
+    #[doc(alias = "AAudioStream_write")]
+    pub fn write_frames<T: AudioSample>(
+        &self,
+        buffer: &[T],
+        timeout_nanoseconds: i64,
+    ) -> Result<u32> {
+        let num_frames = self.frames_for::<T>(buffer.len())?;
+        unsafe { self.write(buffer.as_ptr().cast(), num_frames, timeout_nanoseconds) }
+    }
 }
 
 impl Drop for AudioStream {
@@ -0,0 +1,424 @@
+//! A lock-free bridge between a real-time [`AudioStream`] data callback and regular threads.
+
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
+
+use super::{
+    AudioCallbackResult, AudioDirection, AudioError, AudioSample, AudioStream, AudioStreamBuilder,
+    Result,
+};
+
+/// Single-producer single-consumer ring buffer of samples.
+struct RingBuffer<T> {
+    buffer: Box<[UnsafeCell<T>]>,
+    /// Total number of samples ever read, only written by the consumer.
+    read: AtomicUsize,
+    /// Total number of samples ever written, only written by the producer.
+    write: AtomicUsize,
+}
+
+// SAFETY: The producer only writes to slots that the consumer has released and vice versa, which
+// is synchronized through the `read` and `write` counters.
+unsafe impl<T: Send> Sync for RingBuffer<T> {}
+
+impl<T: AudioSample> RingBuffer<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            buffer: (0..capacity)
+                .map(|_| UnsafeCell::new(T::default()))
+                .collect(),
+            read: AtomicUsize::new(0),
+            write: AtomicUsize::new(0),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn len(&self) -> usize {
+        let write = self.write.load(Ordering::Acquire);
+        let read = self.read.load(Ordering::Acquire);
+        write.wrapping_sub(read)
+    }
+
+    /// Copies as many samples from `src` as there is room for. Must only be called by the single
+    /// producer.
+    fn push(&self, src: &[T]) -> usize {
+        let write = self.write.load(Ordering::Relaxed);
+        let read = self.read.load(Ordering::Acquire);
+        let n = src.len().min(self.capacity() - write.wrapping_sub(read));
+        for (i, sample) in src[..n].iter().enumerate() {
+            let slot = &self.buffer[write.wrapping_add(i) % self.capacity()];
+            unsafe { *slot.get() = *sample };
+        }
+        self.write.store(write.wrapping_add(n), Ordering::Release);
+        n
+    }
+
+    /// Copies as many samples into `dst` as are available. Must only be called by the single
+    /// consumer.
+    fn pop(&self, dst: &mut [T]) -> usize {
+        let read = self.read.load(Ordering::Relaxed);
+        let write = self.write.load(Ordering::Acquire);
+        let n = dst.len().min(write.wrapping_sub(read));
+        for (i, sample) in dst[..n].iter_mut().enumerate() {
+            let slot = &self.buffer[read.wrapping_add(i) % self.capacity()];
+            *sample = unsafe { *slot.get() };
+        }
+        self.read.store(read.wrapping_add(n), Ordering::Release);
+        n
+    }
+}
+
+/// State shared with the real-time data callback.
+struct State<T> {
+    ring: RingBuffer<T>,
+    underruns: AtomicU64,
+    overruns: AtomicU64,
+}
+
+type Configure = dyn Fn() -> Result<AudioStreamBuilder> + Send + Sync;
+
+struct Shared<T> {
+    state: Arc<State<T>>,
+    configure: Box<Configure>,
+    direction: AudioDirection,
+    channel_count: i32,
+    stream: Mutex<Option<AudioStream>>,
+    /// Whether the stream should be running, so that it is started again after a restart.
+    running: AtomicBool,
+    auto_restart: AtomicBool,
+    restarts: AtomicU64,
+}
+
+impl<T: AudioSample> Shared<T> {
+    fn open_stream(self: &Arc<Self>) -> Result<AudioStream> {
+        let channel_count = self.channel_count;
+        let direction = self.direction;
+
+        let state = self.state.clone();
+        let data_callback =
+            move |_: &AudioStream, audio_data: *mut std::ffi::c_void, num_frames| {
+                let len = num_frames as usize * channel_count as usize;
+                // SAFETY: The format and channel count of the stream were checked after opening it
+                let buffer = unsafe { std::slice::from_raw_parts_mut(audio_data.cast::<T>(), len) };
+                if direction == AudioDirection::Input {
+                    if state.ring.push(buffer) < len {
+                        state.overruns.fetch_add(1, Ordering::Relaxed);
+                    }
+                } else {
+                    let n = state.ring.pop(buffer);
+                    if n < len {
+                        buffer[n..].fill(T::default());
+                        state.underruns.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                AudioCallbackResult::Continue
+            };
+
+        // The stream is owned by `Shared`, only hold on to it weakly to not create a cycle
+        let shared = Arc::downgrade(self);
+        let error_callback = move |_: &AudioStream, error| {
+            if error != AudioError::Disconnected {
+                return;
+            }
+            let Some(shared) = Weak::upgrade(&shared) else {
+                return;
+            };
+            if shared.auto_restart.load(Ordering::Relaxed) {
+                // The stream must not be closed or reopened from within its own callback
+                std::thread::spawn(move || {
+                    if let Err(e) = shared.restart() {
+                        log::error!("Failed to restart disconnected AudioStream: {e}");
+                    }
+                });
+            }
+        };
+
+        let stream = (self.configure)()?
+            .direction(direction)
+            .format(T::FORMAT)
+            .channel_count(channel_count)
+            .data_callback(Box::new(data_callback))
+            .error_callback(Box::new(error_callback))
+            .open_stream()?;
+        if stream.format() != T::FORMAT || stream.channel_count() != channel_count {
+            return Err(AudioError::InvalidFormat);
+        }
+        Ok(stream)
+    }
+
+    fn restart(self: &Arc<Self>) -> Result<()> {
+        let mut stream = self.stream.lock().unwrap();
+        // Check again under the lock, as the bridge may have been dropped since the restart was
+        // scheduled, and the reopened stream would then be left playing without an owner.
+        if !self.auto_restart.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        // Close the disconnected stream before opening the new one, so that the callbacks of both
+        // never run concurrently.
+        drop(stream.take());
+        let new_stream = self.open_stream()?;
+        if self.running.load(Ordering::Relaxed) {
+            new_stream.request_start()?;
+        }
+        *stream = Some(new_stream);
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Opens an [`AudioStream`] whose data callback exchanges samples with regular threads through a
+/// built-in lock-free ring buffer.
+///
+/// The data callback runs on a real-time thread, where locks, allocations and blocking calls
+/// can cause audible glitches. With an output bridge, a [`RealtimeProducer`] writes samples into
+/// the ring buffer that the callback plays back; with an input bridge, the callback records into
+/// the ring buffer that is drained by a [`RealtimeConsumer`]. When the ring buffer runs empty
+/// (output) or full (input) the callback never waits, instead it counts an underrun or overrun.
+///
+/// When the stream reports [`AudioError::Disconnected`], for example because headphones were
+/// unplugged, the bridge reopens the stream on a separate thread with the same configuration
+/// and restarts it if it was running. This can be turned off with
+/// [`set_auto_restart()`][Self::set_auto_restart()].
+///
+/// ```no_run
+/// # use ndk::audio::*;
+/// # fn main() -> Result<()> {
+/// let (bridge, mut producer) = RealtimeBridge::<f32>::output(
+///     || Ok(AudioStreamBuilder::new()?.performance_mode(AudioPerformanceMode::LowLatency)),
+///     2,
+///     4096,
+/// )?;
+/// bridge.start()?;
+/// let silence = [0.0; 512];
+/// producer.push(&silence);
+/// # Ok(())
+/// # }
+/// ```
+pub struct RealtimeBridge<T: AudioSample> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: AudioSample> fmt::Debug for RealtimeBridge<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RealtimeBridge")
+            .field("direction", &self.shared.direction)
+            .field("channel_count", &self.shared.channel_count)
+            .field("capacity", &self.capacity())
+            .field("underrun_count", &self.underrun_count())
+            .field("overrun_count", &self.overrun_count())
+            .field("restart_count", &self.restart_count())
+            .finish()
+    }
+}
+
+impl<T: AudioSample> RealtimeBridge<T> {
+    fn open(
+        configure: Box<Configure>,
+        direction: AudioDirection,
+        channel_count: i32,
+        capacity_in_frames: usize,
+    ) -> Result<Self> {
+        if channel_count <= 0 {
+            return Err(AudioError::IllegalArgument);
+        }
+        let capacity = capacity_in_frames
+            .checked_mul(channel_count as usize)
+            .ok_or(AudioError::IllegalArgument)?;
+        let shared = Arc::new(Shared {
+            state: Arc::new(State {
+                ring: RingBuffer::new(capacity),
+                underruns: AtomicU64::new(0),
+                overruns: AtomicU64::new(0),
+            }),
+            configure,
+            direction,
+            channel_count,
+            stream: Mutex::new(None),
+            running: AtomicBool::new(false),
+            auto_restart: AtomicBool::new(true),
+            restarts: AtomicU64::new(0),
+        });
+        *shared.stream.lock().unwrap() = Some(shared.open_stream()?);
+        Ok(Self { shared })
+    }
+
+    /// Opens an output stream that plays the samples written to the returned
+    /// [`RealtimeProducer`].
+    ///
+    /// `configure` returns a builder with the desired stream parameters, and is called again
+    /// whenever the stream is reopened. The direction, format (from `T`), channel count and
+    /// callbacks are set by the bridge. The ring buffer holds `capacity_in_frames` frames of
+    /// `channel_count` samples each.
+    ///
+    /// Fails with [`AudioError::IllegalArgument`] if `channel_count` is not positive, or if the
+    /// ring buffer size overflows.
+    pub fn output(
+        configure: impl Fn() -> Result<AudioStreamBuilder> + Send + Sync + 'static,
+        channel_count: i32,
+        capacity_in_frames: usize,
+    ) -> Result<(Self, RealtimeProducer<T>)> {
+        let bridge = Self::open(
+            Box::new(configure),
+            AudioDirection::Output,
+            channel_count,
+            capacity_in_frames,
+        )?;
+        let producer = RealtimeProducer {
+            state: bridge.shared.state.clone(),
+        };
+        Ok((bridge, producer))
+    }
+
+    /// Opens an input stream whose recorded samples can be read from the returned
+    /// [`RealtimeConsumer`].
+    ///
+    /// See [`output()`][Self::output()] for the meaning of the parameters.
+    pub fn input(
+        configure: impl Fn() -> Result<AudioStreamBuilder> + Send + Sync + 'static,
+        channel_count: i32,
+        capacity_in_frames: usize,
+    ) -> Result<(Self, RealtimeConsumer<T>)> {
+        let bridge = Self::open(
+            Box::new(configure),
+            AudioDirection::Input,
+            channel_count,
+            capacity_in_frames,
+        )?;
+        let consumer = RealtimeConsumer {
+            state: bridge.shared.state.clone(),
+        };
+        Ok((bridge, consumer))
+    }
+
+    /// Starts the stream, see [`AudioStream::request_start()`].
+    pub fn start(&self) -> Result<()> {
+        self.shared.running.store(true, Ordering::Relaxed);
+        self.with_stream(|s| s.request_start())
+            .unwrap_or(Err(AudioError::Disconnected))
+    }
+
+    /// Stops the stream, see [`AudioStream::request_stop()`].
+    pub fn stop(&self) -> Result<()> {
+        self.shared.running.store(false, Ordering::Relaxed);
+        self.with_stream(|s| s.request_stop())
+            .unwrap_or(Err(AudioError::Disconnected))
+    }
+
+    /// Calls `f` with the current stream, or returns [`None`] if the stream failed to reopen
+    /// after a disconnect.
+    ///
+    /// The stream may be replaced after a disconnect, so it cannot be borrowed for longer.
+    pub fn with_stream<R>(&self, f: impl FnOnce(&AudioStream) -> R) -> Option<R> {
+        self.shared.stream.lock().unwrap().as_ref().map(f)
+    }
+
+    /// Specifies whether the stream is reopened automatically after [`AudioError::Disconnected`].
+    ///
+    /// The default is [`true`].
+    pub fn set_auto_restart(&self, auto_restart: bool) {
+        self.shared
+            .auto_restart
+            .store(auto_restart, Ordering::Relaxed);
+    }
+
+    /// The number of samples that the ring buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.state.ring.capacity()
+    }
+
+    /// The number of times the data callback of an output stream had to play silence because
+    /// the [`RealtimeProducer`] did not provide samples in time.
+    ///
+    /// Unlike [`AudioStream::x_run_count()`], this survives stream restarts and only counts
+    /// underruns between the ring buffer and the data callback.
+    pub fn underrun_count(&self) -> u64 {
+        self.shared.state.underruns.load(Ordering::Relaxed)
+    }
+
+    /// The number of times the data callback of an input stream had to drop samples because
+    /// the [`RealtimeConsumer`] did not read them in time.
+    pub fn overrun_count(&self) -> u64 {
+        self.shared.state.overruns.load(Ordering::Relaxed)
+    }
+
+    /// The [`AudioStream::x_run_count()`] of the current stream, counting underruns and overruns
+    /// between the data callback and the audio hardware.
+    pub fn x_run_count(&self) -> Option<i32> {
+        self.with_stream(|s| s.x_run_count())
+    }
+
+    /// The number of times the stream was reopened after a disconnect.
+    pub fn restart_count(&self) -> u64 {
+        self.shared.restarts.load(Ordering::Relaxed)
+    }
+}
+
+impl<T: AudioSample> Drop for RealtimeBridge<T> {
+    fn drop(&mut self) {
+        // Stop pending restart threads, which check this after taking the lock below
+        self.shared.auto_restart.store(false, Ordering::Relaxed);
+        // Close the stream now; a pending restart thread may still hold on to `Shared`
+        drop(self.shared.stream.lock().unwrap().take());
+    }
+}
+
+/// Writes samples for an output [`RealtimeBridge`], from a regular (non real-time) thread.
+pub struct RealtimeProducer<T: AudioSample> {
+    state: Arc<State<T>>,
+}
+
+impl<T: AudioSample> fmt::Debug for RealtimeProducer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RealtimeProducer")
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+impl<T: AudioSample> RealtimeProducer<T> {
+    /// Copies as many interleaved samples from `samples` into the ring buffer as fit, and
+    /// returns how many were copied. Never blocks.
+    pub fn push(&mut self, samples: &[T]) -> usize {
+        self.state.ring.push(samples)
+    }
+
+    /// The number of samples that can currently be pushed without dropping any.
+    pub fn available(&self) -> usize {
+        self.state.ring.capacity() - self.state.ring.len()
+    }
+}
+
+/// Reads samples from an input [`RealtimeBridge`], on a regular (non real-time) thread.
+pub struct RealtimeConsumer<T: AudioSample> {
+    state: Arc<State<T>>,
+}
+
+impl<T: AudioSample> fmt::Debug for RealtimeConsumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RealtimeConsumer")
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+impl<T: AudioSample> RealtimeConsumer<T> {
+    /// Copies as many interleaved samples from the ring buffer into `samples` as are available,
+    /// and returns how many were copied. Never blocks.
+    pub fn pop(&mut self, samples: &mut [T]) -> usize {
+        self.state.ring.pop(samples)
+    }
+
+    /// The number of samples that can currently be popped.
+    pub fn available(&self) -> usize {
+        self.state.ring.len()
+    }
+}
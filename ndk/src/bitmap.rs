@@ -8,13 +8,46 @@
 
 use jni_sys::{jobject, JNIEnv};
 use num_enum::{FromPrimitive, IntoPrimitive};
-use std::{error, fmt, mem::MaybeUninit};
+use std::{
+    error,
+    ffi::c_void,
+    fmt,
+    mem::{align_of, size_of, MaybeUninit},
+    ops::Deref,
+};
 
+#[cfg(feature = "api-level-30")]
+use std::{ffi::c_int, io};
+
+use crate::api_level::{weak_fn, Unsupported};
 #[cfg(feature = "api-level-30")]
 use crate::data_space::DataSpace;
+#[cfg(all(feature = "jni", feature = "api-level-29"))]
+use crate::hardware_buffer::HardwareBuffer;
 #[cfg(feature = "api-level-30")]
 use crate::hardware_buffer::HardwareBufferRef;
 
+weak_fn! {
+    #[cfg(feature = "api-level-30")]
+    fn AndroidBitmap_getDataSpace(env: *mut JNIEnv, jbitmap: jobject) -> i32;
+    #[cfg(feature = "api-level-30")]
+    fn AndroidBitmap_getHardwareBuffer(
+        env: *mut JNIEnv,
+        bitmap: jobject,
+        out_buffer: *mut *mut ffi::AHardwareBuffer,
+    ) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn AndroidBitmap_compress(
+        info: *const ffi::AndroidBitmapInfo,
+        dataspace: i32,
+        pixels: *const c_void,
+        format: i32,
+        quality: i32,
+        user_context: *mut c_void,
+        fn_: ffi::AndroidBitmap_CompressWriteFunc,
+    ) -> c_int;
+}
+
 #[repr(i32)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
 #[non_exhaustive]
@@ -43,6 +76,14 @@ impl fmt::Display for BitmapError {
 
 impl error::Error for BitmapError {}
 
+impl From<Unsupported> for BitmapError {
+    /// Functions that do not exist on the current device are reported as
+    /// [`BitmapError::BadParameter`].
+    fn from(_: Unsupported) -> Self {
+        Self::BadParameter
+    }
+}
+
 pub type Result<T, E = BitmapError> = std::result::Result<T, E>;
 
 impl BitmapError {
@@ -121,11 +162,14 @@ impl Bitmap {
     ///
     /// Note that [`DataSpace`] only exposes a few values. This may return [`DataSpace::Unknown`],
     /// even for Named ColorSpaces, if they have no corresponding [`DataSpace`].
+    ///
+    /// Returns [`Unsupported`] when the `weak-linking` feature is enabled and the device does not
+    /// support this query.
     #[cfg(feature = "api-level-30")]
     #[doc(alias = "AndroidBitmap_getDataSpace")]
-    pub fn data_space(&self) -> DataSpace {
-        let value = unsafe { ffi::AndroidBitmap_getDataSpace(self.env, self.inner) };
-        value.into()
+    pub fn data_space(&self) -> std::result::Result<DataSpace, Unsupported> {
+        let value = unsafe { AndroidBitmap_getDataSpace(self.env, self.inner)? };
+        Ok(value.into())
     }
 
     /// Attempt to lock the pixel address.
@@ -137,7 +181,7 @@ impl Bitmap {
     /// If this call succeeds, it must be balanced by a call to [`Bitmap::unlock_pixels()`], after
     /// which time the address of the pixels should no longer be used.
     #[doc(alias = "AndroidBitmap_lockPixels")]
-    pub fn lock_pixels(&self) -> Result<*mut std::os::raw::c_void> {
+    pub fn lock_pixels(&self) -> Result<*mut c_void> {
         construct(|res| unsafe { ffi::AndroidBitmap_lockPixels(self.env, self.inner, res) })
     }
 
@@ -148,6 +192,30 @@ impl Bitmap {
         BitmapError::from_status(status)
     }
 
+    /// Lock the pixels for safe read-only access through the returned [`BitmapPixels`] guard,
+    /// which unlocks them again on [`drop()`].
+    ///
+    /// See [`Bitmap::lock_pixels()`] for details.
+    #[doc(alias = "AndroidBitmap_lockPixels")]
+    pub fn lock(&self) -> Result<BitmapPixels<'_>> {
+        let info = self.info()?;
+        let pixels = self.lock_pixels()?;
+        Ok(BitmapPixels {
+            bitmap: self,
+            info,
+            pixels,
+        })
+    }
+
+    /// Mutable variant of [`lock()`][Self::lock()], returning a [`BitmapPixelsMut`] guard.
+    ///
+    /// This borrows `self` mutably, so that no other guard can access the pixels at the same
+    /// time.
+    #[doc(alias = "AndroidBitmap_lockPixels")]
+    pub fn lock_mut(&mut self) -> Result<BitmapPixelsMut<'_>> {
+        Ok(BitmapPixelsMut(self.lock()?))
+    }
+
     /// Retrieve the native object associated with an [`ffi::ANDROID_BITMAP_FLAGS_IS_HARDWARE`]
     /// [`Bitmap`] (requires [`BitmapInfoFlags::is_hardware()`] on [`BitmapInfo::flags()`] to return
     /// [`true`]).
@@ -157,8 +225,11 @@ impl Bitmap {
     #[doc(alias = "AndroidBitmap_getHardwareBuffer")]
     pub fn hardware_buffer(&self) -> Result<HardwareBufferRef> {
         unsafe {
-            let result =
-                construct(|res| ffi::AndroidBitmap_getHardwareBuffer(self.env, self.inner, res))?;
+            let mut result = MaybeUninit::uninit();
+            let status =
+                AndroidBitmap_getHardwareBuffer(self.env, self.inner, result.as_mut_ptr())?;
+            BitmapError::from_status(status)?;
+            let result = result.assume_init();
             let non_null = if cfg!(debug_assertions) {
                 std::ptr::NonNull::new(result).expect("result should never be null")
             } else {
@@ -192,23 +263,20 @@ impl Bitmap {
         quality: i32,
         compress_callback: F,
     ) -> Result<(), BitmapCompressError> {
-        let info = self.info()?;
-        let data_space = self.data_space();
-        let pixels = self.lock_pixels()?;
-        // SAFETY: When lock_pixels() succeeds, assume it returns a valid pointer that stays
-        // valid until we call unlock_pixels().
-        let result = unsafe {
+        let data_space = self.data_space().map_err(BitmapError::from)?;
+        let pixels = self.lock()?;
+        // SAFETY: When lock() succeeds, assume it holds a valid pointer that stays valid until
+        // the guard is dropped.
+        unsafe {
             Self::compress_raw(
-                &info,
+                pixels.info(),
                 data_space,
-                pixels,
+                pixels.as_ptr(),
                 format,
                 quality,
                 compress_callback,
             )
-        };
-        self.unlock_pixels()?;
-        result
+        }
     }
 
     /// Compress `pixels` as described by `info`.
@@ -238,7 +306,7 @@ impl Bitmap {
     pub unsafe fn compress_raw<F: FnMut(&[u8]) -> Result<(), ()>>(
         info: &BitmapInfo,
         data_space: DataSpace,
-        pixels: *const std::ffi::c_void,
+        pixels: *const c_void,
         format: BitmapCompressFormat,
         quality: i32,
         compress_callback: F,
@@ -277,7 +345,7 @@ impl Bitmap {
         }
 
         let status = unsafe {
-            ffi::AndroidBitmap_compress(
+            AndroidBitmap_compress(
                 &info.inner,
                 data_space.into(),
                 pixels,
@@ -286,7 +354,8 @@ impl Bitmap {
                 <*mut _>::cast(&mut cb_state),
                 Some(compress_cb::<F>),
             )
-        };
+        }
+        .map_err(BitmapError::from)?;
 
         if let Some(panic) = cb_state.panic {
             std::panic::resume_unwind(panic)
@@ -294,6 +363,472 @@ impl Bitmap {
 
         Ok(BitmapError::from_status(status)?)
     }
+
+    /// [`compress()`] `self` into `writer`, which is flushed when compression succeeds.
+    ///
+    /// Errors returned by `writer` abort compression and are returned as
+    /// [`BitmapCompressError::Io`].
+    ///
+    /// [`compress()`]: Self::compress()
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "AndroidBitmap_compress")]
+    pub fn compress_to_writer<W: io::Write>(
+        &self,
+        format: BitmapCompressFormat,
+        quality: i32,
+        mut writer: W,
+    ) -> Result<(), BitmapCompressError> {
+        let mut io_error = None;
+        let result = self.compress(format, quality, |data| {
+            writer.write_all(data).map_err(|e| io_error = Some(e))
+        });
+        if let Some(e) = io_error {
+            return Err(e.into());
+        }
+        result?;
+        Ok(writer.flush()?)
+    }
+
+    /// [`compress()`] `self` into a new [`Vec`].
+    ///
+    /// [`compress()`]: Self::compress()
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "AndroidBitmap_compress")]
+    pub fn compress_to_vec(
+        &self,
+        format: BitmapCompressFormat,
+        quality: i32,
+    ) -> Result<Vec<u8>, BitmapCompressError> {
+        let mut data = Vec::new();
+        self.compress_to_writer(format, quality, &mut data)?;
+        Ok(data)
+    }
+
+    /// [`compress_raw()`] `pixels` into `writer`, which is flushed when compression succeeds.
+    ///
+    /// Errors returned by `writer` abort compression and are returned as
+    /// [`BitmapCompressError::Io`].
+    ///
+    /// # Safety
+    /// `pixels` must point to a valid buffer that matches the size, stride and format in `info`.
+    ///
+    /// [`compress_raw()`]: Self::compress_raw()
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "AndroidBitmap_compress")]
+    pub unsafe fn compress_raw_to_writer<W: io::Write>(
+        info: &BitmapInfo,
+        data_space: DataSpace,
+        pixels: *const c_void,
+        format: BitmapCompressFormat,
+        quality: i32,
+        mut writer: W,
+    ) -> Result<(), BitmapCompressError> {
+        let mut io_error = None;
+        let result = Self::compress_raw(info, data_space, pixels, format, quality, |data| {
+            writer.write_all(data).map_err(|e| io_error = Some(e))
+        });
+        if let Some(e) = io_error {
+            return Err(e.into());
+        }
+        result?;
+        Ok(writer.flush()?)
+    }
+
+    /// [`compress_raw()`] `pixels` into a new [`Vec`].
+    ///
+    /// # Safety
+    /// `pixels` must point to a valid buffer that matches the size, stride and format in `info`.
+    ///
+    /// [`compress_raw()`]: Self::compress_raw()
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "AndroidBitmap_compress")]
+    pub unsafe fn compress_raw_to_vec(
+        info: &BitmapInfo,
+        data_space: DataSpace,
+        pixels: *const c_void,
+        format: BitmapCompressFormat,
+        quality: i32,
+    ) -> Result<Vec<u8>, BitmapCompressError> {
+        let mut data = Vec::new();
+        Self::compress_raw_to_writer(info, data_space, pixels, format, quality, &mut data)?;
+        Ok(data)
+    }
+}
+
+/// Constructors for new [`android.graphics.Bitmap`] objects, that can be handed to Java/Kotlin.
+///
+/// These return a JNI local reference, which can be wrapped with [`Bitmap::from_jni()`] to access
+/// the pixels from Rust.
+///
+/// [`android.graphics.Bitmap`]: https://developer.android.com/reference/android/graphics/Bitmap
+#[cfg(feature = "jni")]
+impl Bitmap {
+    const CLASS: &'static str = "android/graphics/Bitmap";
+
+    /// Create a new, mutable [`android.graphics.Bitmap`] with zero-initialized (transparent
+    /// black) pixels, through [`Bitmap#createBitmap(int, int, Config)`].
+    ///
+    /// Fails with [`BitmapCreateError::UnsupportedFormat`] for [`BitmapFormat::RGBA_F16`] below
+    /// API level 26, and for [`BitmapFormat::RGBA_1010102`] below API level 33, where these
+    /// formats have no `Bitmap.Config`.
+    ///
+    /// [`android.graphics.Bitmap`]: https://developer.android.com/reference/android/graphics/Bitmap
+    /// [`Bitmap#createBitmap(int, int, Config)`]: https://developer.android.com/reference/android/graphics/Bitmap#createBitmap(int,%20int,%20android.graphics.Bitmap.Config)
+    #[doc(alias = "createBitmap")]
+    pub fn create<'local>(
+        env: &mut jni::JNIEnv<'local>,
+        width: u32,
+        height: u32,
+        format: BitmapFormat,
+    ) -> Result<jni::objects::JObject<'local>, BitmapCreateError> {
+        #[allow(deprecated)]
+        let (config, min_api_level) = match format {
+            BitmapFormat::RGBA_8888 => ("ARGB_8888", None),
+            BitmapFormat::RGB_565 => ("RGB_565", None),
+            BitmapFormat::RGBA_4444 => ("ARGB_4444", None),
+            BitmapFormat::A_8 => ("ALPHA_8", None),
+            BitmapFormat::RGBA_F16 => ("RGBA_F16", Some(26)),
+            BitmapFormat::RGBA_1010102 => ("RGBA_1010102", Some(33)),
+            format => return Err(BitmapCreateError::UnsupportedFormat(format)),
+        };
+        // Looking up a missing `Bitmap.Config` field would leave a pending `NoSuchFieldError`
+        if min_api_level.map_or(false, |level| crate::api_level::device_api_level() < level) {
+            return Err(BitmapCreateError::UnsupportedFormat(format));
+        }
+        let width = i32::try_from(width).map_err(|_| BitmapError::BadParameter)?;
+        let height = i32::try_from(height).map_err(|_| BitmapError::BadParameter)?;
+
+        let config_class = "android/graphics/Bitmap$Config";
+        let config = env
+            .get_static_field(config_class, config, "Landroid/graphics/Bitmap$Config;")?
+            .l()?;
+        let bitmap = env
+            .call_static_method(
+                Self::CLASS,
+                "createBitmap",
+                "(IILandroid/graphics/Bitmap$Config;)Landroid/graphics/Bitmap;",
+                &[width.into(), height.into(), (&config).into()],
+            )?
+            .l()?;
+        env.delete_local_ref(config)?;
+        Ok(bitmap)
+    }
+
+    /// Create a new [`android.graphics.Bitmap`] of `width` * `height` pixels with the
+    /// [`BitmapFormat`] of `P`, and copy `pixels` into it from Rust.
+    ///
+    /// `pixels` holds the rows of the bitmap without padding, and must contain exactly `width` *
+    /// `height` pixels.
+    ///
+    /// [`android.graphics.Bitmap`]: https://developer.android.com/reference/android/graphics/Bitmap
+    pub fn from_pixels<'local, P: BitmapPixel>(
+        env: &mut jni::JNIEnv<'local>,
+        width: u32,
+        height: u32,
+        pixels: &[P],
+    ) -> Result<jni::objects::JObject<'local>, BitmapCreateError> {
+        let expected = width as usize * height as usize;
+        if pixels.len() != expected {
+            return Err(BitmapCreateError::PixelCount {
+                expected,
+                actual: pixels.len(),
+            });
+        }
+
+        let bitmap = Self::create(env, width, height, P::FORMAT)?;
+        if expected > 0 {
+            // SAFETY: `bitmap` is a valid local reference that outlives `wrapper`
+            let mut wrapper = unsafe { Self::from_jni(env.get_raw(), bitmap.as_raw()) };
+            let mut locked = wrapper.lock_mut()?;
+            for (dst, src) in locked
+                .rows_mut::<P>()?
+                .zip(pixels.chunks_exact(width as usize))
+            {
+                dst.copy_from_slice(src);
+            }
+        }
+        Ok(bitmap)
+    }
+
+    /// Create an immutable [`android.graphics.Bitmap`] that shares the memory of `buffer` without
+    /// copying, through [`Bitmap#wrapHardwareBuffer()`].
+    ///
+    /// `buffer` must have been allocated with
+    /// [`HardwareBufferUsage::GPU_SAMPLED_IMAGE`][crate::hardware_buffer::HardwareBufferUsage::GPU_SAMPLED_IMAGE].
+    /// `color_space` is an [`android.graphics.ColorSpace`], or [`None`] for sRGB.
+    ///
+    /// [`android.graphics.Bitmap`]: https://developer.android.com/reference/android/graphics/Bitmap
+    /// [`Bitmap#wrapHardwareBuffer()`]: https://developer.android.com/reference/android/graphics/Bitmap#wrapHardwareBuffer(android.hardware.HardwareBuffer,%20android.graphics.ColorSpace)
+    /// [`android.graphics.ColorSpace`]: https://developer.android.com/reference/android/graphics/ColorSpace
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "wrapHardwareBuffer")]
+    pub fn wrap_hardware_buffer<'local>(
+        env: &mut jni::JNIEnv<'local>,
+        buffer: &HardwareBuffer,
+        color_space: Option<&jni::objects::JObject<'_>>,
+    ) -> Result<jni::objects::JObject<'local>, BitmapCreateError> {
+        let null = jni::objects::JObject::null();
+        // SAFETY: `env` is a valid JNIEnv, and the returned local reference is owned by us
+        let hardware_buffer =
+            unsafe { jni::objects::JObject::from_raw(buffer.to_jni(env.get_raw())) };
+        let bitmap = env
+            .call_static_method(
+                Self::CLASS,
+                "wrapHardwareBuffer",
+                "(Landroid/hardware/HardwareBuffer;Landroid/graphics/ColorSpace;)Landroid/graphics/Bitmap;",
+                &[(&hardware_buffer).into(), color_space.unwrap_or(&null).into()],
+            )?
+            .l()?;
+        env.delete_local_ref(hardware_buffer)?;
+        if bitmap.is_null() {
+            return Err(BitmapCreateError::HardwareBufferNotWrappable);
+        }
+        Ok(bitmap)
+    }
+}
+
+/// Locked pixels of a [`Bitmap`], returned by [`Bitmap::lock()`].  They are unlocked on
+/// [`drop()`].
+#[derive(Debug)]
+pub struct BitmapPixels<'a> {
+    bitmap: &'a Bitmap,
+    info: BitmapInfo,
+    pixels: *mut c_void,
+}
+
+impl BitmapPixels<'_> {
+    /// The [`BitmapInfo`] describing the layout of the pixels.
+    pub fn info(&self) -> &BitmapInfo {
+        &self.info
+    }
+
+    /// The address of the first pixel.
+    pub fn as_ptr(&self) -> *mut c_void {
+        self.pixels
+    }
+
+    /// All [`BitmapInfo::stride()`] * [`BitmapInfo::height()`] bytes of the pixels.
+    pub fn bytes(&self) -> &[u8] {
+        let len = self.info.stride() as usize * self.info.height() as usize;
+        unsafe { std::slice::from_raw_parts(self.pixels.cast(), len) }
+    }
+
+    /// Checks that `P` matches the format, stride and alignment of the pixels, and returns the
+    /// number of `P` per row of [`BitmapInfo::stride()`] bytes.
+    fn pixels_per_stride<P: BitmapPixel>(&self) -> Result<usize> {
+        let size = size_of::<P>();
+        if self.info.format() != P::FORMAT
+            || self.info.stride() as usize % size != 0
+            || self.pixels as usize % align_of::<P>() != 0
+        {
+            return Err(BitmapError::BadParameter);
+        }
+        Ok(self.info.stride() as usize / size)
+    }
+
+    /// The pixels as a slice of `P`, including any padding pixels at the end of each row that is
+    /// incurred by the stride.
+    ///
+    /// Fails with [`BitmapError::BadParameter`] if `P` does not match the
+    /// [`BitmapInfo::format()`], or if the [`BitmapInfo::stride()`] is not a multiple of the size
+    /// of `P`.
+    pub fn as_slice<P: BitmapPixel>(&self) -> Result<&[P]> {
+        let len = self.pixels_per_stride::<P>()? * self.info.height() as usize;
+        Ok(unsafe { std::slice::from_raw_parts(self.pixels.cast(), len) })
+    }
+
+    /// Returns a slice of [`BitmapInfo::width()`] pixels for each row, ignoring any padding
+    /// pixels incurred by the stride.
+    ///
+    /// Fails under the same conditions as [`as_slice()`][Self::as_slice()].
+    pub fn rows<P: BitmapPixel>(&self) -> Result<impl Iterator<Item = &[P]>> {
+        let stride = self.pixels_per_stride::<P>()?;
+        let width = self.info.width() as usize;
+        Ok(self
+            .as_slice()?
+            .chunks_exact(stride)
+            .map(move |row| &row[..width]))
+    }
+}
+
+impl Drop for BitmapPixels<'_> {
+    #[doc(alias = "AndroidBitmap_unlockPixels")]
+    fn drop(&mut self) {
+        // Panicking here would abort when the guard is dropped during unwinding
+        if let Err(e) = self.bitmap.unlock_pixels() {
+            log::error!("Failed to unlock Bitmap pixels: {e}");
+        }
+    }
+}
+
+/// Mutably locked pixels of a [`Bitmap`], returned by [`Bitmap::lock_mut()`].  They are unlocked
+/// on [`drop()`].
+///
+/// Read access is available through [`Deref`] to [`BitmapPixels`].
+#[derive(Debug)]
+pub struct BitmapPixelsMut<'a>(BitmapPixels<'a>);
+
+impl<'a> Deref for BitmapPixelsMut<'a> {
+    type Target = BitmapPixels<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl BitmapPixelsMut<'_> {
+    /// Mutable variant of [`BitmapPixels::bytes()`].
+    pub fn bytes_mut(&mut self) -> &mut [u8] {
+        let len = self.info.stride() as usize * self.info.height() as usize;
+        unsafe { std::slice::from_raw_parts_mut(self.pixels.cast(), len) }
+    }
+
+    /// Mutable variant of [`BitmapPixels::as_slice()`].
+    pub fn as_slice_mut<P: BitmapPixel>(&mut self) -> Result<&mut [P]> {
+        let len = self.pixels_per_stride::<P>()? * self.info.height() as usize;
+        Ok(unsafe { std::slice::from_raw_parts_mut(self.pixels.cast(), len) })
+    }
+
+    /// Mutable variant of [`BitmapPixels::rows()`].
+    pub fn rows_mut<P: BitmapPixel>(&mut self) -> Result<impl Iterator<Item = &mut [P]>> {
+        let stride = self.pixels_per_stride::<P>()?;
+        let width = self.info.width() as usize;
+        Ok(self
+            .as_slice_mut()?
+            .chunks_exact_mut(stride)
+            .map(move |row| &mut row[..width]))
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A pixel type that can be accessed through [`BitmapPixels::as_slice()`] and
+/// [`BitmapPixels::rows()`].
+///
+/// This trait is sealed and implemented for every non-deprecated [`BitmapFormat`].
+pub trait BitmapPixel: Copy + Default + Send + Sync + 'static + private::Sealed {
+    /// The [`BitmapFormat`] of bitmaps that hold this pixel type.
+    const FORMAT: BitmapFormat;
+}
+
+/// A pixel of a [`BitmapFormat::RGBA_8888`] bitmap.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Rgba8888 {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl private::Sealed for Rgba8888 {}
+impl BitmapPixel for Rgba8888 {
+    const FORMAT: BitmapFormat = BitmapFormat::RGBA_8888;
+}
+
+/// A pixel of a [`BitmapFormat::RGB_565`] bitmap, with 5 bits of red in the most significant
+/// bits, 6 bits of green and 5 bits of blue in the least significant bits.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Rgb565(pub u16);
+
+impl Rgb565 {
+    /// Packs the components, which are truncated to their number of bits.
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self(((r as u16 & 0x1f) << 11) | ((g as u16 & 0x3f) << 5) | (b as u16 & 0x1f))
+    }
+
+    /// The red component, in the range `0..32`.
+    pub fn r(self) -> u8 {
+        (self.0 >> 11) as u8
+    }
+
+    /// The green component, in the range `0..64`.
+    pub fn g(self) -> u8 {
+        ((self.0 >> 5) & 0x3f) as u8
+    }
+
+    /// The blue component, in the range `0..32`.
+    pub fn b(self) -> u8 {
+        (self.0 & 0x1f) as u8
+    }
+}
+
+impl private::Sealed for Rgb565 {}
+impl BitmapPixel for Rgb565 {
+    const FORMAT: BitmapFormat = BitmapFormat::RGB_565;
+}
+
+/// A pixel of a [`BitmapFormat::RGBA_F16`] bitmap.
+///
+/// Every component holds the bits of an IEEE 754 half-precision float.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct RgbaF16 {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+    pub a: u16,
+}
+
+impl private::Sealed for RgbaF16 {}
+impl BitmapPixel for RgbaF16 {
+    const FORMAT: BitmapFormat = BitmapFormat::RGBA_F16;
+}
+
+/// A pixel of a [`BitmapFormat::RGBA_1010102`] bitmap, with 10 bits of red in the least
+/// significant bits, followed by 10 bits of green, 10 bits of blue and 2 bits of alpha.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Rgba1010102(pub u32);
+
+impl Rgba1010102 {
+    /// Packs the components, which are truncated to their number of bits.
+    pub fn new(r: u16, g: u16, b: u16, a: u8) -> Self {
+        Self(
+            (r as u32 & 0x3ff)
+                | ((g as u32 & 0x3ff) << 10)
+                | ((b as u32 & 0x3ff) << 20)
+                | ((a as u32 & 0x3) << 30),
+        )
+    }
+
+    /// The red component, in the range `0..1024`.
+    pub fn r(self) -> u16 {
+        (self.0 & 0x3ff) as u16
+    }
+
+    /// The green component, in the range `0..1024`.
+    pub fn g(self) -> u16 {
+        ((self.0 >> 10) & 0x3ff) as u16
+    }
+
+    /// The blue component, in the range `0..1024`.
+    pub fn b(self) -> u16 {
+        ((self.0 >> 20) & 0x3ff) as u16
+    }
+
+    /// The alpha component, in the range `0..4`.
+    pub fn a(self) -> u8 {
+        (self.0 >> 30) as u8
+    }
+}
+
+impl private::Sealed for Rgba1010102 {}
+impl BitmapPixel for Rgba1010102 {
+    const FORMAT: BitmapFormat = BitmapFormat::RGBA_1010102;
+}
+
+/// A pixel of a [`BitmapFormat::A_8`] bitmap, holding only alpha.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct A8(pub u8);
+
+impl private::Sealed for A8 {}
+impl BitmapPixel for A8 {
+    const FORMAT: BitmapFormat = BitmapFormat::A_8;
 }
 
 /// Possible values for [`ffi::ANDROID_BITMAP_FLAGS_ALPHA_MASK`] within [`BitmapInfoFlags`]
@@ -478,6 +1013,7 @@ pub enum BitmapCompressFormat {
 /// Encapsulates possible errors returned by [`Bitmap::compress()`] or [`Bitmap::compress_raw()`].
 #[cfg(feature = "api-level-30")]
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum BitmapCompressError {
     #[error(transparent)]
     BitmapError(#[from] BitmapError),
@@ -486,4 +1022,29 @@ pub enum BitmapCompressError {
     /// explicitly.
     #[error("The dataspace for this Bitmap is Unknown")]
     DataSpaceUnknown,
+    /// The writer passed to [`Bitmap::compress_to_writer()`] or
+    /// [`Bitmap::compress_raw_to_writer()`] returned an error.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Encapsulates possible errors returned by [`Bitmap::create()`], [`Bitmap::from_pixels()`] and
+/// [`Bitmap::wrap_hardware_buffer()`].
+#[cfg(feature = "jni")]
+#[derive(Debug, thiserror::Error)]
+pub enum BitmapCreateError {
+    #[error(transparent)]
+    BitmapError(#[from] BitmapError),
+    #[error(transparent)]
+    JniError(#[from] jni::errors::Error),
+    /// The [`BitmapFormat`] has no corresponding `android.graphics.Bitmap.Config` on this device.
+    #[error("{0:?} has no corresponding Bitmap.Config")]
+    UnsupportedFormat(BitmapFormat),
+    /// The number of pixels passed to [`Bitmap::from_pixels()`] does not match its size.
+    #[error("Expected {expected} pixels, got {actual}")]
+    PixelCount { expected: usize, actual: usize },
+    /// `Bitmap.wrapHardwareBuffer()` returned `null`, typically because the `HardwareBuffer` was
+    /// not allocated for GPU sampling.
+    #[error("The HardwareBuffer cannot be wrapped in a Bitmap")]
+    HardwareBufferNotWrappable,
 }
@@ -9,11 +9,18 @@
 //!
 //! [`AConfiguration`]: https://developer.android.com/ndk/reference/group/configuration#aconfiguration
 
+#[cfg(feature = "api-level-30")]
+use crate::api_level::{weak_fn, Unsupported};
 use crate::asset::AssetManager;
 use num_enum::{FromPrimitive, IntoPrimitive};
 use std::fmt;
 use std::ptr::NonNull;
 
+#[cfg(feature = "api-level-30")]
+weak_fn! {
+    fn AConfiguration_getScreenRound(config: *mut ffi::AConfiguration) -> i32;
+}
+
 /// A native [`AConfiguration *`]
 ///
 /// [`Configuration`] is an opaque type used to get and set various subsystem configurations.
@@ -243,8 +250,8 @@ impl Configuration {
     }
 
     #[cfg(feature = "api-level-30")]
-    pub fn screen_round(&self) -> ScreenRound {
-        unsafe { ffi::AConfiguration_getScreenRound(self.ptr.as_ptr()).into() }
+    pub fn screen_round(&self) -> Result<ScreenRound, Unsupported> {
+        Ok(unsafe { AConfiguration_getScreenRound(self.ptr.as_ptr())? }.into())
     }
 
     pub fn screen_size(&self) -> ScreenSize {
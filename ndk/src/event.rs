@@ -16,6 +16,28 @@ use std::{ops::Deref, ptr::NonNull};
 use jni_sys::{jobject, JNIEnv};
 use num_enum::{FromPrimitive, IntoPrimitive};
 
+#[cfg(feature = "api-level-33")]
+use crate::api_level::Unsupported;
+#[cfg(feature = "api-level-31")]
+use crate::api_level::{weak_fn, SAME_API_LEVEL};
+
+pub mod gamepad;
+pub mod gestures;
+pub mod input_device;
+pub mod key_character_map;
+pub mod stylus;
+
+#[cfg(feature = "api-level-31")]
+weak_fn! {
+    fn AInputEvent_release(event: *const ffi::AInputEvent);
+    fn AMotionEvent_fromJava(env: *mut JNIEnv, motion_event: jobject) -> *const ffi::AInputEvent;
+    fn AKeyEvent_fromJava(env: *mut JNIEnv, key_event: jobject) -> *const ffi::AInputEvent;
+    #[cfg(feature = "api-level-33")]
+    fn AMotionEvent_getActionButton(motion_event: *const ffi::AInputEvent) -> i32;
+    #[cfg(feature = "api-level-33")]
+    fn AMotionEvent_getClassification(motion_event: *const ffi::AInputEvent) -> i32;
+}
+
 /// A native [`AInputEvent *`]
 ///
 /// [`AInputEvent *`]: https://developer.android.com/ndk/reference/group/input#ainputevent
@@ -48,7 +70,7 @@ impl Drop for MotionEventJava {
     /// The underlying Java object remains valid and does not change its state.
     #[doc(alias = "AInputEvent_release")]
     fn drop(&mut self) {
-        unsafe { ffi::AInputEvent_release(self.0.ptr.as_ptr().cast()) }
+        unsafe { AInputEvent_release(self.0.ptr.as_ptr().cast()) }.expect(SAME_API_LEVEL)
     }
 }
 
@@ -73,12 +95,17 @@ impl Drop for KeyEventJava {
     /// The underlying Java object remains valid and does not change its state.
     #[doc(alias = "AInputEvent_release")]
     fn drop(&mut self) {
-        unsafe { ffi::AInputEvent_release(self.0.ptr.as_ptr().cast()) }
+        unsafe { AInputEvent_release(self.0.ptr.as_ptr().cast()) }.expect(SAME_API_LEVEL)
     }
 }
 
 /// An enum representing the source of an [`InputEvent`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "i32", from = "i32")
+)]
 #[repr(i32)]
 #[non_exhaustive]
 pub enum Source {
@@ -196,10 +223,20 @@ impl InputEvent {
     pub fn device_id(&self) -> i32 {
         unsafe { ffi::AInputEvent_getDeviceId(self.ptr().as_ptr()) }
     }
+
+    /// Captures this event into an [`OwnedInputEvent`], see [`MotionEvent::to_owned()`] and
+    /// [`KeyEvent::to_owned()`].
+    pub fn to_owned(&self) -> OwnedInputEvent {
+        match self {
+            InputEvent::MotionEvent(event) => OwnedInputEvent::MotionEvent(event.to_owned()),
+            InputEvent::KeyEvent(event) => OwnedInputEvent::KeyEvent(event.to_owned()),
+        }
+    }
 }
 
 /// A bitfield representing the state of modifier keys during an event.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MetaState(pub u32);
 
 impl MetaState {
@@ -308,6 +345,11 @@ pub struct MotionEvent {
 
 /// A motion action.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "i32", from = "i32")
+)]
 #[repr(i32)]
 #[non_exhaustive]
 pub enum MotionAction {
@@ -345,6 +387,11 @@ pub enum MotionAction {
 
 /// An axis of a motion event.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "i32", from = "i32")
+)]
 #[repr(i32)]
 #[non_exhaustive]
 #[doc(alias = "AMOTION_EVENT_AXIS")]
@@ -504,6 +551,11 @@ pub enum Axis {
 
 /// The tool type of a pointer.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "i32", from = "i32")
+)]
 #[repr(i32)]
 #[non_exhaustive]
 pub enum ToolType {
@@ -527,6 +579,11 @@ pub enum ToolType {
 
 /// Constants that identify different gesture classification types.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u32", from = "u32")
+)]
 #[repr(u32)]
 #[non_exhaustive]
 #[doc(alias = "AMotionClassification")]
@@ -565,6 +622,7 @@ pub enum MotionClassification {
 
 /// A bitfield representing the state of buttons during a motion event.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ButtonState(pub u32);
 
 impl ButtonState {
@@ -607,6 +665,7 @@ impl ButtonState {
 
 /// A bitfield representing which edges were touched by a motion event.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EdgeFlags(pub u32);
 
 impl EdgeFlags {
@@ -634,6 +693,7 @@ impl EdgeFlags {
 
 /// Flags associated with this [`MotionEvent`].
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MotionEventFlags(pub u32);
 
 impl MotionEventFlags {
@@ -642,6 +702,19 @@ impl MotionEventFlags {
     pub fn window_is_obscured(self) -> bool {
         self.0 & ffi::AMOTION_EVENT_FLAG_WINDOW_IS_OBSCURED != 0
     }
+
+    /// Whether the [`MotionAction::PointerUp`] or [`MotionAction::Cancel`] of this event was
+    /// generated because the system considers the touch accidental, such as a palm resting on the
+    /// screen, and any action it triggered should be undone.
+    ///
+    /// This mirrors the Java [`MotionEvent.FLAG_CANCELED`], which has no NDK constant.
+    ///
+    /// [`MotionEvent.FLAG_CANCELED`]: https://developer.android.com/reference/android/view/MotionEvent#FLAG_CANCELED
+    #[inline]
+    #[doc(alias = "FLAG_CANCELED")]
+    pub fn canceled(self) -> bool {
+        self.0 & 0x20 != 0
+    }
 }
 
 impl MotionEvent {
@@ -669,12 +742,103 @@ impl MotionEvent {
     #[cfg(feature = "api-level-31")]
     #[doc(alias = "AMotionEvent_fromJava")]
     pub unsafe fn from_java(env: *mut JNIEnv, key_event: jobject) -> Option<MotionEventJava> {
-        let ptr = unsafe { ffi::AMotionEvent_fromJava(env, key_event) };
+        let ptr = unsafe { AMotionEvent_fromJava(env, key_event) }.ok()?;
         Some(MotionEventJava(Self::from_ptr(NonNull::new(
             ptr.cast_mut(),
         )?)))
     }
 
+    /// Synthesizes a native [`MotionEvent`] from `event` through the Java
+    /// [`MotionEvent.obtain()`] and [`MotionEvent.addBatch()`] methods, for example to feed
+    /// handcrafted input into code that only accepts [`MotionEvent`] in instrumented tests.
+    ///
+    /// Event times are truncated to the millisecond precision of the Java API.
+    /// [`PointerCoords::raw_x`], [`PointerCoords::raw_y`], [`OwnedMotionEvent::x_offset`],
+    /// [`OwnedMotionEvent::y_offset`], [`OwnedMotionEvent::action_button`] and
+    /// [`OwnedMotionEvent::classification`] cannot be set through the Java API and are ignored.
+    ///
+    /// [`MotionEvent.obtain()`]: https://developer.android.com/reference/android/view/MotionEvent#obtain(long,%20long,%20int,%20int,%20android.view.MotionEvent.PointerProperties[],%20android.view.MotionEvent.PointerCoords[],%20int,%20int,%20float,%20float,%20int,%20int,%20int,%20int)
+    /// [`MotionEvent.addBatch()`]: https://developer.android.com/reference/android/view/MotionEvent#addBatch(long,%20android.view.MotionEvent.PointerCoords[],%20int)
+    #[cfg(all(feature = "api-level-31", feature = "jni"))]
+    #[doc(alias = "MotionEvent.obtain")]
+    pub fn obtain(
+        env: &mut jni::JNIEnv<'_>,
+        event: &OwnedMotionEvent,
+    ) -> jni::errors::Result<MotionEventJava> {
+        use jni::objects::{JObject, JValue};
+
+        const PROPERTIES_CLASS: &str = "android/view/MotionEvent$PointerProperties";
+
+        env.with_local_frame(16, |env| {
+            let pointer_count = event.pointers.len() as i32;
+            let properties =
+                env.new_object_array(pointer_count, PROPERTIES_CLASS, JObject::null())?;
+            for (index, pointer) in event.pointers.iter().enumerate() {
+                let object = env.new_object(PROPERTIES_CLASS, "()V", &[])?;
+                env.set_field(&object, "id", "I", pointer.id.into())?;
+                env.set_field(&object, "toolType", "I", i32::from(pointer.tool_type).into())?;
+                env.set_object_array_element(&properties, index as i32, &object)?;
+                env.delete_local_ref(object)?;
+            }
+
+            // The oldest sample creates the event, all later ones are appended to its history
+            let current: Vec<_> = event.pointers.iter().map(|pointer| &pointer.coords).collect();
+            let mut samples = event
+                .history
+                .iter()
+                .map(|sample| (sample.event_time, sample.pointers.iter().collect()))
+                .chain(std::iter::once((event.event_time, current)));
+            let (event_time, coords) = samples.next().unwrap();
+
+            let action = i32::from(event.action)
+                | (event.pointer_index as i32) << ffi::AMOTION_EVENT_ACTION_POINTER_INDEX_SHIFT;
+            let coords = PointerCoords::to_java_array(env, &coords)?;
+            let object = env
+                .call_static_method(
+                    "android/view/MotionEvent",
+                    "obtain",
+                    "(JJII[Landroid/view/MotionEvent$PointerProperties;[Landroid/view/MotionEvent$PointerCoords;IIFFIIII)Landroid/view/MotionEvent;",
+                    &[
+                        JValue::Long(event.down_time / 1_000_000),
+                        JValue::Long(event_time / 1_000_000),
+                        JValue::Int(action),
+                        JValue::Int(pointer_count),
+                        JValue::Object(&properties),
+                        JValue::Object(&coords),
+                        JValue::Int(event.meta_state.0 as i32),
+                        JValue::Int(event.button_state.0 as i32),
+                        JValue::Float(event.x_precision),
+                        JValue::Float(event.y_precision),
+                        JValue::Int(event.device_id),
+                        JValue::Int(event.edge_flags.0 as i32),
+                        JValue::Int(event.source.into()),
+                        JValue::Int(event.flags.0 as i32),
+                    ],
+                )?
+                .l()?;
+            env.delete_local_ref(coords)?;
+
+            for (event_time, coords) in samples {
+                let coords = PointerCoords::to_java_array(env, &coords)?;
+                env.call_method(
+                    &object,
+                    "addBatch",
+                    "(J[Landroid/view/MotionEvent$PointerCoords;I)V",
+                    &[
+                        JValue::Long(event_time / 1_000_000),
+                        JValue::Object(&coords),
+                        JValue::Int(event.meta_state.0 as i32),
+                    ],
+                )?;
+                env.delete_local_ref(coords)?;
+            }
+
+            let native = unsafe { Self::from_java(env.get_raw(), object.as_raw()) };
+            env.call_method(&object, "recycle", "()V", &[])?;
+            native.ok_or(jni::errors::Error::NullPtr("AMotionEvent_fromJava"))
+        })
+    }
+
     /// Returns a pointer to the native [`ffi::AInputEvent`].
     #[inline]
     pub fn ptr(&self) -> NonNull<ffi::AInputEvent> {
@@ -912,18 +1076,89 @@ impl MotionEvent {
     /// is undefined.
     #[cfg(feature = "api-level-33")]
     #[doc(alias = "AMotionEvent_getActionButton")]
-    pub fn action_button(&self) -> ButtonState {
-        ButtonState(unsafe { ffi::AMotionEvent_getActionButton(self.ptr.as_ptr()) } as u32)
+    pub fn action_button(&self) -> Result<ButtonState, Unsupported> {
+        Ok(ButtonState(
+            unsafe { AMotionEvent_getActionButton(self.ptr.as_ptr())? } as u32,
+        ))
     }
 
     /// Returns the classification for the current gesture. The classification may change as more
     /// events become available for the same gesture.
     #[cfg(feature = "api-level-33")]
     #[doc(alias = "AMotionEvent_getClassification")]
-    pub fn classification(&self) -> MotionClassification {
-        u32::try_from(unsafe { ffi::AMotionEvent_getClassification(self.ptr.as_ptr()) })
-            .unwrap()
-            .into()
+    pub fn classification(&self) -> Result<MotionClassification, Unsupported> {
+        Ok(
+            u32::try_from(unsafe { AMotionEvent_getClassification(self.ptr.as_ptr())? })
+                .unwrap()
+                .into(),
+        )
+    }
+
+    /// Captures all pointers, axis values, historical samples and state of this event into an
+    /// [`OwnedMotionEvent`], which stays valid after the event is finished with
+    /// [`InputQueue::finish_event()`][crate::input_queue::InputQueue::finish_event()].
+    ///
+    /// [`OwnedMotionEvent::action_button`] and [`OwnedMotionEvent::classification`] are only
+    /// captured with `api-level-33`, and on devices that support them with the `weak-linking`
+    /// feature.
+    pub fn to_owned(&self) -> OwnedMotionEvent {
+        let event = self.ptr.as_ptr();
+        let pointers = self
+            .pointers()
+            .map(|pointer| OwnedPointer {
+                id: pointer.pointer_id(),
+                tool_type: pointer.tool_type(),
+                coords: PointerCoords::capture(pointer.raw_x(), pointer.raw_y(), |axis| unsafe {
+                    ffi::AMotionEvent_getAxisValue(event, axis, pointer.pointer_index())
+                }),
+            })
+            .collect();
+        let history = self
+            .history()
+            .map(|sample| HistoricalSample {
+                event_time: sample.event_time(),
+                pointers: sample
+                    .pointers()
+                    .map(|pointer| {
+                        PointerCoords::capture(pointer.raw_x(), pointer.raw_y(), |axis| unsafe {
+                            ffi::AMotionEvent_getHistoricalAxisValue(
+                                event,
+                                axis,
+                                pointer.pointer_index(),
+                                pointer.history_index(),
+                            )
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        OwnedMotionEvent {
+            device_id: self.device_id(),
+            source: self.source(),
+            action: self.action(),
+            pointer_index: self.pointer_index(),
+            pointers,
+            history,
+            meta_state: self.meta_state(),
+            button_state: self.button_state(),
+            #[cfg(feature = "api-level-33")]
+            action_button: self.action_button().unwrap_or(ButtonState(0)),
+            #[cfg(not(feature = "api-level-33"))]
+            action_button: ButtonState(0),
+            down_time: self.down_time(),
+            event_time: self.event_time(),
+            edge_flags: self.edge_flags(),
+            flags: self.flags(),
+            x_offset: self.x_offset(),
+            y_offset: self.y_offset(),
+            x_precision: self.x_precision(),
+            y_precision: self.y_precision(),
+            #[cfg(feature = "api-level-33")]
+            classification: self.classification().unwrap_or(MotionClassification::None),
+            #[cfg(not(feature = "api-level-33"))]
+            classification: MotionClassification::None,
+        }
     }
 }
 
@@ -1397,6 +1632,11 @@ pub struct KeyEvent {
 /// Key actions.
 /// See [the NDK docs](https://developer.android.com/ndk/reference/group/input#anonymous-enum-27)
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "i32", from = "i32")
+)]
 #[repr(i32)]
 #[non_exhaustive]
 pub enum KeyAction {
@@ -1411,6 +1651,11 @@ pub enum KeyAction {
 
 /// Key codes.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "i32", from = "i32")
+)]
 #[repr(i32)]
 #[non_exhaustive]
 pub enum Keycode {
@@ -2022,10 +2267,46 @@ impl KeyEvent {
     #[cfg(feature = "api-level-31")]
     #[doc(alias = "AKeyEvent_fromJava")]
     pub unsafe fn from_java(env: *mut JNIEnv, key_event: jobject) -> Option<KeyEventJava> {
-        let ptr = unsafe { ffi::AKeyEvent_fromJava(env, key_event) };
+        let ptr = unsafe { AKeyEvent_fromJava(env, key_event) }.ok()?;
         Some(KeyEventJava(Self::from_ptr(NonNull::new(ptr.cast_mut())?)))
     }
 
+    /// Synthesizes a native [`KeyEvent`] from `event` through the Java [`KeyEvent`
+    /// constructor], for example to feed handcrafted input into code that only accepts
+    /// [`KeyEvent`] in instrumented tests.
+    ///
+    /// Event times are truncated to the millisecond precision of the Java API.
+    ///
+    /// [`KeyEvent` constructor]: https://developer.android.com/reference/android/view/KeyEvent#KeyEvent(long,%20long,%20int,%20int,%20int,%20int,%20int,%20int,%20int,%20int)
+    #[cfg(all(feature = "api-level-31", feature = "jni"))]
+    pub fn obtain(
+        env: &mut jni::JNIEnv<'_>,
+        event: &OwnedKeyEvent,
+    ) -> jni::errors::Result<KeyEventJava> {
+        use jni::objects::JValue;
+
+        env.with_local_frame(4, |env| {
+            let object = env.new_object(
+                "android/view/KeyEvent",
+                "(JJIIIIIIII)V",
+                &[
+                    JValue::Long(event.down_time / 1_000_000),
+                    JValue::Long(event.event_time / 1_000_000),
+                    JValue::Int(event.action.into()),
+                    JValue::Int(event.key_code.into()),
+                    JValue::Int(event.repeat_count),
+                    JValue::Int(event.meta_state.0 as i32),
+                    JValue::Int(event.device_id),
+                    JValue::Int(event.scan_code),
+                    JValue::Int(event.flags.0 as i32),
+                    JValue::Int(event.source.into()),
+                ],
+            )?;
+            unsafe { Self::from_java(env.get_raw(), object.as_raw()) }
+                .ok_or(jni::errors::Error::NullPtr("AKeyEvent_fromJava"))
+        })
+    }
+
     /// Returns a pointer to the native [`ffi::AInputEvent`].
     #[inline]
     pub fn ptr(&self) -> NonNull<ffi::AInputEvent> {
@@ -2135,10 +2416,29 @@ impl KeyEvent {
     pub fn meta_state(&self) -> MetaState {
         unsafe { MetaState(ffi::AKeyEvent_getMetaState(self.ptr.as_ptr()) as u32) }
     }
+
+    /// Captures the state of this event into an [`OwnedKeyEvent`], which stays valid after the
+    /// event is finished with
+    /// [`InputQueue::finish_event()`][crate::input_queue::InputQueue::finish_event()].
+    pub fn to_owned(&self) -> OwnedKeyEvent {
+        OwnedKeyEvent {
+            device_id: self.device_id(),
+            source: self.source(),
+            action: self.action(),
+            key_code: self.key_code(),
+            scan_code: self.scan_code(),
+            repeat_count: self.repeat_count(),
+            meta_state: self.meta_state(),
+            flags: self.flags(),
+            down_time: self.down_time(),
+            event_time: self.event_time(),
+        }
+    }
 }
 
 /// Flags associated with [`KeyEvent`].
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyEventFlags(pub u32);
 
 impl KeyEventFlags {
@@ -2198,3 +2498,701 @@ impl KeyEventFlags {
         self.0 & ffi::AKEY_EVENT_FLAG_FALLBACK != 0
     }
 }
+
+/// An owned copy of an [`InputEvent`], created by [`InputEvent::to_owned()`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum OwnedInputEvent {
+    MotionEvent(OwnedMotionEvent),
+    KeyEvent(OwnedKeyEvent),
+}
+
+/// An owned snapshot of a [`MotionEvent`], created by [`MotionEvent::to_owned()`].
+///
+/// Unlike [`MotionEvent`] this is plain data: it can be kept around after the native event is
+/// finished, sent to other threads, and constructed by hand to exercise input handling logic
+/// outside of Android:
+///
+/// ```
+/// # use ndk::event::{MotionAction, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+/// let coords = PointerCoords::new(10.0, 20.0);
+/// let event = OwnedMotionEvent {
+///     action: MotionAction::Down,
+///     pointers: vec![OwnedPointer::new(0, ToolType::Finger, coords)],
+///     ..Default::default()
+/// };
+/// assert_eq!(event.pointer(0).unwrap().coords.x(), 10.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedMotionEvent {
+    /// See [`MotionEvent::device_id()`].
+    pub device_id: i32,
+    /// See [`MotionEvent::source()`].
+    pub source: Source,
+    /// See [`MotionEvent::action()`].
+    pub action: MotionAction,
+    /// See [`MotionEvent::pointer_index()`].
+    pub pointer_index: usize,
+    /// The current sample of all pointers, in pointer index order.
+    pub pointers: Vec<OwnedPointer>,
+    /// Samples of all pointers batched into this event, from oldest to newest.
+    pub history: Vec<HistoricalSample>,
+    /// See [`MotionEvent::meta_state()`].
+    pub meta_state: MetaState,
+    /// See [`MotionEvent::button_state()`].
+    pub button_state: ButtonState,
+    /// See [`MotionEvent::action_button()`].
+    pub action_button: ButtonState,
+    /// See [`MotionEvent::down_time()`].
+    pub down_time: i64,
+    /// See [`MotionEvent::event_time()`].
+    pub event_time: i64,
+    /// See [`MotionEvent::edge_flags()`].
+    pub edge_flags: EdgeFlags,
+    /// See [`MotionEvent::flags()`].
+    pub flags: MotionEventFlags,
+    /// See [`MotionEvent::x_offset()`].
+    pub x_offset: f32,
+    /// See [`MotionEvent::y_offset()`].
+    pub y_offset: f32,
+    /// See [`MotionEvent::x_precision()`].
+    pub x_precision: f32,
+    /// See [`MotionEvent::y_precision()`].
+    pub y_precision: f32,
+    /// See [`MotionEvent::classification()`].
+    pub classification: MotionClassification,
+}
+
+impl Default for OwnedMotionEvent {
+    fn default() -> Self {
+        Self {
+            device_id: 0,
+            source: Source::Unknown,
+            action: MotionAction::Move,
+            pointer_index: 0,
+            pointers: Vec::new(),
+            history: Vec::new(),
+            meta_state: MetaState(0),
+            button_state: ButtonState(0),
+            action_button: ButtonState(0),
+            down_time: 0,
+            event_time: 0,
+            edge_flags: EdgeFlags(0),
+            flags: MotionEventFlags(0),
+            x_offset: 0.0,
+            y_offset: 0.0,
+            x_precision: 1.0,
+            y_precision: 1.0,
+            classification: MotionClassification::None,
+        }
+    }
+}
+
+impl OwnedMotionEvent {
+    /// Returns the pointer with the given [`OwnedPointer::id`].
+    pub fn pointer(&self, id: i32) -> Option<&OwnedPointer> {
+        self.pointers.iter().find(|pointer| pointer.id == id)
+    }
+
+    /// Returns the pointer that [`OwnedMotionEvent::action`] applies to, for
+    /// [`MotionAction::PointerDown`] and [`MotionAction::PointerUp`].
+    pub fn action_pointer(&self) -> Option<&OwnedPointer> {
+        self.pointers.get(self.pointer_index)
+    }
+}
+
+/// A pointer in an [`OwnedMotionEvent`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedPointer {
+    /// See [`Pointer::pointer_id()`].
+    pub id: i32,
+    /// See [`Pointer::tool_type()`].
+    pub tool_type: ToolType,
+    /// The current sample of this pointer.
+    pub coords: PointerCoords,
+}
+
+impl Default for OwnedPointer {
+    fn default() -> Self {
+        Self::new(0, ToolType::Finger, PointerCoords::default())
+    }
+}
+
+impl OwnedPointer {
+    /// Creates a pointer with the given [`id`][Self::id], [`tool_type`][Self::tool_type] and
+    /// [`coords`][Self::coords].
+    pub fn new(id: i32, tool_type: ToolType, coords: PointerCoords) -> Self {
+        Self {
+            id,
+            tool_type,
+            coords,
+        }
+    }
+}
+
+/// A historical sample of all pointers in an [`OwnedMotionEvent`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoricalSample {
+    /// See [`HistoricalMotionEvent::event_time()`].
+    pub event_time: i64,
+    /// The sample of every pointer, in the same order as [`OwnedMotionEvent::pointers`].
+    pub pointers: Vec<PointerCoords>,
+}
+
+/// The values of all axes of a pointer at one moment, as in the Java
+/// [`MotionEvent.PointerCoords`].
+///
+/// Only non-zero axis values are stored; all other axes read as `0.0`.
+///
+/// [`MotionEvent.PointerCoords`]: https://developer.android.com/reference/android/view/MotionEvent.PointerCoords
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerCoords {
+    /// See [`Pointer::raw_x()`].
+    pub raw_x: f32,
+    /// See [`Pointer::raw_y()`].
+    pub raw_y: f32,
+    /// The non-zero axis values, in ascending axis order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "PointerCoords::deserialize_axes")
+    )]
+    axes: Vec<(Axis, f32)>,
+}
+
+impl PointerCoords {
+    /// Creates coordinates at `x` and `y`, with [`raw_x`][Self::raw_x] and
+    /// [`raw_y`][Self::raw_y] at the same position as for an event without an offset, and all
+    /// other axes at `0.0`.
+    pub fn new(x: f32, y: f32) -> Self {
+        let mut coords = Self {
+            raw_x: x,
+            raw_y: y,
+            axes: Vec::new(),
+        };
+        coords.set_axis_value(Axis::X, x);
+        coords.set_axis_value(Axis::Y, y);
+        coords
+    }
+
+    /// Restores the ascending axis order that [`PointerCoords::set_axis_value()`] relies on,
+    /// whatever order the serialized axes are in.
+    #[cfg(feature = "serde")]
+    fn deserialize_axes<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(Axis, f32)>, D::Error> {
+        let axes: Vec<(Axis, f32)> = serde::Deserialize::deserialize(deserializer)?;
+        let mut coords = Self::default();
+        for (axis, value) in axes {
+            coords.set_axis_value(axis, value);
+        }
+        Ok(coords.axes)
+    }
+
+    /// Reads all axes, whose values are bits in a 64-bit mask in the native implementation.
+    fn capture(raw_x: f32, raw_y: f32, axis_value: impl Fn(i32) -> f32) -> Self {
+        Self {
+            raw_x,
+            raw_y,
+            axes: (0..64)
+                .map(|axis| (Axis::from(axis), axis_value(axis)))
+                .filter(|&(_, value)| value != 0.0)
+                .collect(),
+        }
+    }
+
+    /// Creates a Java `MotionEvent.PointerCoords[]` holding all axis values of `coords`.
+    #[cfg(all(feature = "api-level-31", feature = "jni"))]
+    fn to_java_array<'local>(
+        env: &mut jni::JNIEnv<'local>,
+        coords: &[&Self],
+    ) -> jni::errors::Result<jni::objects::JObjectArray<'local>> {
+        const COORDS_CLASS: &str = "android/view/MotionEvent$PointerCoords";
+
+        let array = env.new_object_array(
+            coords.len() as i32,
+            COORDS_CLASS,
+            jni::objects::JObject::null(),
+        )?;
+        for (index, coords) in coords.iter().enumerate() {
+            let object = env.new_object(COORDS_CLASS, "()V", &[])?;
+            for &(axis, value) in coords.axes() {
+                env.call_method(
+                    &object,
+                    "setAxisValue",
+                    "(IF)V",
+                    &[i32::from(axis).into(), value.into()],
+                )?;
+            }
+            env.set_object_array_element(&array, index as i32, &object)?;
+            env.delete_local_ref(object)?;
+        }
+        Ok(array)
+    }
+
+    /// The non-zero axis values, in ascending axis order.
+    pub fn axes(&self) -> &[(Axis, f32)] {
+        &self.axes
+    }
+
+    /// See [`Pointer::axis_value()`].
+    pub fn axis_value(&self, axis: Axis) -> f32 {
+        self.axes
+            .binary_search_by_key(&i32::from(axis), |&(a, _)| a.into())
+            .map_or(0.0, |index| self.axes[index].1)
+    }
+
+    /// Sets the value of `axis`, removing it when `value` is `0.0`.
+    pub fn set_axis_value(&mut self, axis: Axis, value: f32) {
+        let key = i32::from(axis);
+        match self.axes.binary_search_by_key(&key, |&(a, _)| a.into()) {
+            Ok(index) if value == 0.0 => {
+                self.axes.remove(index);
+            }
+            Ok(index) => self.axes[index].1 = value,
+            Err(_) if value == 0.0 => {}
+            Err(index) => self.axes.insert(index, (axis, value)),
+        }
+    }
+
+    /// See [`Pointer::x()`].
+    pub fn x(&self) -> f32 {
+        self.axis_value(Axis::X)
+    }
+
+    /// See [`Pointer::y()`].
+    pub fn y(&self) -> f32 {
+        self.axis_value(Axis::Y)
+    }
+
+    /// See [`Pointer::pressure()`].
+    pub fn pressure(&self) -> f32 {
+        self.axis_value(Axis::Pressure)
+    }
+
+    /// See [`Pointer::size()`].
+    pub fn size(&self) -> f32 {
+        self.axis_value(Axis::Size)
+    }
+
+    /// See [`Pointer::orientation()`].
+    pub fn orientation(&self) -> f32 {
+        self.axis_value(Axis::Orientation)
+    }
+
+    /// See [`Pointer::touch_major()`].
+    pub fn touch_major(&self) -> f32 {
+        self.axis_value(Axis::TouchMajor)
+    }
+
+    /// See [`Pointer::touch_minor()`].
+    pub fn touch_minor(&self) -> f32 {
+        self.axis_value(Axis::TouchMinor)
+    }
+
+    /// See [`Pointer::tool_major()`].
+    pub fn tool_major(&self) -> f32 {
+        self.axis_value(Axis::ToolMajor)
+    }
+
+    /// See [`Pointer::tool_minor()`].
+    pub fn tool_minor(&self) -> f32 {
+        self.axis_value(Axis::ToolMinor)
+    }
+}
+
+/// An owned snapshot of a [`KeyEvent`], created by [`KeyEvent::to_owned()`].
+///
+/// Like [`OwnedMotionEvent`] this is plain data that outlives the native event and can be
+/// constructed by hand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedKeyEvent {
+    /// See [`KeyEvent::device_id()`].
+    pub device_id: i32,
+    /// See [`KeyEvent::source()`].
+    pub source: Source,
+    /// See [`KeyEvent::action()`].
+    pub action: KeyAction,
+    /// See [`KeyEvent::key_code()`].
+    pub key_code: Keycode,
+    /// See [`KeyEvent::scan_code()`].
+    pub scan_code: i32,
+    /// See [`KeyEvent::repeat_count()`].
+    pub repeat_count: i32,
+    /// See [`KeyEvent::meta_state()`].
+    pub meta_state: MetaState,
+    /// See [`KeyEvent::flags()`].
+    pub flags: KeyEventFlags,
+    /// See [`KeyEvent::down_time()`].
+    pub down_time: i64,
+    /// See [`KeyEvent::event_time()`].
+    pub event_time: i64,
+}
+
+impl Default for OwnedKeyEvent {
+    fn default() -> Self {
+        Self {
+            device_id: 0,
+            source: Source::Keyboard,
+            action: KeyAction::Down,
+            key_code: Keycode::Unknown,
+            scan_code: 0,
+            repeat_count: 0,
+            meta_state: MetaState(0),
+            flags: KeyEventFlags(0),
+            down_time: 0,
+            event_time: 0,
+        }
+    }
+}
+
+/// Read access to the data of a motion event, implemented by the native [`MotionEvent`] and by
+/// the pure-Rust [`OwnedMotionEvent`].
+///
+/// Input handling code that is generic over this trait runs on real events on Android and on
+/// handcrafted [`OwnedMotionEvent`]s in host tests:
+///
+/// ```
+/// # use ndk::event::{MotionAction, MotionEventSource, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+/// fn centroid(event: &impl MotionEventSource) -> (f32, f32) {
+///     let count = event.pointer_count() as f32;
+///     let (x, y) = (0..event.pointer_count())
+///         .map(|index| (event.x(index), event.y(index)))
+///         .fold((0.0, 0.0), |(ax, ay), (x, y)| (ax + x, ay + y));
+///     (x / count, y / count)
+/// }
+///
+/// let pointer = |id, x, y| OwnedPointer::new(id, ToolType::Finger, PointerCoords::new(x, y));
+/// let event = OwnedMotionEvent {
+///     action: MotionAction::PointerDown,
+///     pointer_index: 1,
+///     pointers: vec![pointer(0, 10.0, 10.0), pointer(1, 30.0, 50.0)],
+///     ..Default::default()
+/// };
+/// assert_eq!(centroid(&event), (20.0, 30.0));
+/// ```
+pub trait MotionEventSource {
+    /// See [`MotionEvent::device_id()`].
+    fn device_id(&self) -> i32;
+    /// See [`MotionEvent::source()`].
+    fn source(&self) -> Source;
+    /// See [`MotionEvent::action()`].
+    fn action(&self) -> MotionAction;
+    /// See [`MotionEvent::pointer_index()`].
+    fn pointer_index(&self) -> usize;
+    /// See [`MotionEvent::pointer_count()`].
+    fn pointer_count(&self) -> usize;
+    /// See [`Pointer::pointer_id()`].
+    fn pointer_id(&self, pointer_index: usize) -> i32;
+    /// See [`Pointer::tool_type()`].
+    fn tool_type(&self, pointer_index: usize) -> ToolType;
+    /// See [`Pointer::axis_value()`].
+    fn axis_value(&self, pointer_index: usize, axis: Axis) -> f32;
+    /// See [`MotionEvent::history_size()`].
+    fn history_size(&self) -> usize;
+    /// See [`HistoricalMotionEvent::event_time()`]. Panics if the history index is out of bounds.
+    fn historical_event_time(&self, history_index: usize) -> i64;
+    /// See [`HistoricalPointer::axis_value()`]. Panics if the pointer index or the history index
+    /// is out of bounds.
+    fn historical_axis_value(&self, pointer_index: usize, history_index: usize, axis: Axis) -> f32;
+    /// See [`MotionEvent::meta_state()`].
+    fn meta_state(&self) -> MetaState;
+    /// See [`MotionEvent::button_state()`].
+    fn button_state(&self) -> ButtonState;
+    /// See [`MotionEvent::down_time()`].
+    fn down_time(&self) -> i64;
+    /// See [`MotionEvent::event_time()`].
+    fn event_time(&self) -> i64;
+    /// See [`MotionEvent::flags()`].
+    fn flags(&self) -> MotionEventFlags;
+
+    /// See [`Pointer::x()`].
+    fn x(&self, pointer_index: usize) -> f32 {
+        self.axis_value(pointer_index, Axis::X)
+    }
+
+    /// See [`Pointer::y()`].
+    fn y(&self, pointer_index: usize) -> f32 {
+        self.axis_value(pointer_index, Axis::Y)
+    }
+
+    /// See [`Pointer::pressure()`].
+    fn pressure(&self, pointer_index: usize) -> f32 {
+        self.axis_value(pointer_index, Axis::Pressure)
+    }
+
+    /// Returns the index of the pointer with the given id, if it is part of this event.
+    fn find_pointer_index(&self, pointer_id: i32) -> Option<usize> {
+        (0..self.pointer_count()).find(|&index| self.pointer_id(index) == pointer_id)
+    }
+}
+
+impl MotionEventSource for MotionEvent {
+    fn device_id(&self) -> i32 {
+        MotionEvent::device_id(self)
+    }
+
+    fn source(&self) -> Source {
+        MotionEvent::source(self)
+    }
+
+    fn action(&self) -> MotionAction {
+        MotionEvent::action(self)
+    }
+
+    fn pointer_index(&self) -> usize {
+        MotionEvent::pointer_index(self)
+    }
+
+    fn pointer_count(&self) -> usize {
+        MotionEvent::pointer_count(self)
+    }
+
+    fn pointer_id(&self, pointer_index: usize) -> i32 {
+        self.pointer_at_index(pointer_index).pointer_id()
+    }
+
+    fn tool_type(&self, pointer_index: usize) -> ToolType {
+        self.pointer_at_index(pointer_index).tool_type()
+    }
+
+    fn axis_value(&self, pointer_index: usize, axis: Axis) -> f32 {
+        self.pointer_at_index(pointer_index).axis_value(axis)
+    }
+
+    fn history_size(&self) -> usize {
+        MotionEvent::history_size(self)
+    }
+
+    fn historical_event_time(&self, history_index: usize) -> i64 {
+        assert!(
+            history_index < self.history_size(),
+            "History index {} is out of bounds",
+            history_index
+        );
+        unsafe { ffi::AMotionEvent_getHistoricalEventTime(self.ptr.as_ptr(), history_index) }
+    }
+
+    fn historical_axis_value(&self, pointer_index: usize, history_index: usize, axis: Axis) -> f32 {
+        assert!(
+            pointer_index < self.pointer_count(),
+            "Pointer index {} is out of bounds",
+            pointer_index
+        );
+        assert!(
+            history_index < self.history_size(),
+            "History index {} is out of bounds",
+            history_index
+        );
+        unsafe {
+            ffi::AMotionEvent_getHistoricalAxisValue(
+                self.ptr.as_ptr(),
+                axis.into(),
+                pointer_index,
+                history_index,
+            )
+        }
+    }
+
+    fn meta_state(&self) -> MetaState {
+        MotionEvent::meta_state(self)
+    }
+
+    fn button_state(&self) -> ButtonState {
+        MotionEvent::button_state(self)
+    }
+
+    fn down_time(&self) -> i64 {
+        MotionEvent::down_time(self)
+    }
+
+    fn event_time(&self) -> i64 {
+        MotionEvent::event_time(self)
+    }
+
+    fn flags(&self) -> MotionEventFlags {
+        MotionEvent::flags(self)
+    }
+}
+
+impl MotionEventSource for OwnedMotionEvent {
+    fn device_id(&self) -> i32 {
+        self.device_id
+    }
+
+    fn source(&self) -> Source {
+        self.source
+    }
+
+    fn action(&self) -> MotionAction {
+        self.action
+    }
+
+    fn pointer_index(&self) -> usize {
+        self.pointer_index
+    }
+
+    fn pointer_count(&self) -> usize {
+        self.pointers.len()
+    }
+
+    fn pointer_id(&self, pointer_index: usize) -> i32 {
+        self.pointers[pointer_index].id
+    }
+
+    fn tool_type(&self, pointer_index: usize) -> ToolType {
+        self.pointers[pointer_index].tool_type
+    }
+
+    fn axis_value(&self, pointer_index: usize, axis: Axis) -> f32 {
+        self.pointers[pointer_index].coords.axis_value(axis)
+    }
+
+    fn history_size(&self) -> usize {
+        self.history.len()
+    }
+
+    fn historical_event_time(&self, history_index: usize) -> i64 {
+        self.history[history_index].event_time
+    }
+
+    fn historical_axis_value(&self, pointer_index: usize, history_index: usize, axis: Axis) -> f32 {
+        self.history[history_index].pointers[pointer_index].axis_value(axis)
+    }
+
+    fn meta_state(&self) -> MetaState {
+        self.meta_state
+    }
+
+    fn button_state(&self) -> ButtonState {
+        self.button_state
+    }
+
+    fn down_time(&self) -> i64 {
+        self.down_time
+    }
+
+    fn event_time(&self) -> i64 {
+        self.event_time
+    }
+
+    fn flags(&self) -> MotionEventFlags {
+        self.flags
+    }
+}
+
+/// Read access to the data of a key event, implemented by the native [`KeyEvent`] and by the
+/// pure-Rust [`OwnedKeyEvent`].
+///
+/// See [`MotionEventSource`] for writing input handling code that is testable off-device.
+pub trait KeyEventSource {
+    /// See [`KeyEvent::device_id()`].
+    fn device_id(&self) -> i32;
+    /// See [`KeyEvent::source()`].
+    fn source(&self) -> Source;
+    /// See [`KeyEvent::action()`].
+    fn action(&self) -> KeyAction;
+    /// See [`KeyEvent::key_code()`].
+    fn key_code(&self) -> Keycode;
+    /// See [`KeyEvent::scan_code()`].
+    fn scan_code(&self) -> i32;
+    /// See [`KeyEvent::repeat_count()`].
+    fn repeat_count(&self) -> i32;
+    /// See [`KeyEvent::meta_state()`].
+    fn meta_state(&self) -> MetaState;
+    /// See [`KeyEvent::flags()`].
+    fn flags(&self) -> KeyEventFlags;
+    /// See [`KeyEvent::down_time()`].
+    fn down_time(&self) -> i64;
+    /// See [`KeyEvent::event_time()`].
+    fn event_time(&self) -> i64;
+}
+
+impl KeyEventSource for KeyEvent {
+    fn device_id(&self) -> i32 {
+        KeyEvent::device_id(self)
+    }
+
+    fn source(&self) -> Source {
+        KeyEvent::source(self)
+    }
+
+    fn action(&self) -> KeyAction {
+        KeyEvent::action(self)
+    }
+
+    fn key_code(&self) -> Keycode {
+        KeyEvent::key_code(self)
+    }
+
+    fn scan_code(&self) -> i32 {
+        KeyEvent::scan_code(self)
+    }
+
+    fn repeat_count(&self) -> i32 {
+        KeyEvent::repeat_count(self)
+    }
+
+    fn meta_state(&self) -> MetaState {
+        KeyEvent::meta_state(self)
+    }
+
+    fn flags(&self) -> KeyEventFlags {
+        KeyEvent::flags(self)
+    }
+
+    fn down_time(&self) -> i64 {
+        KeyEvent::down_time(self)
+    }
+
+    fn event_time(&self) -> i64 {
+        KeyEvent::event_time(self)
+    }
+}
+
+impl KeyEventSource for OwnedKeyEvent {
+    fn device_id(&self) -> i32 {
+        self.device_id
+    }
+
+    fn source(&self) -> Source {
+        self.source
+    }
+
+    fn action(&self) -> KeyAction {
+        self.action
+    }
+
+    fn key_code(&self) -> Keycode {
+        self.key_code
+    }
+
+    fn scan_code(&self) -> i32 {
+        self.scan_code
+    }
+
+    fn repeat_count(&self) -> i32 {
+        self.repeat_count
+    }
+
+    fn meta_state(&self) -> MetaState {
+        self.meta_state
+    }
+
+    fn flags(&self) -> KeyEventFlags {
+        self.flags
+    }
+
+    fn down_time(&self) -> i64 {
+        self.down_time
+    }
+
+    fn event_time(&self) -> i64 {
+        self.event_time
+    }
+}
@@ -0,0 +1,431 @@
+//! Unified game controller state from [`Source::Gamepad`] key events and [`Source::Joystick`]
+//! motion events
+//!
+//! Android reports controller buttons as [`KeyEvent`]s with [`Keycode::ButtonA`] and friends, and
+//! sticks, triggers and hats as [`Axis`] values of [`MotionEvent`]s, with different controllers
+//! using different axes for the same control. [`Gamepads`] folds both into one [`GamepadState`]
+//! per device:
+//!
+//! ```
+//! # use ndk::event::{Keycode, KeyAction, OwnedInputEvent, OwnedKeyEvent, Source};
+//! # use ndk::event::gamepad::{GamepadButtons, GamepadEvent, Gamepads};
+//! let mut gamepads = Gamepads::default();
+//! let events = gamepads.on_owned_event(&OwnedInputEvent::KeyEvent(OwnedKeyEvent {
+//!     device_id: 7,
+//!     source: Source::Gamepad,
+//!     action: KeyAction::Down,
+//!     key_code: Keycode::ButtonA,
+//!     ..Default::default()
+//! }));
+//! assert_eq!(
+//!     events,
+//!     [
+//!         GamepadEvent::Connected { device_id: 7 },
+//!         GamepadEvent::ButtonPressed { device_id: 7, button: GamepadButtons::A },
+//!     ]
+//! );
+//! assert!(gamepads.get(7).unwrap().buttons.contains(GamepadButtons::A));
+//! ```
+//!
+//! [`KeyEvent`]: super::KeyEvent
+//! [`MotionEvent`]: super::MotionEvent
+
+use super::{
+    Axis, InputEvent, KeyAction, Keycode, MotionAction, OwnedInputEvent, OwnedKeyEvent,
+    OwnedMotionEvent, Source,
+};
+
+bitflags::bitflags! {
+    /// The buttons of a [`GamepadState`], including the D-pad.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct GamepadButtons : u32 {
+        const A = 1 << 0;
+        const B = 1 << 1;
+        const X = 1 << 2;
+        const Y = 1 << 3;
+        const L1 = 1 << 4;
+        const R1 = 1 << 5;
+        const L2 = 1 << 6;
+        const R2 = 1 << 7;
+        /// The left stick is pressed.
+        const THUMB_L = 1 << 8;
+        /// The right stick is pressed.
+        const THUMB_R = 1 << 9;
+        const START = 1 << 10;
+        const SELECT = 1 << 11;
+        /// The home or guide button.
+        const MODE = 1 << 12;
+        const DPAD_UP = 1 << 13;
+        const DPAD_DOWN = 1 << 14;
+        const DPAD_LEFT = 1 << 15;
+        const DPAD_RIGHT = 1 << 16;
+    }
+}
+
+impl GamepadButtons {
+    /// Returns the button of `key_code`, or an empty set for keys that are not part of a gamepad.
+    ///
+    /// Some controllers report their select button as [`Keycode::Back`], which is only mapped to
+    /// [`GamepadButtons::SELECT`] when the event comes from a [`Source::Gamepad`], see
+    /// [`GamepadButtons::from_key_event()`].
+    pub fn from_keycode(key_code: Keycode) -> Self {
+        match key_code {
+            Keycode::ButtonA => Self::A,
+            Keycode::ButtonB => Self::B,
+            Keycode::ButtonX => Self::X,
+            Keycode::ButtonY => Self::Y,
+            Keycode::ButtonL1 => Self::L1,
+            Keycode::ButtonR1 => Self::R1,
+            Keycode::ButtonL2 => Self::L2,
+            Keycode::ButtonR2 => Self::R2,
+            Keycode::ButtonThumbl => Self::THUMB_L,
+            Keycode::ButtonThumbr => Self::THUMB_R,
+            Keycode::ButtonStart => Self::START,
+            Keycode::ButtonSelect => Self::SELECT,
+            Keycode::ButtonMode => Self::MODE,
+            Keycode::DpadUp => Self::DPAD_UP,
+            Keycode::DpadDown => Self::DPAD_DOWN,
+            Keycode::DpadLeft => Self::DPAD_LEFT,
+            Keycode::DpadRight => Self::DPAD_RIGHT,
+            _ => Self::empty(),
+        }
+    }
+
+    /// Like [`GamepadButtons::from_keycode()`], additionally mapping [`Keycode::Back`] to
+    /// [`GamepadButtons::SELECT`] for events from a [`Source::Gamepad`]. The back key of a phone
+    /// or remote control is not a gamepad button.
+    ///
+    /// ```
+    /// # use ndk::event::{Keycode, Source};
+    /// # use ndk::event::gamepad::GamepadButtons;
+    /// let select = GamepadButtons::from_key_event(Keycode::Back, Source::Gamepad);
+    /// assert_eq!(select, GamepadButtons::SELECT);
+    /// assert!(GamepadButtons::from_key_event(Keycode::Back, Source::Keyboard).is_empty());
+    /// ```
+    pub fn from_key_event(key_code: Keycode, source: Source) -> Self {
+        match key_code {
+            Keycode::Back if has_source(source, Source::Gamepad) => Self::SELECT,
+            key_code => Self::from_keycode(key_code),
+        }
+    }
+}
+
+/// How the axes of a controller are interpreted by [`Gamepads`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GamepadConfig {
+    /// The radius around the center within which a stick reads as centered. Values outside of it
+    /// are rescaled to still span `0.0..=1.0`.
+    pub stick_dead_zone: f32,
+    /// The value below which a trigger reads as released.
+    pub trigger_dead_zone: f32,
+    /// The horizontal and vertical axis of the right stick. Most controllers use [`Axis::Z`] and
+    /// [`Axis::Rz`], some use [`Axis::Rx`] and [`Axis::Ry`].
+    pub right_stick_axes: (Axis, Axis),
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            stick_dead_zone: 0.1,
+            trigger_dead_zone: 0.05,
+            right_stick_axes: (Axis::Z, Axis::Rz),
+        }
+    }
+}
+
+impl GamepadConfig {
+    /// Derives the configuration from the [motion ranges] of `device`: the right stick axes
+    /// that the device reports, and the largest flat region of its sticks as dead zone.
+    ///
+    /// [motion ranges]: super::input_device::InputDevice::motion_ranges
+    #[cfg(feature = "jni")]
+    pub fn from_device(device: &super::input_device::InputDevice) -> Self {
+        let mut config = Self::default();
+        let has_axis = |axis| device.motion_range(axis, None).is_some();
+        if !has_axis(Axis::Z) && has_axis(Axis::Rx) && has_axis(Axis::Ry) {
+            config.right_stick_axes = (Axis::Rx, Axis::Ry);
+        }
+        let (right_x, right_y) = config.right_stick_axes;
+        let flat = [Axis::X, Axis::Y, right_x, right_y]
+            .into_iter()
+            .filter_map(|axis| device.motion_range(axis, None))
+            .map(|range| range.flat)
+            .fold(0.0, f32::max);
+        if flat > 0.0 {
+            config.stick_dead_zone = flat;
+        }
+        config
+    }
+}
+
+/// The state of one controller, as maintained by [`Gamepads`].
+///
+/// Sticks range from `-1.0` to `1.0` with positive values pointing right and down, triggers from
+/// `0.0` to `1.0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GamepadState {
+    pub device_id: i32,
+    /// All pressed buttons, including the D-pad directions of both D-pad keys and hats.
+    pub buttons: GamepadButtons,
+    pub left_stick: (f32, f32),
+    pub right_stick: (f32, f32),
+    pub left_trigger: f32,
+    pub right_trigger: f32,
+    /// The buttons that are pressed according to key events.
+    key_buttons: GamepadButtons,
+    /// The D-pad directions that are pressed according to hat axes.
+    hat_buttons: GamepadButtons,
+    /// The trigger values according to their axes, before key events are taken into account.
+    analog_triggers: (f32, f32),
+}
+
+impl GamepadState {
+    pub fn is_pressed(&self, button: GamepadButtons) -> bool {
+        self.buttons.contains(button)
+    }
+}
+
+/// A change reported by [`Gamepads`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GamepadEvent {
+    /// The first event of a new device was received.
+    Connected { device_id: i32 },
+    /// The device is no longer connected, see [`Gamepads::retain_devices()`].
+    Disconnected { device_id: i32 },
+    /// A single button was pressed.
+    ButtonPressed {
+        device_id: i32,
+        button: GamepadButtons,
+    },
+    /// A single button was released.
+    ButtonReleased {
+        device_id: i32,
+        button: GamepadButtons,
+    },
+}
+
+/// Tracks the [`GamepadState`] of all controllers that sent input events.
+#[derive(Clone, Debug, Default)]
+pub struct Gamepads {
+    config: GamepadConfig,
+    device_configs: Vec<(i32, GamepadConfig)>,
+    devices: Vec<(GamepadConfig, GamepadState)>,
+}
+
+/// Returns whether `source` contains all bits of `class`.
+fn has_source(source: Source, class: Source) -> bool {
+    let (source, class) = (i32::from(source), i32::from(class));
+    source & class == class
+}
+
+/// Applies a radial `dead_zone` to a stick, rescaling the remaining range to `0.0..=1.0`.
+fn stick(x: f32, y: f32, dead_zone: f32) -> (f32, f32) {
+    let magnitude = x.hypot(y);
+    if magnitude <= dead_zone || magnitude == 0.0 {
+        return (0.0, 0.0);
+    }
+    let scaled = ((magnitude - dead_zone) / (1.0 - dead_zone)).min(1.0);
+    (x / magnitude * scaled, y / magnitude * scaled)
+}
+
+fn trigger(value: f32, dead_zone: f32) -> f32 {
+    if value <= dead_zone {
+        0.0
+    } else {
+        ((value - dead_zone) / (1.0 - dead_zone)).min(1.0)
+    }
+}
+
+impl Gamepads {
+    /// Creates a tracker that uses `config` for all devices without a
+    /// [device-specific configuration][Gamepads::set_device_config()].
+    pub fn new(config: GamepadConfig) -> Self {
+        Self {
+            config,
+            device_configs: Vec::new(),
+            devices: Vec::new(),
+        }
+    }
+
+    /// Returns the state of the controller with the given device id.
+    pub fn get(&self, device_id: i32) -> Option<&GamepadState> {
+        self.devices
+            .iter()
+            .find(|(_, state)| state.device_id == device_id)
+            .map(|(_, state)| state)
+    }
+
+    /// Iterates over the states of all connected controllers.
+    pub fn iter(&self) -> impl Iterator<Item = &GamepadState> {
+        self.devices.iter().map(|(_, state)| state)
+    }
+
+    /// Overrides the configuration of a single device, such as one created with
+    /// `GamepadConfig::from_device()`. The device does not need to have sent any events yet.
+    pub fn set_device_config(&mut self, device_id: i32, config: GamepadConfig) {
+        match self
+            .device_configs
+            .iter_mut()
+            .find(|(id, _)| *id == device_id)
+        {
+            Some((_, device_config)) => *device_config = config,
+            None => self.device_configs.push((device_id, config)),
+        }
+        if let Some((device_config, _)) = self
+            .devices
+            .iter_mut()
+            .find(|(_, state)| state.device_id == device_id)
+        {
+            *device_config = config;
+        }
+    }
+
+    /// Forgets all devices whose id is not part of `connected`, as returned by
+    /// `InputDevice::device_ids()`, returning a [`GamepadEvent::Disconnected`] for each.
+    pub fn retain_devices(&mut self, connected: &[i32]) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+        self.devices.retain(|(_, state)| {
+            let keep = connected.contains(&state.device_id);
+            if !keep {
+                events.push(GamepadEvent::Disconnected {
+                    device_id: state.device_id,
+                });
+            }
+            keep
+        });
+        self.device_configs
+            .retain(|(device_id, _)| connected.contains(device_id));
+        events
+    }
+
+    fn device(
+        &mut self,
+        device_id: i32,
+        events: &mut Vec<GamepadEvent>,
+    ) -> &mut (GamepadConfig, GamepadState) {
+        let index = match self
+            .devices
+            .iter()
+            .position(|(_, state)| state.device_id == device_id)
+        {
+            Some(index) => index,
+            None => {
+                events.push(GamepadEvent::Connected { device_id });
+                let state = GamepadState {
+                    device_id,
+                    ..Default::default()
+                };
+                let config = self
+                    .device_configs
+                    .iter()
+                    .find(|(id, _)| *id == device_id)
+                    .map_or(self.config, |(_, config)| *config);
+                self.devices.push((config, state));
+                self.devices.len() - 1
+            }
+        };
+        &mut self.devices[index]
+    }
+
+    /// Updates the state with `event` if it comes from a gamepad or joystick source,
+    /// returning the resulting changes. Events from other sources are ignored.
+    ///
+    /// Only gamepad events are converted with [`InputEvent::to_owned()`].
+    pub fn on_input_event(&mut self, event: &InputEvent) -> Vec<GamepadEvent> {
+        if !Self::is_gamepad_source(event.source()) {
+            return Vec::new();
+        }
+        self.on_owned_event(&event.to_owned())
+    }
+
+    /// Returns whether events from `source` are handled by [`Gamepads`].
+    pub fn is_gamepad_source(source: Source) -> bool {
+        has_source(source, Source::Gamepad) || has_source(source, Source::Joystick)
+    }
+
+    /// Like [`Gamepads::on_input_event()`], for an [`OwnedInputEvent`].
+    pub fn on_owned_event(&mut self, event: &OwnedInputEvent) -> Vec<GamepadEvent> {
+        match event {
+            OwnedInputEvent::KeyEvent(event) if Self::is_gamepad_source(event.source) => {
+                self.on_key_event(event)
+            }
+            OwnedInputEvent::MotionEvent(event) if has_source(event.source, Source::Joystick) => {
+                self.on_motion_event(event)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn on_key_event(&mut self, event: &OwnedKeyEvent) -> Vec<GamepadEvent> {
+        let button = GamepadButtons::from_key_event(event.key_code, event.source);
+        let mut events = Vec::new();
+        if button.is_empty() {
+            return events;
+        }
+        let (_, state) = self.device(event.device_id, &mut events);
+        match event.action {
+            KeyAction::Down => state.key_buttons.insert(button),
+            KeyAction::Up => state.key_buttons.remove(button),
+            _ => return events,
+        }
+        Self::update(state, &mut events);
+        events
+    }
+
+    fn on_motion_event(&mut self, event: &OwnedMotionEvent) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+        if event.action != MotionAction::Move {
+            return events;
+        }
+        let Some(pointer) = event.pointers.first() else {
+            return events;
+        };
+        let (config, state) = self.device(event.device_id, &mut events);
+        let axis = |axis| pointer.coords.axis_value(axis);
+
+        state.left_stick = stick(axis(Axis::X), axis(Axis::Y), config.stick_dead_zone);
+        let (right_x, right_y) = config.right_stick_axes;
+        state.right_stick = stick(axis(right_x), axis(right_y), config.stick_dead_zone);
+        // Some controllers report their triggers as brake and gas pedals
+        state.analog_triggers = (
+            trigger(
+                axis(Axis::Ltrigger).max(axis(Axis::Brake)),
+                config.trigger_dead_zone,
+            ),
+            trigger(
+                axis(Axis::Rtrigger).max(axis(Axis::Gas)),
+                config.trigger_dead_zone,
+            ),
+        );
+
+        let (hat_x, hat_y) = (axis(Axis::HatX), axis(Axis::HatY));
+        let mut hat = GamepadButtons::empty();
+        hat.set(GamepadButtons::DPAD_LEFT, hat_x < -0.5);
+        hat.set(GamepadButtons::DPAD_RIGHT, hat_x > 0.5);
+        hat.set(GamepadButtons::DPAD_UP, hat_y < -0.5);
+        hat.set(GamepadButtons::DPAD_DOWN, hat_y > 0.5);
+        state.hat_buttons = hat;
+
+        Self::update(state, &mut events);
+        events
+    }
+
+    /// Recomputes the derived fields of `state`, reporting button changes.
+    fn update(state: &mut GamepadState, events: &mut Vec<GamepadEvent>) {
+        let buttons = state.key_buttons | state.hat_buttons;
+        let device_id = state.device_id;
+        for button in (buttons & !state.buttons).iter() {
+            events.push(GamepadEvent::ButtonPressed { device_id, button });
+        }
+        for button in (state.buttons & !buttons).iter() {
+            events.push(GamepadEvent::ButtonReleased { device_id, button });
+        }
+        state.buttons = buttons;
+
+        // Digital-only triggers read as fully pressed
+        let digital = |button| if buttons.contains(button) { 1.0 } else { 0.0 };
+        state.left_trigger = state.analog_triggers.0.max(digital(GamepadButtons::L2));
+        state.right_trigger = state.analog_triggers.1.max(digital(GamepadButtons::R2));
+    }
+}
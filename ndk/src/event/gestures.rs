@@ -0,0 +1,879 @@
+//! Gesture detection on top of [`MotionEventSource`] streams
+//!
+//! The detectors in this module mirror Android's [`GestureDetector`] and [`ScaleGestureDetector`]
+//! and add a [`RotationGestureDetector`] for two-finger rotation. They consume any
+//! [`MotionEventSource`]: native [`MotionEvent`]s straight from the input queue, or synthetic
+//! streams of [`OwnedMotionEvent`]s outside of Android:
+//!
+//! ```
+//! # use ndk::event::{MotionAction, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+//! # use ndk::event::gestures::{Gesture, GestureConfig, GestureDetector};
+//! let event = |action, time_ms: i64, x| {
+//!     let coords = PointerCoords::new(x, 100.0);
+//!     OwnedMotionEvent {
+//!         action,
+//!         event_time: time_ms * 1_000_000,
+//!         pointers: vec![OwnedPointer::new(0, ToolType::Finger, coords)],
+//!         ..Default::default()
+//!     }
+//! };
+//!
+//! let mut detector = GestureDetector::new(GestureConfig::default());
+//! detector.on_motion_event(&event(MotionAction::Down, 0, 100.0));
+//! detector.on_motion_event(&event(MotionAction::Up, 50, 100.0));
+//! // The tap is confirmed once no second tap followed within the double-tap timeout
+//! assert_eq!(detector.poll(400_000_000), vec![Gesture::SingleTapConfirmed { x: 100.0, y: 100.0 }]);
+//! ```
+//!
+//! Time-based gestures (long presses and confirmed single taps) have no event to trigger them;
+//! call [`GestureDetector::poll()`] when [`GestureDetector::next_deadline()`] passes, for example
+//! by timing out a [`ForeignLooper`] poll.
+//!
+//! [`GestureDetector`]: https://developer.android.com/reference/android/view/GestureDetector
+//! [`ScaleGestureDetector`]: https://developer.android.com/reference/android/view/ScaleGestureDetector
+//! [`MotionEvent`]: super::MotionEvent
+//! [`OwnedMotionEvent`]: super::OwnedMotionEvent
+//! [`ForeignLooper`]: crate::looper::ForeignLooper
+
+use std::{collections::VecDeque, f32::consts::PI, time::Duration};
+
+use super::{Axis, MotionAction, MotionEventSource};
+
+/// Distances, velocities and timeouts used by the detectors in this module, mirroring
+/// [`ViewConfiguration`].
+///
+/// Distances are in pixels, velocities in pixels per second. [`GestureConfig::default()`] uses
+/// the Android defaults for a density of 160 dpi; use [`GestureConfig::from_dpi()`] with
+/// [`Configuration::density()`] to scale them to the screen.
+///
+/// [`ViewConfiguration`]: https://developer.android.com/reference/android/view/ViewConfiguration
+/// [`Configuration::density()`]: crate::configuration::Configuration::density()
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GestureConfig {
+    /// The distance a touch can wander before it is considered a pan instead of a tap.
+    pub touch_slop: f32,
+    /// The maximum distance between the two taps of a double tap.
+    pub double_tap_slop: f32,
+    /// The minimum velocity of a fling.
+    pub min_fling_velocity: f32,
+    /// The maximum velocity of a fling, faster flings are clamped.
+    pub max_fling_velocity: f32,
+    /// The minimum span between pointers to start scaling.
+    pub min_scaling_span: f32,
+    /// The change in span before a pinch is considered a scale gesture.
+    pub span_slop: f32,
+    /// The angle in radians two pointers have to rotate before it is considered a rotation
+    /// gesture.
+    pub rotation_slop: f32,
+    /// The maximum time between the release of the first and the press of the second tap of a
+    /// double tap.
+    pub double_tap_timeout: Duration,
+    /// The minimum time between the release of the first and the press of the second tap of a
+    /// double tap.
+    pub double_tap_min_time: Duration,
+    /// The time a touch has to be held down to become a long press.
+    pub long_press_timeout: Duration,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self::from_dpi(160)
+    }
+}
+
+impl GestureConfig {
+    /// Scales the density-independent Android defaults to a screen of `dpi` dots per inch.
+    pub fn from_dpi(dpi: u32) -> Self {
+        let dp = dpi as f32 / 160.0;
+        Self {
+            touch_slop: 8.0 * dp,
+            double_tap_slop: 100.0 * dp,
+            min_fling_velocity: 50.0 * dp,
+            max_fling_velocity: 8000.0 * dp,
+            min_scaling_span: 170.0 * dp,
+            span_slop: 16.0 * dp,
+            rotation_slop: 5f32.to_radians(),
+            double_tap_timeout: Duration::from_millis(300),
+            double_tap_min_time: Duration::from_millis(40),
+            long_press_timeout: Duration::from_millis(400),
+        }
+    }
+}
+
+fn nanos(duration: Duration) -> i64 {
+    duration.as_nanos() as i64
+}
+
+/// Returns the indices of the pointers of `event` that are still down after it, that is without
+/// the pointer that is lifted by [`MotionAction::PointerUp`].
+fn active_pointers(event: &impl MotionEventSource) -> impl Iterator<Item = usize> {
+    let lifted = (event.action() == MotionAction::PointerUp).then_some(event.pointer_index());
+    (0..event.pointer_count()).filter(move |&index| Some(index) != lifted)
+}
+
+/// Returns the id of the pointer that went down or up with `event`.
+fn action_pointer_id(event: &impl MotionEventSource) -> Option<i32> {
+    let index = event.pointer_index();
+    (index < event.pointer_count()).then(|| event.pointer_id(index))
+}
+
+/// Returns the average position of the [`active_pointers()`] of `event`.
+fn focus(event: &impl MotionEventSource) -> (f32, f32) {
+    let (mut x, mut y, mut count) = (0.0, 0.0, 0);
+    for index in active_pointers(event) {
+        x += event.x(index);
+        y += event.y(index);
+        count += 1;
+    }
+    if count == 0 {
+        (0.0, 0.0)
+    } else {
+        (x / count as f32, y / count as f32)
+    }
+}
+
+/// Tracks the velocity of all pointers in a stream of motion events, including the historical
+/// samples batched into every event.
+///
+/// Mirrors [`VelocityTracker`]: the velocity is the slope of a least-squares fit through the
+/// samples of the last 100 milliseconds. A pointer that did not move for 40 milliseconds is
+/// considered stopped.
+///
+/// ```
+/// # use ndk::event::{MotionAction, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+/// # use ndk::event::gestures::VelocityTracker;
+/// let event = |action, time_ms: i64, x| {
+///     let coords = PointerCoords::new(x, 0.0);
+///     OwnedMotionEvent {
+///         action,
+///         event_time: time_ms * 1_000_000,
+///         pointers: vec![OwnedPointer::new(0, ToolType::Finger, coords)],
+///         ..Default::default()
+///     }
+/// };
+///
+/// let mut tracker = VelocityTracker::new();
+/// tracker.add_movement(&event(MotionAction::Down, 0, 0.0));
+/// for time_ms in (10..=50).step_by(10) {
+///     // 2 pixels per millisecond
+///     tracker.add_movement(&event(MotionAction::Move, time_ms, time_ms as f32 * 2.0));
+/// }
+/// let (velocity_x, velocity_y) = tracker.velocity(0).unwrap();
+/// assert!((velocity_x - 2000.0).abs() < 1.0);
+/// assert_eq!(velocity_y, 0.0);
+/// assert_eq!(tracker.velocity(1), None);
+/// ```
+///
+/// [`VelocityTracker`]: https://developer.android.com/reference/android/view/VelocityTracker
+#[derive(Clone, Debug, Default)]
+pub struct VelocityTracker {
+    pointers: Vec<(i32, VecDeque<Sample>)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    time: i64,
+    x: f32,
+    y: f32,
+}
+
+impl VelocityTracker {
+    const HORIZON: i64 = 100_000_000;
+    const STOPPED_TIME: i64 = 40_000_000;
+    const MAX_SAMPLES: usize = 20;
+
+    /// Creates a tracker without any samples.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets all samples.
+    pub fn clear(&mut self) {
+        self.pointers.clear();
+    }
+
+    /// Adds the current and historical samples of all pointers in `event`.
+    ///
+    /// [`MotionAction::Down`] starts a new gesture and forgets all previous samples; a pointer
+    /// that goes down with [`MotionAction::PointerDown`] forgets the samples of an earlier
+    /// pointer with the same id.
+    pub fn add_movement(&mut self, event: &impl MotionEventSource) {
+        match event.action() {
+            MotionAction::Down => self.clear(),
+            MotionAction::PointerDown => {
+                if let Some(pointer_id) = action_pointer_id(event) {
+                    self.pointers.retain(|(id, _)| *id != pointer_id);
+                }
+            }
+            _ => {}
+        }
+
+        for history_index in 0..event.history_size() {
+            let time = event.historical_event_time(history_index);
+            for index in 0..event.pointer_count() {
+                self.add_sample(
+                    event.pointer_id(index),
+                    time,
+                    event.historical_axis_value(index, history_index, Axis::X),
+                    event.historical_axis_value(index, history_index, Axis::Y),
+                );
+            }
+        }
+        for index in 0..event.pointer_count() {
+            self.add_sample(
+                event.pointer_id(index),
+                event.event_time(),
+                event.x(index),
+                event.y(index),
+            );
+        }
+    }
+
+    fn add_sample(&mut self, id: i32, time: i64, x: f32, y: f32) {
+        let samples = match self.pointers.iter_mut().position(|(i, _)| *i == id) {
+            Some(index) => &mut self.pointers[index].1,
+            None => {
+                self.pointers.push((id, VecDeque::new()));
+                &mut self.pointers.last_mut().unwrap().1
+            }
+        };
+        if samples.len() == Self::MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(Sample { time, x, y });
+    }
+
+    /// Returns the velocity of the pointer with the given id in pixels per second, or [`None`]
+    /// if no samples of that pointer were added.
+    pub fn velocity(&self, pointer_id: i32) -> Option<(f32, f32)> {
+        let (_, samples) = self.pointers.iter().find(|(id, _)| *id == pointer_id)?;
+        let newest = samples.back()?;
+
+        // Walk back from the newest sample until the horizon or a pause in the movement
+        let mut count = 1;
+        for (newer, older) in samples.iter().rev().zip(samples.iter().rev().skip(1)) {
+            if newest.time - older.time > Self::HORIZON
+                || newer.time - older.time > Self::STOPPED_TIME
+            {
+                break;
+            }
+            count += 1;
+        }
+        if count < 2 {
+            return Some((0.0, 0.0));
+        }
+
+        let samples = samples.iter().skip(samples.len() - count);
+        let time = |sample: &Sample| (sample.time - newest.time) as f64 / 1e9;
+        let n = count as f64;
+        let (mut t_sum, mut x_sum, mut y_sum) = (0.0, 0.0, 0.0);
+        for sample in samples.clone() {
+            t_sum += time(sample);
+            x_sum += sample.x as f64;
+            y_sum += sample.y as f64;
+        }
+        let (t_mean, x_mean, y_mean) = (t_sum / n, x_sum / n, y_sum / n);
+        let (mut tt, mut tx, mut ty) = (0.0, 0.0, 0.0);
+        for sample in samples {
+            let dt = time(sample) - t_mean;
+            tt += dt * dt;
+            tx += dt * (sample.x as f64 - x_mean);
+            ty += dt * (sample.y as f64 - y_mean);
+        }
+        if tt == 0.0 {
+            return Some((0.0, 0.0));
+        }
+        Some(((tx / tt) as f32, (ty / tt) as f32))
+    }
+}
+
+/// A gesture recognized by a [`GestureDetector`].
+///
+/// All positions are the average position of the pointers that are down.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Gesture {
+    /// The first pointer went down. Mirrors `onDown()`.
+    Down { x: f32, y: f32 },
+    /// A tap was released without moving beyond the touch slop. This is not reported for the
+    /// second tap of a double tap. Mirrors `onSingleTapUp()`.
+    Tap { x: f32, y: f32 },
+    /// A tap that was not followed by a second tap within the double-tap timeout. Mirrors
+    /// `onSingleTapConfirmed()`.
+    SingleTapConfirmed { x: f32, y: f32 },
+    /// The second tap of a double tap went down. Mirrors `onDoubleTap()`.
+    DoubleTap { x: f32, y: f32 },
+    /// The first pointer was held down without moving beyond the touch slop. No further gestures
+    /// are reported until all pointers go up. Mirrors `onLongPress()`.
+    LongPress { x: f32, y: f32 },
+    /// The pointers moved by `(dx, dy)` pixels since the previous pan. Note that the sign is the
+    /// inverse of the distance passed to `onScroll()`.
+    Pan { x: f32, y: f32, dx: f32, dy: f32 },
+    /// The last pointer went up after panning at a velocity of at least
+    /// [`GestureConfig::min_fling_velocity`], in pixels per second. Mirrors `onFling()`.
+    Fling {
+        x: f32,
+        y: f32,
+        velocity_x: f32,
+        velocity_y: f32,
+    },
+}
+
+/// Detects taps, double taps, long presses, pans and flings, mirroring Android's
+/// [`GestureDetector`].
+///
+/// ```
+/// # use ndk::event::{MotionAction, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+/// # use ndk::event::gestures::{Gesture, GestureConfig, GestureDetector};
+/// let event = |action, time_ms: i64, x| {
+///     let coords = PointerCoords::new(x, 0.0);
+///     OwnedMotionEvent {
+///         action,
+///         event_time: time_ms * 1_000_000,
+///         pointers: vec![OwnedPointer::new(0, ToolType::Finger, coords)],
+///         ..Default::default()
+///     }
+/// };
+/// let mut detector = GestureDetector::new(GestureConfig::default());
+///
+/// // Two quick taps are a double tap
+/// detector.on_motion_event(&event(MotionAction::Down, 0, 0.0));
+/// detector.on_motion_event(&event(MotionAction::Up, 50, 0.0));
+/// assert_eq!(
+///     detector.on_motion_event(&event(MotionAction::Down, 150, 0.0)),
+///     [Gesture::Down { x: 0.0, y: 0.0 }, Gesture::DoubleTap { x: 0.0, y: 0.0 }],
+/// );
+/// assert_eq!(detector.on_motion_event(&event(MotionAction::Up, 200, 0.0)), []);
+///
+/// // Holding still is a long press, reported by `poll()`
+/// detector.on_motion_event(&event(MotionAction::Down, 1000, 0.0));
+/// let deadline = detector.next_deadline().unwrap();
+/// assert_eq!(deadline, 1_300_000_000, "the double tap timeout is the earliest deadline");
+/// assert_eq!(detector.poll(deadline), []);
+/// let deadline = detector.next_deadline().unwrap();
+/// assert_eq!(deadline, 1_400_000_000);
+/// assert_eq!(detector.poll(deadline), [Gesture::LongPress { x: 0.0, y: 0.0 }]);
+/// assert_eq!(detector.on_motion_event(&event(MotionAction::Up, 1500, 0.0)), []);
+///
+/// // Releasing a fast pan is a fling
+/// detector.on_motion_event(&event(MotionAction::Down, 2000, 0.0));
+/// for time_ms in (2010..=2050).step_by(10) {
+///     detector.on_motion_event(&event(MotionAction::Move, time_ms, (time_ms - 2000) as f32 * 2.0));
+/// }
+/// let gestures = detector.on_motion_event(&event(MotionAction::Up, 2050, 100.0));
+/// let [Gesture::Fling { velocity_x, velocity_y, .. }] = gestures[..] else {
+///     panic!("Expected a fling, got {gestures:?}");
+/// };
+/// assert!((velocity_x - 2000.0).abs() < 1.0);
+/// assert_eq!(velocity_y, 0.0);
+/// ```
+///
+/// [`GestureDetector`]: https://developer.android.com/reference/android/view/GestureDetector
+#[derive(Clone, Debug)]
+pub struct GestureDetector {
+    config: GestureConfig,
+    velocity_tracker: VelocityTracker,
+    /// Time and focus of the current [`MotionAction::Down`].
+    current_down: Option<(i64, f32, f32)>,
+    /// Time and focus of the [`MotionAction::Down`] and time of the [`MotionAction::Up`] of the
+    /// previous tap, if it can become the first tap of a double tap.
+    previous_tap: Option<(i64, f32, f32, i64)>,
+    last_focus: (f32, f32),
+    still_down: bool,
+    in_tap_region: bool,
+    in_long_press: bool,
+    is_double_tapping: bool,
+    defer_confirm_single_tap: bool,
+    long_press_deadline: Option<i64>,
+    single_tap_deadline: Option<i64>,
+}
+
+impl GestureDetector {
+    /// Creates a detector that recognizes gestures with the thresholds of `config`.
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            velocity_tracker: VelocityTracker::new(),
+            current_down: None,
+            previous_tap: None,
+            last_focus: (0.0, 0.0),
+            still_down: false,
+            in_tap_region: false,
+            in_long_press: false,
+            is_double_tapping: false,
+            defer_confirm_single_tap: false,
+            long_press_deadline: None,
+            single_tap_deadline: None,
+        }
+    }
+
+    /// The thresholds passed to [`new()`][Self::new()].
+    pub fn config(&self) -> &GestureConfig {
+        &self.config
+    }
+
+    /// Returns the [`MotionEventSource::event_time()`] at which [`GestureDetector::poll()`] should
+    /// be called to report a pending [`Gesture::LongPress`] or [`Gesture::SingleTapConfirmed`].
+    pub fn next_deadline(&self) -> Option<i64> {
+        match (self.long_press_deadline, self.single_tap_deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Reports the time-based gestures whose deadline passed at `now`, in the
+    /// [`MotionEventSource::event_time()`] time base.
+    pub fn poll(&mut self, now: i64) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+        let Some((_, x, y)) = self.current_down else {
+            return gestures;
+        };
+        if matches!(self.long_press_deadline, Some(deadline) if deadline <= now) {
+            self.long_press_deadline = None;
+            self.single_tap_deadline = None;
+            self.defer_confirm_single_tap = false;
+            self.in_long_press = true;
+            gestures.push(Gesture::LongPress { x, y });
+        }
+        if matches!(self.single_tap_deadline, Some(deadline) if deadline <= now) {
+            self.single_tap_deadline = None;
+            if self.still_down {
+                // Confirmed when the pointer goes up
+                self.defer_confirm_single_tap = true;
+            } else {
+                gestures.push(Gesture::SingleTapConfirmed { x, y });
+            }
+        }
+        gestures
+    }
+
+    fn cancel_taps(&mut self) {
+        self.long_press_deadline = None;
+        self.single_tap_deadline = None;
+        self.is_double_tapping = false;
+        self.in_tap_region = false;
+        self.defer_confirm_single_tap = false;
+        self.in_long_press = false;
+    }
+
+    /// Feeds the next event of the stream to the detector, returning the recognized gestures.
+    ///
+    /// Call [`GestureDetector::poll()`] first when its [`GestureDetector::next_deadline()`]
+    /// passed before this event.
+    pub fn on_motion_event(&mut self, event: &impl MotionEventSource) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+        self.velocity_tracker.add_movement(event);
+        let (x, y) = focus(event);
+        let time = event.event_time();
+
+        match event.action() {
+            MotionAction::Down => {
+                let had_pending_tap = self.single_tap_deadline.take().is_some();
+                let is_double_tap = had_pending_tap
+                    && self
+                        .previous_tap
+                        .map_or(false, |(_, down_x, down_y, up_time)| {
+                            let elapsed = time - up_time;
+                            let (dx, dy) = (x - down_x, y - down_y);
+                            elapsed >= nanos(self.config.double_tap_min_time)
+                                && elapsed <= nanos(self.config.double_tap_timeout)
+                                && dx * dx + dy * dy
+                                    < self.config.double_tap_slop * self.config.double_tap_slop
+                        });
+                if is_double_tap {
+                    self.is_double_tapping = true;
+                    self.previous_tap = None;
+                } else {
+                    self.single_tap_deadline = Some(time + nanos(self.config.double_tap_timeout));
+                }
+
+                self.current_down = Some((time, x, y));
+                self.last_focus = (x, y);
+                self.still_down = true;
+                self.in_tap_region = true;
+                self.in_long_press = false;
+                self.defer_confirm_single_tap = false;
+                self.long_press_deadline = Some(time + nanos(self.config.long_press_timeout));
+                gestures.push(Gesture::Down { x, y });
+                if is_double_tap {
+                    gestures.push(Gesture::DoubleTap { x, y });
+                }
+            }
+            MotionAction::PointerDown => {
+                self.last_focus = (x, y);
+                self.cancel_taps();
+            }
+            MotionAction::PointerUp => self.last_focus = (x, y),
+            MotionAction::Move if !self.in_long_press && !self.is_double_tapping => {
+                let (dx, dy) = (x - self.last_focus.0, y - self.last_focus.1);
+                if self.in_tap_region {
+                    let (down_x, down_y) = self.current_down.map_or((x, y), |(_, x, y)| (x, y));
+                    let (total_x, total_y) = (x - down_x, y - down_y);
+                    if total_x * total_x + total_y * total_y
+                        > self.config.touch_slop * self.config.touch_slop
+                    {
+                        gestures.push(Gesture::Pan { x, y, dx, dy });
+                        self.last_focus = (x, y);
+                        self.in_tap_region = false;
+                        self.single_tap_deadline = None;
+                        self.long_press_deadline = None;
+                    }
+                } else if dx.abs() >= 1.0 || dy.abs() >= 1.0 {
+                    gestures.push(Gesture::Pan { x, y, dx, dy });
+                    self.last_focus = (x, y);
+                }
+            }
+            MotionAction::Up => {
+                self.still_down = false;
+                if self.is_double_tapping || self.in_long_press {
+                    // Neither the second tap nor a long press end in a tap
+                } else if self.in_tap_region {
+                    gestures.push(Gesture::Tap { x, y });
+                    if self.defer_confirm_single_tap {
+                        gestures.push(Gesture::SingleTapConfirmed { x, y });
+                    }
+                } else if let Some(pointer_id) = action_pointer_id(event) {
+                    let (vx, vy) = self
+                        .velocity_tracker
+                        .velocity(pointer_id)
+                        .unwrap_or_default();
+                    let max = self.config.max_fling_velocity;
+                    if vx.abs() > self.config.min_fling_velocity
+                        || vy.abs() > self.config.min_fling_velocity
+                    {
+                        gestures.push(Gesture::Fling {
+                            x,
+                            y,
+                            velocity_x: vx.clamp(-max, max),
+                            velocity_y: vy.clamp(-max, max),
+                        });
+                    }
+                }
+
+                self.previous_tap = match self.current_down {
+                    Some((down_time, down_x, down_y))
+                        if self.in_tap_region && !self.is_double_tapping && !self.in_long_press =>
+                    {
+                        Some((down_time, down_x, down_y, time))
+                    }
+                    _ => None,
+                };
+                self.is_double_tapping = false;
+                self.defer_confirm_single_tap = false;
+                self.long_press_deadline = None;
+            }
+            MotionAction::Cancel => {
+                self.cancel_taps();
+                self.velocity_tracker.clear();
+                self.current_down = None;
+                self.previous_tap = None;
+                self.still_down = false;
+            }
+            _ => {}
+        }
+        gestures
+    }
+}
+
+/// A gesture recognized by a [`ScaleGestureDetector`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ScaleGesture {
+    /// Mirrors `onScaleBegin()`.
+    Begin { focus_x: f32, focus_y: f32 },
+    /// The span between the pointers changed by `scale_factor` since the previous
+    /// [`ScaleGesture::Scale`] or [`ScaleGesture::Begin`]. Mirrors `onScale()`.
+    Scale {
+        focus_x: f32,
+        focus_y: f32,
+        scale_factor: f32,
+    },
+    /// Mirrors `onScaleEnd()`.
+    End,
+}
+
+/// Detects pinch-to-zoom gestures, mirroring Android's [`ScaleGestureDetector`].
+///
+/// The span is the diameter of the average distance of all pointers to their focus, so that
+/// gestures with more than two pointers scale as well.
+///
+/// ```
+/// # use ndk::event::{MotionAction, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+/// # use ndk::event::gestures::{GestureConfig, ScaleGesture, ScaleGestureDetector};
+/// // Two pointers on a horizontal line, `span` pixels apart
+/// let event = |action, pointer_index, span: f32| {
+///     let pointer = |id, x| OwnedPointer::new(id, ToolType::Finger, PointerCoords::new(x, 0.0));
+///     OwnedMotionEvent {
+///         action,
+///         pointer_index,
+///         pointers: vec![pointer(0, 500.0 - span / 2.0), pointer(1, 500.0 + span / 2.0)],
+///         ..Default::default()
+///     }
+/// };
+/// let mut detector = ScaleGestureDetector::new(GestureConfig::default());
+///
+/// detector.on_motion_event(&event(MotionAction::Down, 0, 0.0));
+/// detector.on_motion_event(&event(MotionAction::PointerDown, 1, 200.0));
+/// // Spreading the pointers beyond the span slop begins the gesture
+/// assert_eq!(
+///     detector.on_motion_event(&event(MotionAction::Move, 0, 250.0)),
+///     [
+///         ScaleGesture::Begin { focus_x: 500.0, focus_y: 0.0 },
+///         ScaleGesture::Scale { focus_x: 500.0, focus_y: 0.0, scale_factor: 1.0 },
+///     ],
+/// );
+/// assert_eq!(
+///     detector.on_motion_event(&event(MotionAction::Move, 0, 500.0)),
+///     [ScaleGesture::Scale { focus_x: 500.0, focus_y: 0.0, scale_factor: 2.0 }],
+/// );
+/// assert!(detector.is_in_progress());
+/// // Lifting a pointer ends it
+/// assert_eq!(
+///     detector.on_motion_event(&event(MotionAction::PointerUp, 1, 500.0)),
+///     [ScaleGesture::End],
+/// );
+/// assert!(!detector.is_in_progress());
+/// ```
+///
+/// [`ScaleGestureDetector`]: https://developer.android.com/reference/android/view/ScaleGestureDetector
+#[derive(Clone, Debug)]
+pub struct ScaleGestureDetector {
+    config: GestureConfig,
+    in_progress: bool,
+    initial_span: f32,
+    previous_span: f32,
+}
+
+impl ScaleGestureDetector {
+    /// Creates a detector that recognizes gestures with the thresholds of `config`.
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            in_progress: false,
+            initial_span: 0.0,
+            previous_span: 0.0,
+        }
+    }
+
+    /// The thresholds passed to [`new()`][Self::new()].
+    pub fn config(&self) -> &GestureConfig {
+        &self.config
+    }
+
+    /// Returns whether a scale gesture is in progress.
+    pub fn is_in_progress(&self) -> bool {
+        self.in_progress
+    }
+
+    /// Feeds the next event of the stream to the detector, returning the recognized gestures.
+    pub fn on_motion_event(&mut self, event: &impl MotionEventSource) -> Vec<ScaleGesture> {
+        let mut gestures = Vec::new();
+        let action = event.action();
+        let stream_complete = matches!(action, MotionAction::Up | MotionAction::Cancel);
+        if action == MotionAction::Down || stream_complete {
+            if self.in_progress {
+                gestures.push(ScaleGesture::End);
+                self.in_progress = false;
+            }
+            self.initial_span = 0.0;
+            if stream_complete {
+                return gestures;
+            }
+        }
+
+        let config_changed = matches!(
+            action,
+            MotionAction::Down | MotionAction::PointerDown | MotionAction::PointerUp
+        );
+        let (focus_x, focus_y) = focus(event);
+        let (mut deviation_x, mut deviation_y, mut count) = (0.0, 0.0, 0);
+        for index in active_pointers(event) {
+            deviation_x += (event.x(index) - focus_x).abs();
+            deviation_y += (event.y(index) - focus_y).abs();
+            count += 1;
+        }
+        let count = count.max(1) as f32;
+        let span = (2.0 * deviation_x / count).hypot(2.0 * deviation_y / count);
+
+        let was_in_progress = self.in_progress;
+        if self.in_progress && (span < self.config.min_scaling_span || config_changed) {
+            gestures.push(ScaleGesture::End);
+            self.in_progress = false;
+            self.initial_span = span;
+        }
+        if config_changed {
+            self.initial_span = span;
+            self.previous_span = span;
+        }
+        if !self.in_progress
+            && span >= self.config.min_scaling_span
+            && (was_in_progress || (span - self.initial_span).abs() > self.config.span_slop)
+        {
+            self.previous_span = span;
+            self.in_progress = true;
+            gestures.push(ScaleGesture::Begin { focus_x, focus_y });
+        }
+        if action == MotionAction::Move && self.in_progress && self.previous_span > 0.0 {
+            gestures.push(ScaleGesture::Scale {
+                focus_x,
+                focus_y,
+                scale_factor: span / self.previous_span,
+            });
+            self.previous_span = span;
+        }
+        gestures
+    }
+}
+
+/// A gesture recognized by a [`RotationGestureDetector`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum RotationGesture {
+    /// The line between the first two pointers rotated beyond [`GestureConfig::rotation_slop`].
+    Begin { focus_x: f32, focus_y: f32 },
+    /// The line between the first two pointers rotated by `delta` radians since the previous
+    /// [`RotationGesture::Rotate`] or [`RotationGesture::Begin`]. Positive angles are clockwise
+    /// on screen, as the y axis points down.
+    Rotate {
+        focus_x: f32,
+        focus_y: f32,
+        delta: f32,
+    },
+    /// The rotation ended because fewer than two pointers are down, or the pointers changed.
+    End,
+}
+
+/// Detects two-finger rotation gestures on the first two pointers that are down.
+///
+/// Rotating across the negative x axis, where the angle of the pointers wraps from `π` to `-π`,
+/// is reported as a small delta:
+///
+/// ```
+/// # use ndk::event::{MotionAction, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+/// # use ndk::event::gestures::{GestureConfig, RotationGesture, RotationGestureDetector};
+/// // Two pointers 100 pixels apart, the second at `angle` radians around the first
+/// let event = |action, angle: f32| {
+///     let pointer = |id, x, y| OwnedPointer::new(id, ToolType::Finger, PointerCoords::new(x, y));
+///     OwnedMotionEvent {
+///         action,
+///         pointer_index: 1,
+///         pointers: vec![
+///             pointer(0, 0.0, 0.0),
+///             pointer(1, 100.0 * angle.cos(), 100.0 * angle.sin()),
+///         ],
+///         ..Default::default()
+///     }
+/// };
+/// let mut detector = RotationGestureDetector::new(GestureConfig::default());
+/// let degrees = f32::to_radians;
+///
+/// detector.on_motion_event(&event(MotionAction::PointerDown, degrees(170.0)));
+/// let gestures = detector.on_motion_event(&event(MotionAction::Move, degrees(180.0)));
+/// assert!(matches!(gestures[..], [RotationGesture::Begin { .. }]));
+/// // From 180° to -170° is a clockwise rotation by 10°, not a counter-clockwise one by 350°
+/// let gestures = detector.on_motion_event(&event(MotionAction::Move, degrees(-170.0)));
+/// let [RotationGesture::Rotate { delta, .. }] = gestures[..] else {
+///     panic!("Expected a rotation, got {gestures:?}");
+/// };
+/// assert!((delta - degrees(10.0)).abs() < 1e-4);
+/// assert_eq!(
+///     detector.on_motion_event(&event(MotionAction::Up, degrees(-170.0))),
+///     [RotationGesture::End],
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct RotationGestureDetector {
+    config: GestureConfig,
+    in_progress: bool,
+    /// The angle at which the pointer configuration last changed, and the previous angle.
+    angles: Option<(f32, f32)>,
+}
+
+/// Wraps `angle` to `(-PI, PI]`.
+fn wrap_angle(angle: f32) -> f32 {
+    let angle = angle.rem_euclid(2.0 * PI);
+    if angle > PI {
+        angle - 2.0 * PI
+    } else {
+        angle
+    }
+}
+
+impl RotationGestureDetector {
+    /// Creates a detector that recognizes gestures with the thresholds of `config`.
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            in_progress: false,
+            angles: None,
+        }
+    }
+
+    /// The thresholds passed to [`new()`][Self::new()].
+    pub fn config(&self) -> &GestureConfig {
+        &self.config
+    }
+
+    /// Returns whether a rotation gesture is in progress.
+    pub fn is_in_progress(&self) -> bool {
+        self.in_progress
+    }
+
+    fn end(&mut self, gestures: &mut Vec<RotationGesture>) {
+        if self.in_progress {
+            gestures.push(RotationGesture::End);
+            self.in_progress = false;
+        }
+        self.angles = None;
+    }
+
+    /// Feeds the next event of the stream to the detector, returning the recognized gestures.
+    pub fn on_motion_event(&mut self, event: &impl MotionEventSource) -> Vec<RotationGesture> {
+        let mut gestures = Vec::new();
+        let action = event.action();
+        if matches!(
+            action,
+            MotionAction::Down | MotionAction::Up | MotionAction::Cancel
+        ) {
+            self.end(&mut gestures);
+            return gestures;
+        }
+
+        let mut pointers = active_pointers(event);
+        let (Some(first), Some(second)) = (pointers.next(), pointers.next()) else {
+            self.end(&mut gestures);
+            return gestures;
+        };
+        let (x1, y1, x2, y2) = (
+            event.x(first),
+            event.y(first),
+            event.x(second),
+            event.y(second),
+        );
+        let angle = (y2 - y1).atan2(x2 - x1);
+        let (focus_x, focus_y) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+
+        let config_changed = matches!(action, MotionAction::PointerDown | MotionAction::PointerUp);
+        let Some((initial, previous)) = self.angles.filter(|_| !config_changed) else {
+            // Continue an ongoing rotation relative to the new pair of pointers
+            self.angles = Some((angle, angle));
+            return gestures;
+        };
+
+        if !self.in_progress {
+            if wrap_angle(angle - initial).abs() > self.config.rotation_slop {
+                self.in_progress = true;
+                self.angles = Some((initial, angle));
+                gestures.push(RotationGesture::Begin { focus_x, focus_y });
+            }
+        } else if action == MotionAction::Move {
+            self.angles = Some((initial, angle));
+            gestures.push(RotationGesture::Rotate {
+                focus_x,
+                focus_y,
+                delta: wrap_angle(angle - previous),
+            });
+        }
+        gestures
+    }
+}
@@ -0,0 +1,307 @@
+//! Introspection of input devices through [`android.view.InputDevice`], to learn more about the
+//! device behind [`InputEvent::device_id()`][super::InputEvent::device_id()].
+//!
+//! [`android.view.InputDevice`]: https://developer.android.com/reference/android/view/InputDevice
+#![cfg(feature = "jni")]
+
+use std::time::Duration;
+
+use jni::{
+    objects::{GlobalRef, JBooleanArray, JIntArray, JObject, JString, JValue},
+    JNIEnv,
+};
+use num_enum::{FromPrimitive, IntoPrimitive};
+
+use super::{Axis, Keycode, Source, SourceClass};
+
+/// The keyboard of an [`InputDevice`].
+///
+/// Note that these match the `KEYBOARD_TYPE_*` values of [`android.view.InputDevice`] in the
+/// Android Java API.
+///
+/// [`android.view.InputDevice`]: https://developer.android.com/reference/android/view/InputDevice
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[non_exhaustive]
+pub enum InputDeviceKeyboardType {
+    /// The device does not have a keyboard.
+    #[doc(alias = "KEYBOARD_TYPE_NONE")]
+    None = 0,
+    /// The device has buttons or keys, but they are not typically used for entering text, such
+    /// as the buttons of a gamepad.
+    #[doc(alias = "KEYBOARD_TYPE_NON_ALPHABETIC")]
+    NonAlphabetic = 1,
+    /// The device has a keyboard for entering text.
+    #[doc(alias = "KEYBOARD_TYPE_ALPHABETIC")]
+    Alphabetic = 2,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// The range of values of an [`Axis`] of an [`InputDevice`], as reported by
+/// [`android.view.InputDevice.MotionRange`].
+///
+/// [`android.view.InputDevice.MotionRange`]: https://developer.android.com/reference/android/view/InputDevice.MotionRange
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct MotionRange {
+    pub axis: Axis,
+    /// The source for which the axis is defined.
+    pub source: Source,
+    pub min: f32,
+    pub max: f32,
+    /// The extent of the center flat position, within which values should be treated as zero.
+    pub flat: f32,
+    /// The error tolerance, within which values should be treated as noise.
+    pub fuzz: f32,
+    /// The resolution in units per millimeter, or `0.0` if unknown.
+    pub resolution: f32,
+}
+
+impl MotionRange {
+    /// Returns the extent of the range, `max - min`.
+    pub fn range(&self) -> f32 {
+        self.max - self.min
+    }
+}
+
+/// A snapshot of the properties of an [`android.view.InputDevice`], as returned by
+/// [`InputDevice::get()`].
+///
+/// [`android.view.InputDevice`]: https://developer.android.com/reference/android/view/InputDevice
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct InputDevice {
+    object: GlobalRef,
+    /// The id as returned by [`InputEvent::device_id()`][super::InputEvent::device_id()].
+    pub id: i32,
+    pub name: String,
+    /// A stable identifier of the device that persists across reconnections and reboots.
+    pub descriptor: String,
+    pub vendor_id: i32,
+    pub product_id: i32,
+    /// The number of the player for game controllers, starting at `1`, or `0` if the device is
+    /// not a game controller.
+    pub controller_number: i32,
+    /// The bitmask of all [`Source`]s of the device, see [`InputDevice::supports_source()`].
+    pub sources: i32,
+    pub keyboard_type: InputDeviceKeyboardType,
+    /// Whether the device is a virtual device, such as the device that reports injected events,
+    /// instead of physical hardware.
+    pub is_virtual: bool,
+    /// Whether the device has a vibrator, see [`InputDevice::vibrate()`].
+    pub has_vibrator: bool,
+    pub motion_ranges: Vec<MotionRange>,
+}
+
+impl PartialEq for InputDevice {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.descriptor == other.descriptor
+    }
+}
+
+impl InputDevice {
+    /// Looks up the device with the given id through [`InputDevice.getDevice()`], returning
+    /// [`None`] if it does not exist.
+    ///
+    /// [`InputDevice.getDevice()`]: https://developer.android.com/reference/android/view/InputDevice#getDevice(int)
+    ///
+    /// ```no_run
+    /// # use ndk::event::{Axis, Source};
+    /// # use ndk::event::input_device::InputDevice;
+    /// # fn example(env: &mut jni::JNIEnv<'_>, event: &ndk::event::MotionEvent) {
+    /// if let Some(device) = InputDevice::get(env, event.device_id()).unwrap() {
+    ///     if device.supports_source(Source::Joystick) {
+    ///         let stick = device.motion_range(Axis::X, Some(Source::Joystick));
+    ///         println!("{}: left stick {stick:?}", device.name);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[doc(alias = "getDevice")]
+    pub fn get(env: &mut JNIEnv<'_>, device_id: i32) -> jni::errors::Result<Option<Self>> {
+        env.with_local_frame(32, |env| {
+            let device = env
+                .call_static_method(
+                    "android/view/InputDevice",
+                    "getDevice",
+                    "(I)Landroid/view/InputDevice;",
+                    &[JValue::Int(device_id)],
+                )?
+                .l()?;
+            if device.is_null() {
+                return Ok(None);
+            }
+            Self::from_java(env, &device).map(Some)
+        })
+    }
+
+    /// Lists the ids of all input devices that are currently connected, through
+    /// [`InputDevice.getDeviceIds()`].
+    ///
+    /// [`InputDevice.getDeviceIds()`]: https://developer.android.com/reference/android/view/InputDevice#getDeviceIds()
+    #[doc(alias = "getDeviceIds")]
+    pub fn device_ids(env: &mut JNIEnv<'_>) -> jni::errors::Result<Vec<i32>> {
+        env.with_local_frame(4, |env| {
+            let array = JIntArray::from(
+                env.call_static_method("android/view/InputDevice", "getDeviceIds", "()[I", &[])?
+                    .l()?,
+            );
+            let mut ids = vec![0; env.get_array_length(&array)? as usize];
+            env.get_int_array_region(&array, 0, &mut ids)?;
+            Ok(ids)
+        })
+    }
+
+    fn from_java(env: &mut JNIEnv<'_>, device: &JObject<'_>) -> jni::errors::Result<Self> {
+        let string = |env: &mut JNIEnv<'_>, method| -> jni::errors::Result<String> {
+            let string = JString::from(
+                env.call_method(device, method, "()Ljava/lang/String;", &[])?
+                    .l()?,
+            );
+            if string.is_null() {
+                return Ok(String::new());
+            }
+            let string = env.get_string(&string)?.into();
+            Ok(string)
+        };
+        let name = string(env, "getName")?;
+        let descriptor = string(env, "getDescriptor")?;
+
+        let vibrator = env
+            .call_method(device, "getVibrator", "()Landroid/os/Vibrator;", &[])?
+            .l()?;
+        let has_vibrator = env.call_method(&vibrator, "hasVibrator", "()Z", &[])?.z()?;
+
+        let ranges = env
+            .call_method(device, "getMotionRanges", "()Ljava/util/List;", &[])?
+            .l()?;
+        let count = env.call_method(&ranges, "size", "()I", &[])?.i()?;
+        let motion_ranges = (0..count)
+            .map(|i| {
+                env.with_local_frame(4, |env| {
+                    let range = env
+                        .call_method(&ranges, "get", "(I)Ljava/lang/Object;", &[JValue::Int(i)])?
+                        .l()?;
+                    let mut float = |method| env.call_method(&range, method, "()F", &[])?.f();
+                    Ok(MotionRange {
+                        min: float("getMin")?,
+                        max: float("getMax")?,
+                        flat: float("getFlat")?,
+                        fuzz: float("getFuzz")?,
+                        resolution: float("getResolution")?,
+                        axis: env.call_method(&range, "getAxis", "()I", &[])?.i()?.into(),
+                        source: env
+                            .call_method(&range, "getSource", "()I", &[])?
+                            .i()?
+                            .into(),
+                    })
+                })
+            })
+            .collect::<jni::errors::Result<_>>()?;
+
+        Ok(Self {
+            object: env.new_global_ref(device)?,
+            id: env.call_method(device, "getId", "()I", &[])?.i()?,
+            name,
+            descriptor,
+            vendor_id: env.call_method(device, "getVendorId", "()I", &[])?.i()?,
+            product_id: env.call_method(device, "getProductId", "()I", &[])?.i()?,
+            controller_number: env
+                .call_method(device, "getControllerNumber", "()I", &[])?
+                .i()?,
+            sources: env.call_method(device, "getSources", "()I", &[])?.i()?,
+            keyboard_type: env
+                .call_method(device, "getKeyboardType", "()I", &[])?
+                .i()?
+                .into(),
+            is_virtual: env.call_method(device, "isVirtual", "()Z", &[])?.z()?,
+            has_vibrator,
+            motion_ranges,
+        })
+    }
+
+    /// Returns whether all bits of `source` are part of [`InputDevice::sources`], mirroring
+    /// [`InputDevice.supportsSource()`].
+    ///
+    /// [`InputDevice.supportsSource()`]: https://developer.android.com/reference/android/view/InputDevice#supportsSource(int)
+    #[doc(alias = "supportsSource")]
+    pub fn supports_source(&self, source: Source) -> bool {
+        let source = i32::from(source);
+        self.sources & source == source
+    }
+
+    /// Returns the union of the [`SourceClass`]es of all [`InputDevice::sources`].
+    pub fn source_classes(&self) -> SourceClass {
+        SourceClass::from_bits_retain((self.sources & ffi::AINPUT_SOURCE_CLASS_MASK as i32) as u8)
+    }
+
+    /// Returns the range of `axis`, for the given `source` or for any source of the device,
+    /// mirroring [`InputDevice.getMotionRange()`].
+    ///
+    /// [`InputDevice.getMotionRange()`]: https://developer.android.com/reference/android/view/InputDevice#getMotionRange(int,%20int)
+    #[doc(alias = "getMotionRange")]
+    pub fn motion_range(&self, axis: Axis, source: Option<Source>) -> Option<&MotionRange> {
+        self.motion_ranges
+            .iter()
+            .find(|range| range.axis == axis && source.map_or(true, |s| range.source == s))
+    }
+
+    /// Returns whether the device has each of `keys`, through [`InputDevice.hasKeys()`].
+    ///
+    /// [`InputDevice.hasKeys()`]: https://developer.android.com/reference/android/view/InputDevice#hasKeys(int...)
+    #[doc(alias = "hasKeys")]
+    pub fn has_keys(
+        &self,
+        env: &mut JNIEnv<'_>,
+        keys: &[Keycode],
+    ) -> jni::errors::Result<Vec<bool>> {
+        env.with_local_frame(4, |env| {
+            let keys = keys.iter().map(|&k| k.into()).collect::<Vec<i32>>();
+            let array = env.new_int_array(keys.len() as i32)?;
+            env.set_int_array_region(&array, 0, &keys)?;
+            let result = JBooleanArray::from(
+                env.call_method(&self.object, "hasKeys", "([I)[Z", &[JValue::from(&array)])?
+                    .l()?,
+            );
+            let mut has = vec![0; keys.len()];
+            env.get_boolean_array_region(&result, 0, &mut has)?;
+            Ok(has.into_iter().map(|b| b != 0).collect())
+        })
+    }
+
+    /// Vibrates the device for `duration`, if it [has a vibrator][InputDevice::has_vibrator],
+    /// such as the rumble motor of a game controller.
+    pub fn vibrate(&self, env: &mut JNIEnv<'_>, duration: Duration) -> jni::errors::Result<()> {
+        env.with_local_frame(4, |env| {
+            let vibrator = self.vibrator(env)?;
+            let millis = duration.as_millis().try_into().unwrap_or(i64::MAX);
+            env.call_method(&vibrator, "vibrate", "(J)V", &[JValue::Long(millis)])?;
+            Ok(())
+        })
+    }
+
+    /// Stops a vibration started by [`InputDevice::vibrate()`].
+    #[doc(alias = "cancel")]
+    pub fn cancel_vibration(&self, env: &mut JNIEnv<'_>) -> jni::errors::Result<()> {
+        env.with_local_frame(4, |env| {
+            let vibrator = self.vibrator(env)?;
+            env.call_method(&vibrator, "cancel", "()V", &[])?;
+            Ok(())
+        })
+    }
+
+    fn vibrator<'local>(&self, env: &mut JNIEnv<'local>) -> jni::errors::Result<JObject<'local>> {
+        env.call_method(&self.object, "getVibrator", "()Landroid/os/Vibrator;", &[])?
+            .l()
+    }
+
+    /// Returns the Java [`android.view.InputDevice`] object.
+    ///
+    /// [`android.view.InputDevice`]: https://developer.android.com/reference/android/view/InputDevice
+    pub fn as_java(&self) -> &JObject<'static> {
+        self.object.as_obj()
+    }
+}
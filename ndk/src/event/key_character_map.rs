@@ -0,0 +1,573 @@
+//! Translation of [`Keycode`]s and [`MetaState`] to text, mirroring
+//! [`android.view.KeyCharacterMap`]
+//!
+//! A [`KeyCharacterMap`] is either [loaded][KeyCharacterMap::load()] from the layout of an input
+//! device through JNI, or the [built-in][KeyCharacterMap::built_in()] US layout of the Android
+//! virtual keyboard which is available without a JVM. Dead keys of the loaded layouts are
+//! combined with the following character by a [`DeadKeyComposer`].
+//!
+//! [`android.view.KeyCharacterMap`]: https://developer.android.com/reference/android/view/KeyCharacterMap
+
+#[cfg(feature = "jni")]
+use jni::{
+    objects::{GlobalRef, JObject, JValue},
+    JNIEnv, JavaVM,
+};
+use num_enum::{FromPrimitive, IntoPrimitive};
+
+use super::{Keycode, MetaState};
+
+/// The type of keyboard described by a [`KeyCharacterMap`].
+///
+/// Note that these match the keyboard type constants of [`android.view.KeyCharacterMap`] in the
+/// Android Java API.
+///
+/// [`android.view.KeyCharacterMap`]: https://developer.android.com/reference/android/view/KeyCharacterMap
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[non_exhaustive]
+pub enum KeyboardType {
+    /// A numeric (12-key) keyboard.
+    #[doc(alias = "NUMERIC")]
+    Numeric = 1,
+    /// A keyboard with all the letters, but with more than one letter per key.
+    #[doc(alias = "PREDICTIVE")]
+    Predictive = 2,
+    /// A keyboard with all the letters, and maybe some numbers.
+    #[doc(alias = "ALPHA")]
+    Alpha = 3,
+    /// A full PC-style keyboard.
+    #[doc(alias = "FULL")]
+    Full = 4,
+    /// A keyboard that is only used to control special functions rather than for typing.
+    #[doc(alias = "SPECIAL_FUNCTION")]
+    SpecialFunction = 5,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// The key and modifiers to dispatch instead of an unhandled key, as returned by
+/// [`KeyCharacterMap::fallback_action()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FallbackAction {
+    pub key_code: Keycode,
+    pub meta_state: MetaState,
+}
+
+/// `KeyCharacterMap.COMBINING_ACCENT`, flagging the result of `get()` as a dead key.
+#[cfg(feature = "jni")]
+const COMBINING_ACCENT: i32 = 0x80000000u32 as i32;
+/// `KeyCharacterMap.VIRTUAL_KEYBOARD`, the device id of the virtual keyboard.
+#[cfg(feature = "jni")]
+const VIRTUAL_KEYBOARD: i32 = -1;
+
+#[derive(Clone, Debug)]
+enum Backend {
+    BuiltIn,
+    #[cfg(feature = "jni")]
+    Java {
+        vm: std::sync::Arc<JavaVM>,
+        map: GlobalRef,
+    },
+}
+
+/// Maps keys to the characters they produce, the accents of dead keys, and fallback actions.
+///
+/// Mirrors [`android.view.KeyCharacterMap`].
+///
+/// [`android.view.KeyCharacterMap`]: https://developer.android.com/reference/android/view/KeyCharacterMap
+#[derive(Clone, Debug)]
+pub struct KeyCharacterMap {
+    backend: Backend,
+}
+
+impl KeyCharacterMap {
+    /// A pure-Rust map of the US layout of the Android virtual keyboard, for use when no JVM is
+    /// available.
+    ///
+    /// It maps letters, digits, punctuation, the numeric keypad, space, tab and enter, and
+    /// provides the fallback actions of Android's `Generic.kcm`, but has no dead keys.
+    ///
+    /// ```
+    /// # use ndk::event::{Keycode, MetaState};
+    /// # use ndk::event::key_character_map::KeyCharacterMap;
+    /// let map = KeyCharacterMap::built_in();
+    /// assert_eq!(map.get(Keycode::A, MetaState(0)), Some('a'));
+    /// // AMETA_SHIFT_ON
+    /// assert_eq!(map.get(Keycode::Keycode1, MetaState(0x1)), Some('!'));
+    /// ```
+    pub fn built_in() -> Self {
+        Self {
+            backend: Backend::BuiltIn,
+        }
+    }
+
+    /// Loads the key character map of the input device with the given id, through
+    /// [`KeyCharacterMap.load()`].
+    ///
+    /// Use a negative `device_id`, such as the id of events injected by the system, for the
+    /// virtual keyboard.
+    ///
+    /// [`KeyCharacterMap.load()`]: https://developer.android.com/reference/android/view/KeyCharacterMap#load(int)
+    #[cfg(feature = "jni")]
+    pub fn load(env: &mut JNIEnv<'_>, device_id: i32) -> jni::errors::Result<Self> {
+        let device_id = if device_id < 0 {
+            VIRTUAL_KEYBOARD
+        } else {
+            device_id
+        };
+        env.with_local_frame(4, |env| {
+            let map = env
+                .call_static_method(
+                    "android/view/KeyCharacterMap",
+                    "load",
+                    "(I)Landroid/view/KeyCharacterMap;",
+                    &[JValue::Int(device_id)],
+                )?
+                .l()?;
+            Ok(Self {
+                backend: Backend::Java {
+                    vm: std::sync::Arc::new(env.get_java_vm()?),
+                    map: env.new_global_ref(map)?,
+                },
+            })
+        })
+    }
+
+    /// Calls `f` with the Java `KeyCharacterMap`, attaching the current thread to the JVM if
+    /// needed. JNI errors are logged and reported as [`None`], as Java reports missing mappings.
+    ///
+    /// `f` runs in its own local reference frame, as a native thread that stays attached never
+    /// returns to Java to release the local references created on every key event.
+    #[cfg(feature = "jni")]
+    fn with_java<T>(
+        vm: &JavaVM,
+        map: &GlobalRef,
+        f: impl FnOnce(&mut JNIEnv<'_>, &JObject<'_>) -> jni::errors::Result<T>,
+    ) -> Option<T> {
+        let mut env = match vm.attach_current_thread() {
+            Ok(env) => env,
+            Err(e) => {
+                log::error!("Failed to attach to the JVM: {e}");
+                return None;
+            }
+        };
+        match env.with_local_frame(4, |env| f(env, map.as_obj())) {
+            Ok(result) => Some(result),
+            Err(e) => {
+                if env.exception_check().unwrap_or(false) {
+                    let _ = env.exception_clear();
+                }
+                log::error!("KeyCharacterMap call failed: {e}");
+                None
+            }
+        }
+    }
+
+    /// Calls `KeyCharacterMap.get()`, which returns `0` for keys without a character.
+    #[cfg(feature = "jni")]
+    fn java_get(&self, key_code: Keycode, meta_state: MetaState) -> Option<i32> {
+        let Backend::Java { vm, map } = &self.backend else {
+            return None;
+        };
+        Self::with_java(vm, map, |env, map| {
+            env.call_method(
+                map,
+                "get",
+                "(II)I",
+                &[
+                    JValue::Int(key_code.into()),
+                    JValue::Int(meta_state.0 as i32),
+                ],
+            )?
+            .i()
+        })
+        .filter(|&c| c != 0)
+    }
+
+    /// Returns the type of keyboard this map describes.
+    #[doc(alias = "getKeyboardType")]
+    pub fn keyboard_type(&self) -> KeyboardType {
+        match &self.backend {
+            Backend::BuiltIn => KeyboardType::Full,
+            #[cfg(feature = "jni")]
+            Backend::Java { vm, map } => Self::with_java(vm, map, |env, map| {
+                env.call_method(map, "getKeyboardType", "()I", &[])?.i()
+            })
+            .map_or(KeyboardType::Full, KeyboardType::from),
+        }
+    }
+
+    /// Returns the character that `key_code` produces with the modifiers in `meta_state`, or
+    /// [`None`] if it produces no character or is a [dead key][Self::dead_char()].
+    pub fn get(&self, key_code: Keycode, meta_state: MetaState) -> Option<char> {
+        match &self.backend {
+            Backend::BuiltIn => built_in::get(key_code, meta_state),
+            #[cfg(feature = "jni")]
+            Backend::Java { .. } => self
+                .java_get(key_code, meta_state)
+                .filter(|c| c & COMBINING_ACCENT == 0)
+                .and_then(|c| char::from_u32(c as u32)),
+        }
+    }
+
+    /// Returns the combining accent, such as `'\u{301}'` for an acute accent, if `key_code` is a
+    /// dead key with the modifiers in `meta_state`.
+    ///
+    /// Combine it with the next character using [`combine_dead_char()`] or a
+    /// [`DeadKeyComposer`].
+    pub fn dead_char(&self, key_code: Keycode, meta_state: MetaState) -> Option<char> {
+        match &self.backend {
+            Backend::BuiltIn => {
+                // The built-in layout has no dead keys
+                let _ = (key_code, meta_state);
+                None
+            }
+            #[cfg(feature = "jni")]
+            Backend::Java { .. } => self
+                .java_get(key_code, meta_state)
+                .filter(|c| c & COMBINING_ACCENT != 0)
+                .and_then(|c| char::from_u32((c & !COMBINING_ACCENT) as u32)),
+        }
+    }
+
+    /// Returns the primary character printed on `key_code`, such as `'A'` for [`Keycode::A`].
+    #[doc(alias = "getDisplayLabel")]
+    pub fn display_label(&self, key_code: Keycode) -> Option<char> {
+        match &self.backend {
+            Backend::BuiltIn => built_in::display_label(key_code),
+            #[cfg(feature = "jni")]
+            Backend::Java { vm, map } => Self::with_java(vm, map, |env, map| {
+                env.call_method(
+                    map,
+                    "getDisplayLabel",
+                    "(I)C",
+                    &[JValue::Int(key_code.into())],
+                )?
+                .c()
+            })
+            .filter(|&c| c != 0)
+            .and_then(|c| char::from_u32(c.into())),
+        }
+    }
+
+    /// Returns the key to dispatch instead when an application does not handle `key_code` with
+    /// the modifiers in `meta_state`, such as [`Keycode::Back`] for [`Keycode::Escape`].
+    #[doc(alias = "getFallbackAction")]
+    pub fn fallback_action(
+        &self,
+        key_code: Keycode,
+        meta_state: MetaState,
+    ) -> Option<FallbackAction> {
+        match &self.backend {
+            Backend::BuiltIn => built_in::fallback_action(key_code, meta_state),
+            #[cfg(feature = "jni")]
+            Backend::Java { vm, map } => Self::with_java(vm, map, |env, map| {
+                let action = env
+                    .call_method(
+                        map,
+                        "getFallbackAction",
+                        "(II)Landroid/view/KeyCharacterMap$FallbackAction;",
+                        &[
+                            JValue::Int(key_code.into()),
+                            JValue::Int(meta_state.0 as i32),
+                        ],
+                    )?
+                    .l()?;
+                if action.is_null() {
+                    return Ok(None);
+                }
+                let action_key_code = env.get_field(&action, "keyCode", "I")?.i()?;
+                let action_meta_state = env.get_field(&action, "metaState", "I")?.i()?;
+                Ok(Some(FallbackAction {
+                    key_code: action_key_code.into(),
+                    meta_state: MetaState(action_meta_state as u32),
+                }))
+            })
+            .flatten(),
+        }
+    }
+}
+
+/// Combines the combining `accent` of a dead key with the character `c` typed after it, such as
+/// `'\u{301}'` (acute accent) and `'e'` to `'é'`, mirroring `KeyCharacterMap.getDeadChar()`.
+///
+/// Typing space or the dead key itself produces the accent as a standalone (spacing) character.
+/// Returns [`None`] if the combination does not exist.
+///
+/// ```
+/// # use ndk::event::key_character_map::combine_dead_char;
+/// assert_eq!(combine_dead_char('\u{308}', 'u'), Some('ü'));
+/// assert_eq!(combine_dead_char('\u{300}', ' '), Some('`'));
+/// assert_eq!(combine_dead_char('\u{300}', 'x'), None);
+/// ```
+#[doc(alias = "getDeadChar")]
+pub fn combine_dead_char(accent: char, c: char) -> Option<char> {
+    let (spacing, combinations) = match accent {
+        '\u{300}' => ('`', "AÀEÈIÌOÒUÙaàeèiìoòuùNǸnǹWẀwẁYỲyỳ"),
+        '\u{301}' => (
+            '´',
+            "AÁEÉIÍOÓUÚYÝaáeéiíoóuúyýCĆcćNŃnńSŚsśZŹzźGǴgǵLĹlĺRŔrŕWẂwẃ",
+        ),
+        '\u{302}' => ('^', "AÂEÊIÎOÔUÛaâeêiîoôuûCĈcĉGĜgĝHĤhĥJĴjĵSŜsŝWŴwŵYŶyŷZẐzẑ"),
+        '\u{303}' => ('~', "AÃNÑOÕaãnñoõIĨiĩUŨuũEẼeẽYỸyỹ"),
+        '\u{308}' => ('¨', "AÄEËIÏOÖUÜaäeëiïoöuüyÿYŸHḦhḧWẄwẅXẌxẍtẗ"),
+        '\u{30a}' => ('˚', "AÅaåUŮuůwẘyẙ"),
+        '\u{327}' => ('¸', "CÇcçSŞsşGĢgģKĶkķLĻlļNŅnņRŖrŗTŢtţ"),
+        '\u{30c}' => (
+            'ˇ',
+            "CČcčDĎdďEĚeěNŇnňRŘrřSŠsšTŤtťZŽzžAǍaǎIǏiǐOǑoǒUǓuǔGǦgǧKǨkǩjǰ",
+        ),
+        _ => return None,
+    };
+    if c == ' ' || c == accent {
+        return Some(spacing);
+    }
+    let mut chars = combinations.chars();
+    while let (Some(base), Some(combined)) = (chars.next(), chars.next()) {
+        if base == c {
+            return Some(combined);
+        }
+    }
+    None
+}
+
+/// Turns key presses into characters, combining dead keys with the character typed after them.
+///
+/// ```
+/// # use ndk::event::{Keycode, MetaState};
+/// # use ndk::event::key_character_map::{DeadKeyComposer, KeyCharacterMap};
+/// let map = KeyCharacterMap::built_in();
+/// let mut composer = DeadKeyComposer::default();
+/// assert_eq!(composer.process(&map, Keycode::H, MetaState(0)), Some('h'));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeadKeyComposer {
+    accent: Option<char>,
+}
+
+impl DeadKeyComposer {
+    /// Returns the pending accent of the last dead key, if any.
+    pub fn pending_accent(&self) -> Option<char> {
+        self.accent
+    }
+
+    /// Forgets the pending accent.
+    pub fn reset(&mut self) {
+        self.accent = None;
+    }
+
+    /// Processes a key press, returning the character to insert.
+    ///
+    /// A dead key returns [`None`] and is combined with the next character. If that
+    /// combination does not exist, the next character is returned without accent.
+    pub fn process(
+        &mut self,
+        map: &KeyCharacterMap,
+        key_code: Keycode,
+        meta_state: MetaState,
+    ) -> Option<char> {
+        if let Some(accent) = map.dead_char(key_code, meta_state) {
+            return match self.accent.replace(accent) {
+                // Typing a dead key twice produces the standalone accent
+                Some(pending) if pending == accent => {
+                    self.accent = None;
+                    combine_dead_char(accent, accent)
+                }
+                _ => None,
+            };
+        }
+
+        let c = map.get(key_code, meta_state)?;
+        Some(match self.accent.take() {
+            Some(accent) => combine_dead_char(accent, c).unwrap_or(c),
+            None => c,
+        })
+    }
+}
+
+/// The US layout of the Android virtual keyboard, following `Virtual.kcm` and `Generic.kcm`.
+mod built_in {
+    use super::{FallbackAction, Keycode, MetaState};
+
+    const LETTERS: [Keycode; 26] = [
+        Keycode::A,
+        Keycode::B,
+        Keycode::C,
+        Keycode::D,
+        Keycode::E,
+        Keycode::F,
+        Keycode::G,
+        Keycode::H,
+        Keycode::I,
+        Keycode::J,
+        Keycode::K,
+        Keycode::L,
+        Keycode::M,
+        Keycode::N,
+        Keycode::O,
+        Keycode::P,
+        Keycode::Q,
+        Keycode::R,
+        Keycode::S,
+        Keycode::T,
+        Keycode::U,
+        Keycode::V,
+        Keycode::W,
+        Keycode::X,
+        Keycode::Y,
+        Keycode::Z,
+    ];
+
+    /// Keys that produce a character, with and without shift.
+    const SYMBOLS: [(Keycode, char, char); 26] = [
+        (Keycode::Keycode0, '0', ')'),
+        (Keycode::Keycode1, '1', '!'),
+        (Keycode::Keycode2, '2', '@'),
+        (Keycode::Keycode3, '3', '#'),
+        (Keycode::Keycode4, '4', '$'),
+        (Keycode::Keycode5, '5', '%'),
+        (Keycode::Keycode6, '6', '^'),
+        (Keycode::Keycode7, '7', '&'),
+        (Keycode::Keycode8, '8', '*'),
+        (Keycode::Keycode9, '9', '('),
+        (Keycode::Grave, '`', '~'),
+        (Keycode::Minus, '-', '_'),
+        (Keycode::Equals, '=', '+'),
+        (Keycode::LeftBracket, '[', '{'),
+        (Keycode::RightBracket, ']', '}'),
+        (Keycode::Backslash, '\\', '|'),
+        (Keycode::Semicolon, ';', ':'),
+        (Keycode::Apostrophe, '\'', '"'),
+        (Keycode::Comma, ',', '<'),
+        (Keycode::Period, '.', '>'),
+        (Keycode::Slash, '/', '?'),
+        (Keycode::Space, ' ', ' '),
+        (Keycode::Tab, '\t', '\t'),
+        (Keycode::Enter, '\n', '\n'),
+        (Keycode::NumpadEnter, '\n', '\n'),
+        (Keycode::At, '@', '@'),
+    ];
+
+    /// Keys of the numeric keypad that produce a character regardless of num lock.
+    const NUMPAD_SYMBOLS: [(Keycode, char); 8] = [
+        (Keycode::NumpadDivide, '/'),
+        (Keycode::NumpadMultiply, '*'),
+        (Keycode::NumpadSubtract, '-'),
+        (Keycode::NumpadAdd, '+'),
+        (Keycode::NumpadComma, ','),
+        (Keycode::NumpadEquals, '='),
+        (Keycode::NumpadLeftParen, '('),
+        (Keycode::NumpadRightParen, ')'),
+    ];
+
+    /// Keys of the numeric keypad that only produce a character with num lock, and their
+    /// fallback without it.
+    const NUMPAD_DIGITS: [(Keycode, char, Keycode); 11] = [
+        (Keycode::Numpad0, '0', Keycode::Insert),
+        (Keycode::Numpad1, '1', Keycode::MoveEnd),
+        (Keycode::Numpad2, '2', Keycode::DpadDown),
+        (Keycode::Numpad3, '3', Keycode::PageDown),
+        (Keycode::Numpad4, '4', Keycode::DpadLeft),
+        (Keycode::Numpad5, '5', Keycode::DpadCenter),
+        (Keycode::Numpad6, '6', Keycode::DpadRight),
+        (Keycode::Numpad7, '7', Keycode::MoveHome),
+        (Keycode::Numpad8, '8', Keycode::DpadUp),
+        (Keycode::Numpad9, '9', Keycode::PageUp),
+        (Keycode::NumpadDot, '.', Keycode::ForwardDel),
+    ];
+
+    /// Other keys in `Generic.kcm` that fall back to a different key.
+    const FALLBACKS: [(Keycode, Keycode); 8] = [
+        (Keycode::ButtonA, Keycode::DpadCenter),
+        (Keycode::ButtonB, Keycode::Back),
+        (Keycode::ButtonX, Keycode::DpadCenter),
+        (Keycode::ButtonY, Keycode::Back),
+        (Keycode::ButtonStart, Keycode::DpadCenter),
+        (Keycode::ButtonSelect, Keycode::Menu),
+        (Keycode::ButtonMode, Keycode::Menu),
+        (Keycode::ButtonThumbl, Keycode::DpadCenter),
+    ];
+
+    fn letter(key_code: Keycode) -> Option<char> {
+        let index = LETTERS.iter().position(|&k| k == key_code)?;
+        Some((b'a' + index as u8) as char)
+    }
+
+    pub(super) fn get(key_code: Keycode, meta_state: MetaState) -> Option<char> {
+        let shift = meta_state.shift_on();
+        if let Some(c) = letter(key_code) {
+            return Some(if shift != meta_state.caps_lock_on() {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            });
+        }
+        if let Some(&(_, base, shifted)) = SYMBOLS.iter().find(|(k, ..)| *k == key_code) {
+            return Some(if shift { shifted } else { base });
+        }
+        if let Some(&(_, c)) = NUMPAD_SYMBOLS.iter().find(|(k, _)| *k == key_code) {
+            return Some(c);
+        }
+        NUMPAD_DIGITS
+            .iter()
+            .find(|(k, ..)| *k == key_code)
+            .filter(|_| meta_state.num_lock_on())
+            .map(|&(_, c, _)| c)
+    }
+
+    pub(super) fn display_label(key_code: Keycode) -> Option<char> {
+        if let Some(c) = letter(key_code) {
+            return Some(c.to_ascii_uppercase());
+        }
+        let base = SYMBOLS
+            .iter()
+            .find(|(k, ..)| *k == key_code)
+            .map(|&(_, c, _)| c)
+            .or_else(|| {
+                NUMPAD_SYMBOLS
+                    .iter()
+                    .find(|(k, _)| *k == key_code)
+                    .map(|&(_, c)| c)
+            })
+            .or_else(|| {
+                NUMPAD_DIGITS
+                    .iter()
+                    .find(|(k, ..)| *k == key_code)
+                    .map(|&(_, c, _)| c)
+            })?;
+        // Whitespace has no printable label
+        (!base.is_whitespace()).then_some(base)
+    }
+
+    pub(super) fn fallback_action(
+        key_code: Keycode,
+        meta_state: MetaState,
+    ) -> Option<FallbackAction> {
+        let fallback = match key_code {
+            Keycode::Escape if meta_state.ctrl_on() => Keycode::Menu,
+            Keycode::Escape if meta_state.alt_on() || meta_state.meta_on() => Keycode::Home,
+            Keycode::Escape => Keycode::Back,
+            Keycode::Space if meta_state.ctrl_on() => Keycode::LanguageSwitch,
+            Keycode::Space if meta_state.alt_on() || meta_state.meta_on() => Keycode::Search,
+            _ => {
+                if let Some(&(_, _, fallback)) = NUMPAD_DIGITS
+                    .iter()
+                    .find(|(k, ..)| *k == key_code)
+                    .filter(|_| !meta_state.num_lock_on())
+                {
+                    fallback
+                } else {
+                    FALLBACKS.iter().find(|(k, _)| *k == key_code)?.1
+                }
+            }
+        };
+        // The fallback is dispatched without the modifiers that selected it
+        Some(FallbackAction {
+            key_code: fallback,
+            meta_state: MetaState(0),
+        })
+    }
+}
@@ -0,0 +1,528 @@
+//! Stylus input for drawing: pressure, tilt, hover, buttons, palm rejection and prediction
+//!
+//! [`StylusTracker`] turns the [`ToolType::Stylus`] and [`ToolType::Eraser`] pointers of a
+//! stream of motion events into [`StylusSample`]s, including the samples batched into each
+//! event's history. Finger touches that arrive while a stylus is in use, or that are large enough
+//! to be a resting palm, are reported by [`StylusTracker::is_palm()`] so that they can be
+//! ignored:
+//!
+//! ```
+//! # use ndk::event::{Axis, MotionAction, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+//! # use ndk::event::stylus::{StylusConfig, StylusPhase, StylusTracker};
+//! let pointer = |id, tool_type, x: f32| {
+//!     let mut coords = PointerCoords::new(x, 0.0);
+//!     coords.set_axis_value(Axis::Pressure, 0.5);
+//!     OwnedPointer::new(id, tool_type, coords)
+//! };
+//!
+//! let mut tracker = StylusTracker::new(StylusConfig::default());
+//! let samples = tracker.on_motion_event(&OwnedMotionEvent {
+//!     action: MotionAction::Down,
+//!     pointers: vec![pointer(0, ToolType::Stylus, 10.0)],
+//!     ..Default::default()
+//! });
+//! assert_eq!(samples[0].phase, StylusPhase::Down);
+//! assert_eq!(samples[0].pressure, 0.5);
+//!
+//! // A finger that lands while the stylus is down is the hand holding it
+//! tracker.on_motion_event(&OwnedMotionEvent {
+//!     action: MotionAction::PointerDown,
+//!     pointer_index: 1,
+//!     pointers: vec![pointer(0, ToolType::Stylus, 10.0), pointer(1, ToolType::Finger, 200.0)],
+//!     ..Default::default()
+//! });
+//! assert!(tracker.is_palm(1));
+//! ```
+
+use std::{
+    collections::VecDeque,
+    f32::consts::{FRAC_PI_2, TAU},
+    time::Duration,
+};
+
+use super::{Axis, ButtonState, MotionAction, MotionEventSource, ToolType};
+
+/// Where in its lifecycle a stylus was when a [`StylusSample`] was taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StylusPhase {
+    /// The stylus came into hover range above the screen.
+    HoverEnter,
+    /// The stylus moved while hovering.
+    Hover,
+    /// The stylus left hover range, or is about to touch the screen.
+    HoverExit,
+    /// The stylus touched the screen.
+    Down,
+    /// The stylus moved while touching the screen.
+    Move,
+    /// The stylus was lifted off the screen.
+    Up,
+    /// The stroke was canceled, and should be discarded.
+    Cancel,
+    /// The sample was extrapolated by a [`MotionPredictor`] and is not part of the stroke. Draw it
+    /// to hide latency, and replace it once the next real sample arrives.
+    Predicted,
+}
+
+/// A single stylus position, merged from the axes of a motion event.
+///
+/// Besides the raw [`Axis::Tilt`] and [`Axis::Orientation`], the direction of the stylus is also
+/// converted to the web's conventions:
+///
+/// ```
+/// # use std::f32::consts::{FRAC_PI_2, PI};
+/// # use ndk::event::{Axis, MotionAction, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+/// # use ndk::event::stylus::{StylusConfig, StylusSample, StylusTracker};
+/// let sample = |tilt, orientation| -> StylusSample {
+///     let mut coords = PointerCoords::new(0.0, 0.0);
+///     coords.set_axis_value(Axis::Tilt, tilt);
+///     coords.set_axis_value(Axis::Orientation, orientation);
+///     let pointer = OwnedPointer::new(0, ToolType::Stylus, coords);
+///     let event = OwnedMotionEvent {
+///         action: MotionAction::Down,
+///         pointers: vec![pointer],
+///         ..Default::default()
+///     };
+///     StylusTracker::new(StylusConfig::default()).on_motion_event(&event)[0]
+/// };
+/// let assert_close = |a: f32, b: f32| assert!((a - b).abs() < 1e-6, "{a} != {b}");
+///
+/// // Upright, nominally leaning towards the top of the screen, which is the negative y axis
+/// let upright = sample(0.0, 0.0);
+/// assert_close(upright.altitude, FRAC_PI_2);
+/// assert_close(upright.azimuth, 3.0 * FRAC_PI_2);
+/// // Flat on the screen, leaning to the right
+/// let flat = sample(FRAC_PI_2, FRAC_PI_2);
+/// assert_close(flat.altitude, 0.0);
+/// assert_close(flat.azimuth, 0.0);
+/// // Leaning to the left
+/// assert_close(sample(0.5, -FRAC_PI_2).azimuth, PI);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StylusSample {
+    pub pointer_id: i32,
+    /// Either [`ToolType::Stylus`] or [`ToolType::Eraser`].
+    pub tool_type: ToolType,
+    pub phase: StylusPhase,
+    /// The time of the sample, in the `java.lang.System.nanoTime()` time base.
+    pub event_time: i64,
+    pub x: f32,
+    pub y: f32,
+    /// The normalized pressure from `0.0` to `1.0`, or `0.0` while hovering.
+    pub pressure: f32,
+    /// See [`Axis::Tilt`]: the angle between the stylus and the screen normal in radians, from
+    /// `0.0` for upright to `π/2` for flat.
+    pub tilt: f32,
+    /// See [`Axis::Orientation`]: the direction the stylus leans in radians, `0.0` towards the
+    /// top of the screen and increasing clockwise, from `-π` to `π`.
+    pub orientation: f32,
+    /// [`StylusSample::orientation`] as an angle from the positive x axis increasing clockwise,
+    /// from `0.0` to `2π`, as used by the web's `PointerEvent.azimuthAngle`.
+    pub azimuth: f32,
+    /// The angle between the stylus and the screen in radians, from `0.0` for flat to `π/2` for
+    /// upright, as used by the web's `PointerEvent.altitudeAngle`.
+    pub altitude: f32,
+    /// See [`Axis::Distance`]: the distance of a hovering stylus from the screen, or `0.0` if
+    /// unknown or touching.
+    pub distance: f32,
+    /// The pressed buttons, see [`ButtonState::stylus_primary()`] and
+    /// [`ButtonState::stylus_secondary()`].
+    pub buttons: ButtonState,
+}
+
+impl StylusSample {
+    /// Reads the sample of pointer `pointer_index` from `event`, from its history if
+    /// `history_index` is set.
+    fn read(
+        event: &impl MotionEventSource,
+        pointer_index: usize,
+        history_index: Option<usize>,
+        phase: StylusPhase,
+    ) -> Self {
+        let axis = |axis| match history_index {
+            Some(history_index) => event.historical_axis_value(pointer_index, history_index, axis),
+            None => event.axis_value(pointer_index, axis),
+        };
+        let tilt = axis(Axis::Tilt);
+        let orientation = axis(Axis::Orientation);
+        Self {
+            pointer_id: event.pointer_id(pointer_index),
+            tool_type: event.tool_type(pointer_index),
+            phase,
+            event_time: match history_index {
+                Some(history_index) => event.historical_event_time(history_index),
+                None => event.event_time(),
+            },
+            x: axis(Axis::X),
+            y: axis(Axis::Y),
+            pressure: axis(Axis::Pressure),
+            tilt,
+            orientation,
+            azimuth: (orientation - FRAC_PI_2).rem_euclid(TAU),
+            altitude: FRAC_PI_2 - tilt.clamp(0.0, FRAC_PI_2),
+            distance: axis(Axis::Distance),
+            buttons: event.button_state(),
+        }
+    }
+}
+
+/// Thresholds used by [`StylusTracker`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StylusConfig {
+    /// Finger touches with a [`Axis::TouchMajor`] above this many pixels are rejected as palms.
+    pub palm_touch_major: f32,
+    /// Finger touches that start within this time after a stylus was lifted or left hover range
+    /// are rejected as palms.
+    pub palm_timeout: Duration,
+    /// How far ahead to extrapolate a [`StylusPhase::Predicted`] sample after every movement, or
+    /// [`None`] to not predict.
+    pub prediction: Option<Duration>,
+}
+
+impl Default for StylusConfig {
+    fn default() -> Self {
+        Self::from_dpi(160)
+    }
+}
+
+impl StylusConfig {
+    /// Scales the palm size threshold to a screen of `dpi` dots per inch.
+    pub fn from_dpi(dpi: u32) -> Self {
+        Self {
+            // A fingertip is about 10mm wide, the side of a hand considerably more
+            palm_touch_major: 20.0 / 25.4 * dpi as f32,
+            palm_timeout: Duration::from_millis(500),
+            prediction: None,
+        }
+    }
+}
+
+/// Extrapolates the next position of a stroke from the velocity of its most recent samples.
+///
+/// This is a simple, device-independent alternative to the platform's `MotionPredictor`.
+/// [`StylusTracker`] uses one per stylus when [`StylusConfig::prediction`] is set:
+///
+/// ```
+/// # use std::time::Duration;
+/// # use ndk::event::{MotionAction, OwnedMotionEvent, OwnedPointer, PointerCoords, ToolType};
+/// # use ndk::event::stylus::{MotionPredictor, StylusConfig, StylusPhase, StylusTracker};
+/// let event = |action, time_ms: i64, x| {
+///     let pointer = OwnedPointer::new(0, ToolType::Stylus, PointerCoords::new(x, 0.0));
+///     OwnedMotionEvent {
+///         action,
+///         event_time: time_ms * 1_000_000,
+///         pointers: vec![pointer],
+///         ..Default::default()
+///     }
+/// };
+/// let config = StylusConfig {
+///     prediction: Some(Duration::from_millis(10)),
+///     ..Default::default()
+/// };
+/// let mut tracker = StylusTracker::new(config);
+///
+/// let [down] = tracker.on_motion_event(&event(MotionAction::Down, 0, 0.0))[..] else {
+///     panic!("Expected a single sample");
+/// };
+/// // Moving at 1 pixel per millisecond
+/// let samples = tracker.on_motion_event(&event(MotionAction::Move, 10, 10.0));
+/// let [moved, predicted] = samples[..] else {
+///     panic!("Expected a sample and a prediction, got {samples:?}");
+/// };
+/// assert_eq!(moved.phase, StylusPhase::Move);
+/// assert_eq!(predicted.phase, StylusPhase::Predicted);
+/// assert_eq!((predicted.event_time, predicted.x), (20_000_000, 20.0));
+///
+/// // The same samples, fed to a predictor directly
+/// let mut predictor = MotionPredictor::new(Duration::from_millis(10));
+/// predictor.record(&down);
+/// assert_eq!(predictor.predict(), None, "A single sample has no velocity");
+/// predictor.record(&moved);
+/// assert_eq!(predictor.predict(), Some(predicted));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MotionPredictor {
+    horizon: Duration,
+    samples: VecDeque<StylusSample>,
+}
+
+impl MotionPredictor {
+    /// The number of samples the velocity is averaged over.
+    const SAMPLES: usize = 4;
+    /// Samples older than this do not contribute to the velocity.
+    const MAX_AGE: Duration = Duration::from_millis(50);
+
+    /// Creates a predictor that extrapolates `horizon` into the future.
+    pub fn new(horizon: Duration) -> Self {
+        Self {
+            horizon,
+            samples: VecDeque::with_capacity(Self::SAMPLES),
+        }
+    }
+
+    /// Adds a sample of the stroke.
+    pub fn record(&mut self, sample: &StylusSample) {
+        if self.samples.len() == Self::SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(*sample);
+    }
+
+    /// Forgets all samples, for example when a stroke ended.
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns the expected sample `horizon` after the most recent one, or [`None`] if the stroke
+    /// is too short or too old to estimate a velocity from.
+    pub fn predict(&self) -> Option<StylusSample> {
+        let last = self.samples.back()?;
+        let max_age = Self::MAX_AGE.as_nanos() as i64;
+        let first = self
+            .samples
+            .iter()
+            .find(|sample| last.event_time - sample.event_time <= max_age)?;
+        let dt = (last.event_time - first.event_time) as f32;
+        if dt <= 0.0 {
+            return None;
+        }
+        let ahead = self.horizon.as_nanos() as f32 / dt;
+        Some(StylusSample {
+            phase: StylusPhase::Predicted,
+            event_time: last.event_time + self.horizon.as_nanos() as i64,
+            x: last.x + (last.x - first.x) * ahead,
+            y: last.y + (last.y - first.y) * ahead,
+            ..*last
+        })
+    }
+}
+
+/// A stylus that is touching or hovering over the screen.
+#[derive(Clone, Debug)]
+struct ActiveStylus {
+    pointer_id: i32,
+    predictor: Option<MotionPredictor>,
+}
+
+/// Converts motion events into [`StylusSample`]s and rejects palm touches, see the
+/// [module documentation](self).
+///
+/// A hovering stylus reports samples as well, and strokes that the system canceled, for example
+/// because it turned out to be an accidental touch, end in [`StylusPhase::Cancel`]:
+///
+/// ```
+/// # use ndk::event::{MotionAction, MotionEventFlags, OwnedMotionEvent, OwnedPointer, ToolType};
+/// # use ndk::event::stylus::{StylusConfig, StylusPhase, StylusTracker};
+/// let event = |action, flags| OwnedMotionEvent {
+///     action,
+///     flags: MotionEventFlags(flags),
+///     pointers: vec![OwnedPointer { tool_type: ToolType::Stylus, ..Default::default() }],
+///     ..Default::default()
+/// };
+/// let mut tracker = StylusTracker::new(StylusConfig::default());
+/// let mut phase = |action, flags| {
+///     let samples = tracker.on_motion_event(&event(action, flags));
+///     assert_eq!(samples.len(), 1);
+///     samples[0].phase
+/// };
+///
+/// assert_eq!(phase(MotionAction::HoverEnter, 0), StylusPhase::HoverEnter);
+/// assert_eq!(phase(MotionAction::HoverMove, 0), StylusPhase::Hover);
+/// assert_eq!(phase(MotionAction::HoverExit, 0), StylusPhase::HoverExit);
+/// assert_eq!(phase(MotionAction::Down, 0), StylusPhase::Down);
+/// // MotionEvent.FLAG_CANCELED
+/// assert_eq!(phase(MotionAction::Up, 0x20), StylusPhase::Cancel);
+/// assert!(!tracker.is_stylus_active());
+/// ```
+#[derive(Clone, Debug)]
+pub struct StylusTracker {
+    config: StylusConfig,
+    styluses: Vec<ActiveStylus>,
+    /// The time the last stylus was lifted or left hover range.
+    last_stylus_time: Option<i64>,
+    /// Finger pointers that are considered palms.
+    palms: Vec<i32>,
+    /// Pointers that went up in the last event, and are forgotten on the next one.
+    released: Vec<i32>,
+}
+
+fn is_stylus(tool_type: ToolType) -> bool {
+    matches!(tool_type, ToolType::Stylus | ToolType::Eraser)
+}
+
+impl StylusTracker {
+    /// Creates a tracker that rejects palms and predicts samples according to `config`.
+    pub fn new(config: StylusConfig) -> Self {
+        Self {
+            config,
+            styluses: Vec::new(),
+            last_stylus_time: None,
+            palms: Vec::new(),
+            released: Vec::new(),
+        }
+    }
+
+    /// The configuration passed to [`new()`][Self::new()].
+    pub fn config(&self) -> &StylusConfig {
+        &self.config
+    }
+
+    /// Whether a stylus is touching or hovering over the screen.
+    pub fn is_stylus_active(&self) -> bool {
+        !self.styluses.is_empty()
+    }
+
+    /// Whether the finger pointer `pointer_id` of the last event is considered a palm, including
+    /// pointers that the system [canceled][super::MotionEventFlags::canceled()].
+    ///
+    /// Once rejected, a pointer stays rejected until it is lifted.
+    pub fn is_palm(&self, pointer_id: i32) -> bool {
+        self.palms.contains(&pointer_id)
+    }
+
+    /// Processes the next motion event, returning the samples of all stylus pointers in it.
+    ///
+    /// The history of the event is returned first, in chronological order, followed by the
+    /// current sample of each stylus and, if [`StylusConfig::prediction`] is set, the predicted
+    /// sample of each stylus that moved.
+    pub fn on_motion_event(&mut self, event: &impl MotionEventSource) -> Vec<StylusSample> {
+        for pointer_id in self.released.drain(..) {
+            self.palms.retain(|&id| id != pointer_id);
+        }
+
+        let action = event.action();
+        let action_index = event.pointer_index();
+        let canceled = event.flags().canceled();
+        let mut samples = Vec::new();
+
+        for index in 0..event.pointer_count() {
+            let is_action_pointer = index == action_index;
+            let pointer_id = event.pointer_id(index);
+            let phase = match action {
+                MotionAction::Down => StylusPhase::Down,
+                MotionAction::PointerDown if is_action_pointer => StylusPhase::Down,
+                MotionAction::Up | MotionAction::PointerUp if is_action_pointer && canceled => {
+                    StylusPhase::Cancel
+                }
+                MotionAction::Up => StylusPhase::Up,
+                MotionAction::PointerUp if is_action_pointer => StylusPhase::Up,
+                MotionAction::Cancel => StylusPhase::Cancel,
+                MotionAction::HoverEnter => StylusPhase::HoverEnter,
+                MotionAction::HoverMove => StylusPhase::Hover,
+                MotionAction::HoverExit => StylusPhase::HoverExit,
+                MotionAction::Move | MotionAction::PointerDown | MotionAction::PointerUp => {
+                    StylusPhase::Move
+                }
+                MotionAction::ButtonPress | MotionAction::ButtonRelease => {
+                    if event.pressure(index) > 0.0 {
+                        StylusPhase::Move
+                    } else {
+                        StylusPhase::Hover
+                    }
+                }
+                _ => continue,
+            };
+
+            if is_stylus(event.tool_type(index)) {
+                self.on_stylus(event, index, phase, &mut samples);
+            } else {
+                self.on_finger(event, index, phase);
+            }
+
+            if matches!(phase, StylusPhase::Up | StylusPhase::Cancel) {
+                self.released.push(pointer_id);
+            }
+        }
+
+        samples.sort_by_key(|sample| (sample.phase == StylusPhase::Predicted, sample.event_time));
+        samples
+    }
+
+    fn on_stylus(
+        &mut self,
+        event: &impl MotionEventSource,
+        index: usize,
+        phase: StylusPhase,
+        samples: &mut Vec<StylusSample>,
+    ) {
+        let pointer_id = event.pointer_id(index);
+        let position = self
+            .styluses
+            .iter()
+            .position(|stylus| stylus.pointer_id == pointer_id);
+        let stylus = match position {
+            Some(position) => &mut self.styluses[position],
+            None => {
+                self.styluses.push(ActiveStylus {
+                    pointer_id,
+                    predictor: self.config.prediction.map(MotionPredictor::new),
+                });
+                self.styluses.last_mut().unwrap()
+            }
+        };
+
+        let history_phase = match phase {
+            StylusPhase::HoverEnter | StylusPhase::Hover | StylusPhase::HoverExit => {
+                StylusPhase::Hover
+            }
+            _ => StylusPhase::Move,
+        };
+        for history_index in 0..event.history_size() {
+            let sample = StylusSample::read(event, index, Some(history_index), history_phase);
+            if let Some(predictor) = &mut stylus.predictor {
+                predictor.record(&sample);
+            }
+            samples.push(sample);
+        }
+
+        let sample = StylusSample::read(event, index, None, phase);
+        samples.push(sample);
+        if let Some(predictor) = &mut stylus.predictor {
+            match phase {
+                StylusPhase::Down => {
+                    predictor.reset();
+                    predictor.record(&sample);
+                }
+                StylusPhase::Move => {
+                    predictor.record(&sample);
+                    samples.extend(predictor.predict());
+                }
+                _ => predictor.reset(),
+            }
+        }
+
+        if matches!(
+            phase,
+            StylusPhase::HoverExit | StylusPhase::Up | StylusPhase::Cancel
+        ) {
+            self.styluses
+                .retain(|stylus| stylus.pointer_id != pointer_id);
+            self.last_stylus_time = Some(sample.event_time);
+        }
+    }
+
+    fn on_finger(&mut self, event: &impl MotionEventSource, index: usize, phase: StylusPhase) {
+        let pointer_id = event.pointer_id(index);
+        if self.palms.contains(&pointer_id) {
+            return;
+        }
+        let recent_stylus = self.last_stylus_time.map_or(false, |time| {
+            event.event_time() - time <= self.config.palm_timeout.as_nanos() as i64
+        });
+        let is_palm = match phase {
+            StylusPhase::Down => self.is_stylus_active() || recent_stylus,
+            // The system recognized the touch as accidental
+            StylusPhase::Cancel => event.flags().canceled(),
+            _ => false,
+        } || event.axis_value(index, Axis::TouchMajor) > self.config.palm_touch_major;
+        if is_palm {
+            self.palms.push(pointer_id);
+        }
+    }
+}
+
+impl Default for StylusTracker {
+    fn default() -> Self {
+        Self::new(StylusConfig::default())
+    }
+}
@@ -7,7 +7,7 @@
 #![cfg(feature = "api-level-29")]
 
 use std::convert::TryFrom;
-use std::ffi::{CStr, OsStr};
+use std::ffi::{c_char, CStr, OsStr};
 use std::fmt::{self, Write as _};
 use std::os::unix::prelude::OsStrExt;
 use std::path::Path;
@@ -15,6 +15,35 @@ use std::ptr::NonNull;
 
 use num_enum::IntoPrimitive;
 
+use crate::api_level::{weak_fn, Unsupported, SAME_API_LEVEL};
+
+weak_fn! {
+    fn AFont_getAxisCount(font: *const ffi::AFont) -> usize;
+    fn AFont_getAxisTag(font: *const ffi::AFont, axis_index: u32) -> u32;
+    fn AFont_getAxisValue(font: *const ffi::AFont, axis_index: u32) -> f32;
+    fn AFont_getCollectionIndex(font: *const ffi::AFont) -> usize;
+    fn AFont_getFontFilePath(font: *const ffi::AFont) -> *const c_char;
+    fn AFont_getLocale(font: *const ffi::AFont) -> *const c_char;
+    fn AFont_getWeight(font: *const ffi::AFont) -> u16;
+    fn AFont_isItalic(font: *const ffi::AFont) -> bool;
+    fn AFont_close(font: *mut ffi::AFont);
+    fn AFontMatcher_create() -> *mut ffi::AFontMatcher;
+    fn AFontMatcher_match(
+        matcher: *const ffi::AFontMatcher,
+        family_name: *const c_char,
+        text: *const u16,
+        text_length: u32,
+        run_length_out: *mut u32,
+    ) -> *mut ffi::AFont;
+    fn AFontMatcher_setFamilyVariant(matcher: *mut ffi::AFontMatcher, family_variant: u32);
+    fn AFontMatcher_setLocales(matcher: *mut ffi::AFontMatcher, language_tags: *const c_char);
+    fn AFontMatcher_setStyle(matcher: *mut ffi::AFontMatcher, weight: u16, italic: bool);
+    fn AFontMatcher_destroy(matcher: *mut ffi::AFontMatcher);
+    fn ASystemFontIterator_open() -> *mut ffi::ASystemFontIterator;
+    fn ASystemFontIterator_next(iterator: *mut ffi::ASystemFontIterator) -> *mut ffi::AFont;
+    fn ASystemFontIterator_close(iterator: *mut ffi::ASystemFontIterator);
+}
+
 /// An integer holding a valid font weight value between 1 and 1000.
 ///
 /// See the [`Font::weight`] definition for more details.
@@ -296,7 +325,7 @@ impl Font {
     /// // slnt: -12
     /// ```
     pub fn axis_count(&self) -> usize {
-        unsafe { ffi::AFont_getAxisCount(self.ptr.as_ptr()) }
+        unsafe { AFont_getAxisCount(self.ptr.as_ptr()).expect(SAME_API_LEVEL) }
     }
 
     /// Returns an OpenType axis tag associated with the current font.
@@ -305,14 +334,14 @@ impl Font {
     pub fn axis_tag_at(&self, idx: usize) -> AxisTag {
         // Android returns Axis Tag in big-endian.
         // See https://cs.android.com/android/platform/superproject/+/refs/heads/master:frameworks/base/native/android/system_fonts.cpp;l=197 for details
-        AxisTag(unsafe { ffi::AFont_getAxisTag(self.ptr.as_ptr(), idx as u32) })
+        AxisTag(unsafe { AFont_getAxisTag(self.ptr.as_ptr(), idx as u32).expect(SAME_API_LEVEL) })
     }
 
     /// Returns an OpenType axis value associated with the current font.
     ///
     /// See [`Font::axis_count()`] for more details.
     pub fn axis_value_at(&self, idx: usize) -> f32 {
-        unsafe { ffi::AFont_getAxisValue(self.ptr.as_ptr(), idx as u32) }
+        unsafe { AFont_getAxisValue(self.ptr.as_ptr(), idx as u32).expect(SAME_API_LEVEL) }
     }
 
     /// Returns a font collection index value associated with the current font.
@@ -321,7 +350,7 @@ impl Font {
     /// non-negative value as a font offset in the collection. This always returns 0 if the target
     /// font file is a regular font.
     pub fn collection_index(&self) -> usize {
-        unsafe { ffi::AFont_getCollectionIndex(self.ptr.as_ptr()) }
+        unsafe { AFont_getCollectionIndex(self.ptr.as_ptr()).expect(SAME_API_LEVEL) }
     }
 
     /// Returns an absolute path to the current font file.
@@ -336,7 +365,9 @@ impl Font {
     /// The file extension could be one of `*.otf`, `*.ttf`, `*.otc` or `*.ttc`.
     /// The font file specified by the returned path is guaranteed to be openable with `O_RDONLY`.
     pub fn path(&self) -> &Path {
-        let path = unsafe { CStr::from_ptr(ffi::AFont_getFontFilePath(self.ptr.as_ptr())) };
+        let path = unsafe {
+            CStr::from_ptr(AFont_getFontFilePath(self.ptr.as_ptr()).expect(SAME_API_LEVEL))
+        };
         OsStr::from_bytes(path.to_bytes()).as_ref()
     }
 
@@ -346,7 +377,7 @@ impl Font {
     ///
     /// [`Locale.forLanguageTag(java.lang.String)`]: https://developer.android.com/reference/java/util/Locale.html#forLanguageTag(java.lang.String)
     pub fn locale(&self) -> Option<&CStr> {
-        let ptr = unsafe { ffi::AFont_getLocale(self.ptr.as_ptr()) };
+        let ptr = unsafe { AFont_getLocale(self.ptr.as_ptr()).expect(SAME_API_LEVEL) };
         if ptr.is_null() {
             None
         } else {
@@ -371,18 +402,18 @@ impl Font {
     /// | 800   | Extra Bold (Ultra Bold)   | [`FontWeight::EXTRA_BOLD`]  |
     /// | 900   | Black (Heavy)             | [`FontWeight::BLACK`]       |
     pub fn weight(&self) -> FontWeight {
-        FontWeight(unsafe { ffi::AFont_getWeight(self.ptr.as_ptr()) })
+        FontWeight(unsafe { AFont_getWeight(self.ptr.as_ptr()).expect(SAME_API_LEVEL) })
     }
 
     /// Returns [`true`] if the current font is italic, otherwise returns [`false`].
     pub fn is_italic(&self) -> bool {
-        unsafe { ffi::AFont_isItalic(self.ptr.as_ptr()) }
+        unsafe { AFont_isItalic(self.ptr.as_ptr()).expect(SAME_API_LEVEL) }
     }
 }
 
 impl Drop for Font {
     fn drop(&mut self) {
-        unsafe { ffi::AFont_close(self.ptr.as_ptr()) }
+        unsafe { AFont_close(self.ptr.as_ptr()).expect(SAME_API_LEVEL) }
     }
 }
 
@@ -431,10 +462,10 @@ impl FontMatcher {
 
     /// Creates a new [`FontMatcher`] object. [`FontMatcher`] selects the best font from the
     /// parameters set by the user.
-    pub fn new() -> Self {
-        let ptr = NonNull::new(unsafe { ffi::AFontMatcher_create() })
+    pub fn new() -> Result<Self, Unsupported> {
+        let ptr = NonNull::new(unsafe { AFontMatcher_create()? })
             .expect("AFontMatcher_create returned NULL");
-        unsafe { FontMatcher::from_ptr(ptr) }
+        Ok(unsafe { FontMatcher::from_ptr(ptr) })
     }
 
     /// Performs the matching from the generic font family for the text and select one font.
@@ -463,13 +494,16 @@ impl FontMatcher {
         }
         unsafe {
             Font::from_ptr(
-                NonNull::new(ffi::AFontMatcher_match(
-                    self.ptr.as_ptr(),
-                    family_name.as_ptr(),
-                    text.as_ptr(),
-                    text.len() as _,
-                    run_length_out.map_or(std::ptr::null_mut(), |u| u),
-                ))
+                NonNull::new(
+                    AFontMatcher_match(
+                        self.ptr.as_ptr(),
+                        family_name.as_ptr(),
+                        text.as_ptr(),
+                        text.len() as _,
+                        run_length_out.map_or(std::ptr::null_mut(), |u| u),
+                    )
+                    .expect(SAME_API_LEVEL),
+                )
                 .expect("AFontMatcher_match returned NULL"),
             )
         }
@@ -479,7 +513,10 @@ impl FontMatcher {
     ///
     /// If this function is not called, the match is performed with [`FamilyVariant::Default`].
     pub fn set_family_variant(&mut self, family_variant: FamilyVariant) {
-        unsafe { ffi::AFontMatcher_setFamilyVariant(self.ptr.as_ptr(), family_variant.into()) }
+        unsafe {
+            AFontMatcher_setFamilyVariant(self.ptr.as_ptr(), family_variant.into())
+                .expect(SAME_API_LEVEL)
+        }
     }
 
     /// Sets the locale of the font to be matched.
@@ -490,20 +527,25 @@ impl FontMatcher {
     ///
     /// - `language_tags`: comma separated IETF BCP47 compliant language tags.
     pub fn set_locales(&mut self, language_tags: &CStr) {
-        unsafe { ffi::AFontMatcher_setLocales(self.ptr.as_ptr(), language_tags.as_ptr()) }
+        unsafe {
+            AFontMatcher_setLocales(self.ptr.as_ptr(), language_tags.as_ptr())
+                .expect(SAME_API_LEVEL)
+        }
     }
 
     /// Sets the style of the font to be matched.
     ///
     /// If this function is not called, the match is performed with [`FontWeight::NORMAL`] with non-italic style.
     pub fn set_style(&mut self, weight: FontWeight, italic: bool) {
-        unsafe { ffi::AFontMatcher_setStyle(self.ptr.as_ptr(), weight.to_u16(), italic) }
+        unsafe {
+            AFontMatcher_setStyle(self.ptr.as_ptr(), weight.to_u16(), italic).expect(SAME_API_LEVEL)
+        }
     }
 }
 
 impl Drop for FontMatcher {
     fn drop(&mut self) {
-        unsafe { ffi::AFontMatcher_destroy(self.ptr.as_ptr()) }
+        unsafe { AFontMatcher_destroy(self.ptr.as_ptr()).expect(SAME_API_LEVEL) }
     }
 }
 
@@ -530,8 +572,11 @@ impl SystemFontIterator {
     }
 
     /// Creates a system font iterator.
+    ///
+    /// Returns [`None`] on failure, or when the `weak-linking` feature is enabled and the device
+    /// does not support enumerating system fonts.
     pub fn new() -> Option<Self> {
-        NonNull::new(unsafe { ffi::ASystemFontIterator_open() })
+        NonNull::new(unsafe { ASystemFontIterator_open() }.ok()?)
             .map(|p| unsafe { SystemFontIterator::from_ptr(p) })
     }
 }
@@ -540,13 +585,13 @@ impl Iterator for SystemFontIterator {
     type Item = Font;
 
     fn next(&mut self) -> Option<Self::Item> {
-        NonNull::new(unsafe { ffi::ASystemFontIterator_next(self.ptr.as_ptr()) })
+        NonNull::new(unsafe { ASystemFontIterator_next(self.ptr.as_ptr()).expect(SAME_API_LEVEL) })
             .map(|p| unsafe { Font::from_ptr(p) })
     }
 }
 
 impl Drop for SystemFontIterator {
     fn drop(&mut self) {
-        unsafe { ffi::ASystemFontIterator_close(self.ptr.as_ptr()) }
+        unsafe { ASystemFontIterator_close(self.ptr.as_ptr()).expect(SAME_API_LEVEL) }
     }
 }
@@ -10,14 +10,77 @@ use std::{
     ops::Deref,
     os::{
         fd::{AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd},
-        raw::c_void,
+        raw::{c_int, c_void},
     },
     ptr::NonNull,
 };
 
 use jni_sys::{jobject, JNIEnv};
 
-use super::{hardware_buffer_format::HardwareBufferFormat, utils::status_to_io_result};
+use super::{
+    api_level::{weak_fn, Unsupported, SAME_API_LEVEL},
+    hardware_buffer_format::HardwareBufferFormat,
+    utils::status_to_io_result,
+};
+
+weak_fn! {
+    fn AHardwareBuffer_acquire(buffer: *mut ffi::AHardwareBuffer);
+    fn AHardwareBuffer_allocate(
+        desc: *const ffi::AHardwareBuffer_Desc,
+        out_buffer: *mut *mut ffi::AHardwareBuffer,
+    ) -> c_int;
+    fn AHardwareBuffer_describe(
+        buffer: *const ffi::AHardwareBuffer,
+        out_desc: *mut ffi::AHardwareBuffer_Desc,
+    );
+    fn AHardwareBuffer_fromHardwareBuffer(
+        env: *mut JNIEnv,
+        hardware_buffer_obj: jobject,
+    ) -> *mut ffi::AHardwareBuffer;
+    #[cfg(feature = "api-level-31")]
+    fn AHardwareBuffer_getId(buffer: *const ffi::AHardwareBuffer, out_id: *mut u64) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn AHardwareBuffer_isSupported(desc: *const ffi::AHardwareBuffer_Desc) -> c_int;
+    fn AHardwareBuffer_lock(
+        buffer: *mut ffi::AHardwareBuffer,
+        usage: u64,
+        fence: i32,
+        rect: *const ffi::ARect,
+        out_virtual_address: *mut *mut c_void,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn AHardwareBuffer_lockAndGetInfo(
+        buffer: *mut ffi::AHardwareBuffer,
+        usage: u64,
+        fence: i32,
+        rect: *const ffi::ARect,
+        out_virtual_address: *mut *mut c_void,
+        out_bytes_per_pixel: *mut i32,
+        out_bytes_per_stride: *mut i32,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn AHardwareBuffer_lockPlanes(
+        buffer: *mut ffi::AHardwareBuffer,
+        usage: u64,
+        fence: i32,
+        rect: *const ffi::ARect,
+        out_planes: *mut ffi::AHardwareBuffer_Planes,
+    ) -> c_int;
+    fn AHardwareBuffer_recvHandleFromUnixSocket(
+        socket_fd: c_int,
+        out_buffer: *mut *mut ffi::AHardwareBuffer,
+    ) -> c_int;
+    fn AHardwareBuffer_release(buffer: *mut ffi::AHardwareBuffer);
+    fn AHardwareBuffer_sendHandleToUnixSocket(
+        buffer: *const ffi::AHardwareBuffer,
+        socket_fd: c_int,
+    ) -> c_int;
+    fn AHardwareBuffer_toHardwareBuffer(
+        env: *mut JNIEnv,
+        hardware_buffer: *mut ffi::AHardwareBuffer,
+    ) -> jobject;
+    fn AHardwareBuffer_unlock(buffer: *mut ffi::AHardwareBuffer, fence: *mut i32) -> c_int;
+}
 
 bitflags::bitflags! {
     /// Buffer usage flags, specifying how the buffer will be accessed.
@@ -197,9 +260,11 @@ impl HardwareBufferUsage {
 
 pub type Rect = ffi::ARect;
 
-fn construct<T>(with_ptr: impl FnOnce(*mut T) -> i32) -> Result<T> {
+fn construct<T>(
+    with_ptr: impl FnOnce(*mut T) -> std::result::Result<i32, Unsupported>,
+) -> Result<T> {
     let mut result = MaybeUninit::uninit();
-    let status = with_ptr(result.as_mut_ptr());
+    let status = with_ptr(result.as_mut_ptr())?;
     status_to_io_result(status).map(|()| unsafe { result.assume_init() })
 }
 
@@ -262,7 +327,7 @@ impl HardwareBuffer {
     /// results are undefined and may include program termination.
     pub fn allocate(desc: HardwareBufferDesc) -> Result<HardwareBufferRef> {
         unsafe {
-            let ptr = construct(|res| ffi::AHardwareBuffer_allocate(&desc.into_native(), res))?;
+            let ptr = construct(|res| AHardwareBuffer_allocate(&desc.into_native(), res))?;
 
             Ok(HardwareBufferRef::from_ptr(NonNull::new_unchecked(ptr)))
         }
@@ -278,24 +343,30 @@ impl HardwareBuffer {
     /// is closed, explicitly or by the garbage collector, be sure to retrieve a strong reference
     /// using [`HardwareBuffer::acquire()`].
     ///
+    /// Returns [`Unsupported`] when the `weak-linking` feature is enabled and the device does not
+    /// support [`HardwareBuffer`].
+    ///
     /// [Java `HardwareBuffer`]: https://developer.android.com/reference/android/hardware/HardwareBuffer
-    pub unsafe fn from_jni(env: *mut JNIEnv, hardware_buffer: jobject) -> Self {
-        let ptr = ffi::AHardwareBuffer_fromHardwareBuffer(env, hardware_buffer);
+    pub unsafe fn from_jni(
+        env: *mut JNIEnv,
+        hardware_buffer: jobject,
+    ) -> std::result::Result<Self, Unsupported> {
+        let ptr = AHardwareBuffer_fromHardwareBuffer(env, hardware_buffer)?;
 
-        Self::from_ptr(NonNull::new_unchecked(ptr))
+        Ok(Self::from_ptr(NonNull::new_unchecked(ptr)))
     }
 
     /// # Safety
     /// By calling this function, you assert that `env` is a valid pointer to a [`JNIEnv`].
     pub unsafe fn to_jni(&self, env: *mut JNIEnv) -> jobject {
-        ffi::AHardwareBuffer_toHardwareBuffer(env, self.as_ptr())
+        AHardwareBuffer_toHardwareBuffer(env, self.as_ptr()).expect(SAME_API_LEVEL)
     }
 
     /// Return a description of the [`HardwareBuffer`] in the passed [`HardwareBufferDesc`] struct.
     pub fn describe(&self) -> HardwareBufferDesc {
         let desc = unsafe {
             let mut result = MaybeUninit::uninit();
-            ffi::AHardwareBuffer_describe(self.as_ptr(), result.as_mut_ptr());
+            AHardwareBuffer_describe(self.as_ptr(), result.as_mut_ptr()).expect(SAME_API_LEVEL);
             result.assume_init()
         };
 
@@ -321,17 +392,20 @@ impl HardwareBuffer {
     /// The return value of this function may depend on all fields in the description, except
     /// [`HardwareBufferDesc::stride`], which is always ignored. For example, some implementations
     /// have implementation-defined limits on texture size and layer count.
+    ///
+    /// Returns [`Unsupported`] when the `weak-linking` feature is enabled and the device does not
+    /// support this query.
     #[cfg(feature = "api-level-29")]
-    pub fn is_supported(desc: HardwareBufferDesc) -> bool {
-        let res = unsafe { ffi::AHardwareBuffer_isSupported(&desc.into_native()) };
-        res == 1
+    pub fn is_supported(desc: HardwareBufferDesc) -> std::result::Result<bool, Unsupported> {
+        let res = unsafe { AHardwareBuffer_isSupported(&desc.into_native())? };
+        Ok(res == 1)
     }
 
     /// Get the system-wide unique id for this [`HardwareBuffer`].
     #[cfg(feature = "api-level-31")]
     #[doc(alias = "AHardwareBuffer_getId")]
     pub fn id(&self) -> Result<u64> {
-        construct(|res| unsafe { ffi::AHardwareBuffer_getId(self.as_ptr(), res) })
+        construct(|res| unsafe { AHardwareBuffer_getId(self.as_ptr(), res) })
     }
 
     /// Lock the [`HardwareBuffer`] for direct CPU access.
@@ -381,7 +455,7 @@ impl HardwareBuffer {
             None => std::ptr::null(),
         };
         construct(|res| unsafe {
-            ffi::AHardwareBuffer_lock(self.as_ptr(), usage.bits(), fence, rect, res)
+            AHardwareBuffer_lock(self.as_ptr(), usage.bits(), fence, rect, res)
         })
     }
 
@@ -408,7 +482,7 @@ impl HardwareBuffer {
         let mut bytes_per_pixel = MaybeUninit::uninit();
         let mut bytes_per_stride = MaybeUninit::uninit();
         let status = unsafe {
-            ffi::AHardwareBuffer_lockAndGetInfo(
+            AHardwareBuffer_lockAndGetInfo(
                 self.as_ptr(),
                 usage.bits(),
                 fence,
@@ -416,7 +490,7 @@ impl HardwareBuffer {
                 virtual_address.as_mut_ptr(),
                 bytes_per_pixel.as_mut_ptr(),
                 bytes_per_stride.as_mut_ptr(),
-            )
+            )?
         };
         status_to_io_result(status).map(|()| unsafe {
             LockedPlaneInfo {
@@ -457,7 +531,7 @@ impl HardwareBuffer {
             None => std::ptr::null(),
         };
         let planes = construct(|res| unsafe {
-            ffi::AHardwareBuffer_lockPlanes(self.as_ptr(), usage.bits(), fence, rect, res)
+            AHardwareBuffer_lockPlanes(self.as_ptr(), usage.bits(), fence, rect, res)
         })?;
 
         Ok(HardwareBufferPlanes {
@@ -472,7 +546,7 @@ impl HardwareBuffer {
     /// will block until all work is completed. See [`unlock_async()`][Self::unlock_async()] for
     /// a non-blocking variant that returns a file descriptor to be signaled on unlocking instead.
     pub fn unlock(&self) -> Result<()> {
-        let status = unsafe { ffi::AHardwareBuffer_unlock(self.as_ptr(), std::ptr::null_mut()) };
+        let status = unsafe { AHardwareBuffer_unlock(self.as_ptr(), std::ptr::null_mut())? };
         status_to_io_result(status)
     }
 
@@ -483,7 +557,7 @@ impl HardwareBuffer {
     /// descriptor once it's no longer needed. See [`unlock()`][Self::unlock()] for a variant that
     /// blocks instead.
     pub fn unlock_async(&self) -> Result<Option<OwnedFd>> {
-        let fence = construct(|res| unsafe { ffi::AHardwareBuffer_unlock(self.as_ptr(), res) })?;
+        let fence = construct(|res| unsafe { AHardwareBuffer_unlock(self.as_ptr(), res) })?;
         Ok(match fence {
             -1 => None,
             fence => Some(unsafe { OwnedFd::from_raw_fd(fence) }),
@@ -498,7 +572,7 @@ impl HardwareBuffer {
     pub fn recv_handle_from_unix_socket(socket_fd: BorrowedFd<'_>) -> Result<Self> {
         unsafe {
             let ptr = construct(|res| {
-                ffi::AHardwareBuffer_recvHandleFromUnixSocket(socket_fd.as_raw_fd(), res)
+                AHardwareBuffer_recvHandleFromUnixSocket(socket_fd.as_raw_fd(), res)
             })?;
 
             Ok(Self::from_ptr(NonNull::new_unchecked(ptr)))
@@ -512,7 +586,7 @@ impl HardwareBuffer {
     /// [`std::os::unix::io::AsFd::as_fd()`] implementation.
     pub fn send_handle_to_unix_socket(&self, socket_fd: BorrowedFd<'_>) -> Result<()> {
         let status = unsafe {
-            ffi::AHardwareBuffer_sendHandleToUnixSocket(self.as_ptr(), socket_fd.as_raw_fd())
+            AHardwareBuffer_sendHandleToUnixSocket(self.as_ptr(), socket_fd.as_raw_fd())?
         };
         status_to_io_result(status)
     }
@@ -523,7 +597,7 @@ impl HardwareBuffer {
     /// by [`HardwareBufferRef`], is [`drop()`]ped.
     pub fn acquire(&self) -> HardwareBufferRef {
         unsafe {
-            ffi::AHardwareBuffer_acquire(self.as_ptr());
+            AHardwareBuffer_acquire(self.as_ptr()).expect(SAME_API_LEVEL);
             HardwareBufferRef::from_ptr(self.inner)
         }
     }
@@ -562,7 +636,7 @@ impl Deref for HardwareBufferRef {
 
 impl Drop for HardwareBufferRef {
     fn drop(&mut self) {
-        unsafe { ffi::AHardwareBuffer_release(self.inner.as_ptr()) }
+        unsafe { AHardwareBuffer_release(self.inner.as_ptr()) }.expect(SAME_API_LEVEL)
     }
 }
 
@@ -69,7 +69,7 @@ pub enum HardwareBufferFormat {
 
 impl HardwareBufferFormat {
     /// Returns [`None`] when there is no immediate byte size available for this format, for
-    /// example on planar buffer formats.
+    /// example on planar buffer formats. See [`Self::plane_layouts()`] for those.
     pub fn bytes_per_pixel(self) -> Option<usize> {
         Some(match self {
             Self::R8G8B8A8_UNORM | Self::R8G8B8X8_UNORM => 4,
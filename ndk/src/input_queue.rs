@@ -2,18 +2,30 @@
 //!
 //! [`AInputQueue`]: https://developer.android.com/ndk/reference/group/input#ainputqueue
 
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt;
 use std::io::Result;
-use std::os::raw::c_int;
+use std::marker::PhantomData;
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::ptr::{self, NonNull};
 
 #[cfg(feature = "api-level-33")]
 use jni_sys::{jobject, JNIEnv};
 
+#[cfg(feature = "api-level-33")]
+use crate::api_level::weak_fn;
 use crate::event::InputEvent;
 #[cfg(doc)]
 use crate::event::KeyEvent;
-use crate::looper::ForeignLooper;
-use crate::utils::status_to_io_result;
+use crate::looper::{ForeignLooper, ThreadLooper};
+use crate::utils::{abort_on_panic, status_to_io_result};
+
+#[cfg(feature = "api-level-33")]
+weak_fn! {
+    fn AInputQueue_fromJava(env: *mut JNIEnv, input_queue: jobject) -> *mut ffi::AInputQueue;
+}
 
 /// A native [`AInputQueue *`]
 ///
@@ -51,11 +63,14 @@ impl InputQueue {
     /// reference to the Java object and that it has not been disposed before using the returned
     /// object.
     ///
+    /// Returns [`None`] when the `weak-linking` feature is enabled and the device does not support
+    /// this function.
+    ///
     /// [`android.view.InputQueue`]: https://developer.android.com/reference/android/view/InputQueue
     #[cfg(feature = "api-level-33")]
     #[doc(alias = "AInputQueue_fromJava")]
     pub unsafe fn from_java(env: *mut JNIEnv, input_queue: jobject) -> Option<Self> {
-        let ptr = unsafe { ffi::AInputQueue_fromJava(env, input_queue) };
+        let ptr = unsafe { AInputQueue_fromJava(env, input_queue) }.ok()?;
         Some(Self::from_ptr(NonNull::new(ptr)?))
     }
 
@@ -140,4 +155,169 @@ impl InputQueue {
     pub fn detach_looper(&self) {
         unsafe { ffi::AInputQueue_detachLooper(self.ptr.as_ptr()) }
     }
+
+    /// Takes all available events from the queue and passes them to `handler`, which returns
+    /// whether it handled the event.
+    ///
+    /// Key events are [pre-dispatched][InputQueue::pre_dispatch()] first, and skipped if the IME
+    /// consumed them. Every other event is [finished][InputQueue::finish_event()] exactly once
+    /// with the result of `handler`, or as unhandled if `handler` panics.
+    ///
+    /// Returns the number of events passed to `handler`.
+    pub fn dispatch_events(&self, mut handler: impl FnMut(&InputEvent) -> bool) -> Result<usize> {
+        /// Finishes the event when dropped, including while unwinding from a panicking handler.
+        struct Finish<'a> {
+            queue: &'a InputQueue,
+            event: Option<InputEvent>,
+            handled: bool,
+        }
+
+        impl Drop for Finish<'_> {
+            fn drop(&mut self) {
+                if let Some(event) = self.event.take() {
+                    self.queue.finish_event(event, self.handled);
+                }
+            }
+        }
+
+        let mut count = 0;
+        while let Some(event) = self.event()? {
+            let event = match event {
+                InputEvent::KeyEvent(_) => match self.pre_dispatch(event) {
+                    Some(event) => event,
+                    None => continue,
+                },
+                event => event,
+            };
+            let mut finish = Finish {
+                queue: self,
+                event: Some(event),
+                handled: false,
+            };
+            finish.handled = handler(finish.event.as_ref().unwrap());
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Attaches this queue to `looper` so that all events are
+    /// [dispatched][InputQueue::dispatch_events()] to `handler` whenever the looper is polled,
+    /// until the returned [`InputQueueDispatcher`] is dropped.
+    ///
+    /// A panic in `handler` cannot unwind through the looper. Instead, it is caught, and the
+    /// event is finished as unhandled. Use [`InputQueueDispatcher::take_panic()`] after polling to
+    /// propagate it with [`std::panic::resume_unwind()`].
+    ///
+    /// `handler` must be `'static` because the looper keeps calling it for as long as the
+    /// dispatcher exists, which [`std::mem::forget()`] can extend indefinitely. If `handler` polls
+    /// the same looper itself, it is not called again recursively: events that arrive meanwhile
+    /// stay in the queue and are dispatched once `handler` returns.
+    ///
+    /// ```no_run
+    /// # use ndk::{input_queue::InputQueue, looper::ThreadLooper};
+    /// # fn run(queue: &InputQueue) {
+    /// let looper = ThreadLooper::for_thread().unwrap();
+    /// let dispatcher = queue.dispatcher(&looper, |event| {
+    ///     log::info!("Received {event:?}");
+    ///     false
+    /// });
+    /// loop {
+    ///     looper.poll_all().unwrap();
+    ///     if let Some(panic) = dispatcher.take_panic() {
+    ///         std::panic::resume_unwind(panic);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[doc(alias = "AInputQueue_attachLooper")]
+    pub fn dispatcher<F: FnMut(&InputEvent) -> bool + 'static>(
+        &self,
+        looper: &ThreadLooper,
+        handler: F,
+    ) -> InputQueueDispatcher<'_, F> {
+        extern "C" fn callback<F: FnMut(&InputEvent) -> bool>(
+            _fd: c_int,
+            _events: c_int,
+            data: *mut c_void,
+        ) -> c_int {
+            abort_on_panic(|| {
+                // Only ever borrowed shared, because the handler may poll the looper and re-enter
+                let state = unsafe { &*data.cast::<DispatchState<F>>() };
+                let Ok(mut handler) = state.handler.try_borrow_mut() else {
+                    // Re-entered from the handler, the outer call dispatches the remaining events
+                    return 1;
+                };
+                let queue = unsafe { InputQueue::from_ptr(state.queue) };
+                match catch_unwind(AssertUnwindSafe(|| queue.dispatch_events(&mut *handler))) {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => log::error!("Failed to read from InputQueue: {e}"),
+                    Err(panic) => state.panic.set(Some(panic)),
+                }
+                // Keep the callback registered until the dispatcher detaches it
+                1
+            })
+        }
+
+        let state = Box::into_raw(Box::new(DispatchState {
+            queue: self.ptr,
+            handler: RefCell::new(handler),
+            panic: Cell::new(None),
+        }));
+        unsafe {
+            ffi::AInputQueue_attachLooper(
+                self.ptr.as_ptr(),
+                looper.as_foreign().ptr().as_ptr(),
+                ffi::ALOOPER_POLL_CALLBACK,
+                Some(callback::<F>),
+                state.cast(),
+            )
+        }
+        InputQueueDispatcher {
+            queue: self,
+            state,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The state shared between an [`InputQueueDispatcher`] and its looper callback.
+struct DispatchState<F> {
+    queue: NonNull<ffi::AInputQueue>,
+    handler: RefCell<F>,
+    panic: Cell<Option<Box<dyn Any + Send>>>,
+}
+
+/// Dispatches the events of an [`InputQueue`] while its [`ThreadLooper`] is polled, created by
+/// [`InputQueue::dispatcher()`].
+///
+/// Dropping the dispatcher [detaches][InputQueue::detach_looper()] the queue from the looper.
+pub struct InputQueueDispatcher<'a, F> {
+    queue: &'a InputQueue,
+    state: *mut DispatchState<F>,
+    // The callback runs on the looper thread, so the handler must not move to another thread
+    _marker: PhantomData<*mut ()>,
+}
+
+impl<F> fmt::Debug for InputQueueDispatcher<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InputQueueDispatcher")
+            .field("queue", &self.queue)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F> InputQueueDispatcher<'_, F> {
+    /// Returns the payload of the last panic of the handler, if it panicked since the last call.
+    pub fn take_panic(&self) -> Option<Box<dyn Any + Send>> {
+        // The callback only runs while the looper is polled on this thread, not concurrently
+        unsafe { (*self.state).panic.take() }
+    }
+}
+
+impl<F> Drop for InputQueueDispatcher<'_, F> {
+    #[doc(alias = "AInputQueue_detachLooper")]
+    fn drop(&mut self) {
+        self.queue.detach_looper();
+        drop(unsafe { Box::from_raw(self.state) });
+    }
 }
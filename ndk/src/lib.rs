@@ -11,6 +11,7 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod api_level;
 pub mod asset;
 pub mod audio;
 pub mod bitmap;
@@ -26,7 +27,11 @@ pub mod media;
 pub mod media_error;
 pub mod native_activity;
 pub mod native_window;
+pub mod net;
+pub mod nnapi;
 pub mod performance_hint;
+pub mod permission;
+pub mod pixel_format;
 pub mod shared_memory;
 pub mod surface_texture;
 pub mod sync;
@@ -5,19 +5,148 @@
 #![cfg(feature = "api-level-24")]
 
 #[cfg(feature = "api-level-26")]
-use std::os::fd::{FromRawFd, IntoRawFd, OwnedFd};
-use std::{ffi::c_void, fmt, mem::MaybeUninit, ptr::NonNull};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::{
+    ffi::{c_int, c_void},
+    fmt,
+    mem::MaybeUninit,
+    ptr::NonNull,
+};
 
 use num_enum::{FromPrimitive, IntoPrimitive};
 
+use crate::api_level::{weak_fn, Unsupported, SAME_API_LEVEL};
 #[cfg(feature = "api-level-26")]
 use crate::hardware_buffer::{HardwareBuffer, HardwareBufferUsage};
 use crate::media_error::{construct, construct_never_null, MediaError, Result};
 use crate::native_window::NativeWindow;
+use crate::pixel_format::{
+    yuv_420_888_to_rgba8888, PixelConversionError, Region, YuvConversion, YuvPlanes,
+};
 use crate::utils::abort_on_panic;
 #[cfg(feature = "api-level-34")]
 use crate::{data_space::DataSpace, hardware_buffer_format::HardwareBufferFormat};
 
+weak_fn! {
+    fn AImageReader_acquireLatestImage(
+        reader: *mut ffi::AImageReader,
+        image: *mut *mut ffi::AImage,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-26")]
+    fn AImageReader_acquireLatestImageAsync(
+        reader: *mut ffi::AImageReader,
+        image: *mut *mut ffi::AImage,
+        acquire_fence_fd: *mut c_int,
+    ) -> ffi::media_status_t;
+    fn AImageReader_acquireNextImage(
+        reader: *mut ffi::AImageReader,
+        image: *mut *mut ffi::AImage,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-26")]
+    fn AImageReader_acquireNextImageAsync(
+        reader: *mut ffi::AImageReader,
+        image: *mut *mut ffi::AImage,
+        acquire_fence_fd: *mut c_int,
+    ) -> ffi::media_status_t;
+    fn AImageReader_delete(reader: *mut ffi::AImageReader);
+    fn AImageReader_getFormat(
+        reader: *const ffi::AImageReader,
+        format: *mut i32,
+    ) -> ffi::media_status_t;
+    fn AImageReader_getHeight(
+        reader: *const ffi::AImageReader,
+        height: *mut i32,
+    ) -> ffi::media_status_t;
+    fn AImageReader_getMaxImages(
+        reader: *const ffi::AImageReader,
+        max_images: *mut i32,
+    ) -> ffi::media_status_t;
+    fn AImageReader_getWidth(
+        reader: *const ffi::AImageReader,
+        width: *mut i32,
+    ) -> ffi::media_status_t;
+    fn AImageReader_getWindow(
+        reader: *mut ffi::AImageReader,
+        window: *mut *mut ffi::ANativeWindow,
+    ) -> ffi::media_status_t;
+    fn AImageReader_new(
+        width: i32,
+        height: i32,
+        format: i32,
+        max_images: i32,
+        reader: *mut *mut ffi::AImageReader,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-34")]
+    fn AImageReader_newWithDataSpace(
+        width: i32,
+        height: i32,
+        usage: u64,
+        max_images: i32,
+        hardware_buffer_format: u32,
+        data_space: i32,
+        reader: *mut *mut ffi::AImageReader,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-26")]
+    fn AImageReader_newWithUsage(
+        width: i32,
+        height: i32,
+        format: i32,
+        usage: u64,
+        max_images: i32,
+        reader: *mut *mut ffi::AImageReader,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-26")]
+    fn AImageReader_setBufferRemovedListener(
+        reader: *mut ffi::AImageReader,
+        listener: *mut ffi::AImageReader_BufferRemovedListener,
+    ) -> ffi::media_status_t;
+    fn AImageReader_setImageListener(
+        reader: *mut ffi::AImageReader,
+        listener: *mut ffi::AImageReader_ImageListener,
+    ) -> ffi::media_status_t;
+    fn AImage_delete(image: *mut ffi::AImage);
+    #[cfg(feature = "api-level-26")]
+    fn AImage_deleteAsync(image: *mut ffi::AImage, release_fence_fd: c_int);
+    fn AImage_getCropRect(
+        image: *const ffi::AImage,
+        rect: *mut ffi::AImageCropRect,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-34")]
+    fn AImage_getDataSpace(image: *const ffi::AImage, data_space: *mut i32) -> ffi::media_status_t;
+    fn AImage_getFormat(image: *const ffi::AImage, format: *mut i32) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-26")]
+    fn AImage_getHardwareBuffer(
+        image: *const ffi::AImage,
+        buffer: *mut *mut ffi::AHardwareBuffer,
+    ) -> ffi::media_status_t;
+    fn AImage_getHeight(image: *const ffi::AImage, height: *mut i32) -> ffi::media_status_t;
+    fn AImage_getNumberOfPlanes(
+        image: *const ffi::AImage,
+        num_planes: *mut i32,
+    ) -> ffi::media_status_t;
+    fn AImage_getPlaneData(
+        image: *const ffi::AImage,
+        plane_idx: c_int,
+        data: *mut *mut u8,
+        data_length: *mut c_int,
+    ) -> ffi::media_status_t;
+    fn AImage_getPlanePixelStride(
+        image: *const ffi::AImage,
+        plane_idx: c_int,
+        pixel_stride: *mut i32,
+    ) -> ffi::media_status_t;
+    fn AImage_getPlaneRowStride(
+        image: *const ffi::AImage,
+        plane_idx: c_int,
+        row_stride: *mut i32,
+    ) -> ffi::media_status_t;
+    fn AImage_getTimestamp(
+        image: *const ffi::AImage,
+        timestamp_ns: *mut i64,
+    ) -> ffi::media_status_t;
+    fn AImage_getWidth(image: *const ffi::AImage, width: *mut i32) -> ffi::media_status_t;
+}
+
 #[repr(i32)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
 #[allow(non_camel_case_types)]
@@ -87,10 +216,12 @@ impl<T> AcquireResult<T> {
 impl AcquireResult<Image> {
     /// Inlined version of [`construct_never_null()`] with IMGREADER-specific result mapping.
     fn construct_never_null(
-        with_ptr: impl FnOnce(*mut *mut ffi::AImage) -> ffi::media_status_t,
+        with_ptr: impl FnOnce(
+            *mut *mut ffi::AImage,
+        ) -> std::result::Result<ffi::media_status_t, Unsupported>,
     ) -> Result<Self> {
         let mut result = MaybeUninit::uninit();
-        let status = with_ptr(result.as_mut_ptr());
+        let status = with_ptr(result.as_mut_ptr())?;
         match status {
             ffi::media_status_t::AMEDIA_IMGREADER_NO_BUFFER_AVAILABLE => {
                 Ok(Self::NoBufferAvailable)
@@ -155,7 +286,7 @@ impl ImageReader {
     #[doc(alias = "AImageReader_new")]
     pub fn new(width: i32, height: i32, format: ImageFormat, max_images: i32) -> Result<Self> {
         let inner = construct_never_null(|res| unsafe {
-            ffi::AImageReader_new(width, height, format.into(), max_images, res)
+            AImageReader_new(width, height, format.into(), max_images, res)
         })?;
 
         Ok(Self::from_ptr(inner))
@@ -171,14 +302,7 @@ impl ImageReader {
         max_images: i32,
     ) -> Result<Self> {
         let inner = construct_never_null(|res| unsafe {
-            ffi::AImageReader_newWithUsage(
-                width,
-                height,
-                format.into(),
-                usage.bits(),
-                max_images,
-                res,
-            )
+            AImageReader_newWithUsage(width, height, format.into(), usage.bits(), max_images, res)
         })?;
 
         Ok(Self::from_ptr(inner))
@@ -195,7 +319,7 @@ impl ImageReader {
         data_space: DataSpace,
     ) -> Result<Self> {
         let inner = construct_never_null(|res| unsafe {
-            ffi::AImageReader_newWithDataSpace(
+            AImageReader_newWithDataSpace(
                 width,
                 height,
                 usage.bits(),
@@ -232,7 +356,7 @@ impl ImageReader {
             context: ptr as _,
             onImageAvailable: Some(on_image_available),
         };
-        let status = unsafe { ffi::AImageReader_setImageListener(self.as_ptr(), &mut listener) };
+        let status = unsafe { AImageReader_setImageListener(self.as_ptr(), &mut listener)? };
 
         // keep listener alive until Drop or new listener is assigned
         self.image_cb = Some(boxed);
@@ -265,7 +389,7 @@ impl ImageReader {
             onBufferRemoved: Some(on_buffer_removed),
         };
         let status =
-            unsafe { ffi::AImageReader_setBufferRemovedListener(self.as_ptr(), &mut listener) };
+            unsafe { AImageReader_setBufferRemovedListener(self.as_ptr(), &mut listener)? };
 
         // keep listener alive until Drop or new listener is assigned
         self.buffer_removed_cb = Some(boxed);
@@ -279,36 +403,36 @@ impl ImageReader {
     #[doc(alias = "AImageReader_getWindow")]
     pub fn window(&self) -> Result<NativeWindow> {
         unsafe {
-            let ptr = construct_never_null(|res| ffi::AImageReader_getWindow(self.as_ptr(), res))?;
+            let ptr = construct_never_null(|res| AImageReader_getWindow(self.as_ptr(), res))?;
             Ok(NativeWindow::clone_from_ptr(ptr))
         }
     }
 
     #[doc(alias = "AImageReader_getWidth")]
     pub fn width(&self) -> Result<i32> {
-        construct(|res| unsafe { ffi::AImageReader_getWidth(self.as_ptr(), res) })
+        construct(|res| unsafe { AImageReader_getWidth(self.as_ptr(), res) })
     }
 
     #[doc(alias = "AImageReader_getHeight")]
     pub fn height(&self) -> Result<i32> {
-        construct(|res| unsafe { ffi::AImageReader_getHeight(self.as_ptr(), res) })
+        construct(|res| unsafe { AImageReader_getHeight(self.as_ptr(), res) })
     }
 
     #[doc(alias = "AImageReader_getFormat")]
     pub fn format(&self) -> Result<ImageFormat> {
-        let format = construct(|res| unsafe { ffi::AImageReader_getFormat(self.as_ptr(), res) })?;
+        let format = construct(|res| unsafe { AImageReader_getFormat(self.as_ptr(), res) })?;
         Ok(format.into())
     }
 
     #[doc(alias = "AImageReader_getMaxImages")]
     pub fn max_images(&self) -> Result<i32> {
-        construct(|res| unsafe { ffi::AImageReader_getMaxImages(self.as_ptr(), res) })
+        construct(|res| unsafe { AImageReader_getMaxImages(self.as_ptr(), res) })
     }
 
     #[doc(alias = "AImageReader_acquireNextImage")]
     pub fn acquire_next_image(&self) -> Result<AcquireResult<Image>> {
         AcquireResult::construct_never_null(|res| unsafe {
-            ffi::AImageReader_acquireNextImage(self.as_ptr(), res)
+            AImageReader_acquireNextImage(self.as_ptr(), res)
         })
     }
 
@@ -326,7 +450,7 @@ impl ImageReader {
     ) -> Result<AcquireResult<(Image, Option<OwnedFd>)>> {
         let mut fence = MaybeUninit::uninit();
         AcquireResult::construct_never_null(|res| {
-            ffi::AImageReader_acquireNextImageAsync(self.as_ptr(), res, fence.as_mut_ptr())
+            AImageReader_acquireNextImageAsync(self.as_ptr(), res, fence.as_mut_ptr())
         })
         .map(|result| {
             result.map(|image| match fence.assume_init() {
@@ -339,7 +463,7 @@ impl ImageReader {
     #[doc(alias = "AImageReader_acquireLatestImage")]
     pub fn acquire_latest_image(&self) -> Result<AcquireResult<Image>> {
         AcquireResult::construct_never_null(|res| unsafe {
-            ffi::AImageReader_acquireLatestImage(self.as_ptr(), res)
+            AImageReader_acquireLatestImage(self.as_ptr(), res)
         })
     }
 
@@ -357,7 +481,7 @@ impl ImageReader {
     ) -> Result<AcquireResult<(Image, Option<OwnedFd>)>> {
         let mut fence = MaybeUninit::uninit();
         AcquireResult::construct_never_null(|res| {
-            ffi::AImageReader_acquireLatestImageAsync(self.as_ptr(), res, fence.as_mut_ptr())
+            AImageReader_acquireLatestImageAsync(self.as_ptr(), res, fence.as_mut_ptr())
         })
         .map(|result| {
             result.map(|image| match fence.assume_init() {
@@ -371,7 +495,7 @@ impl ImageReader {
 impl Drop for ImageReader {
     #[doc(alias = "AImageReader_delete")]
     fn drop(&mut self) {
-        unsafe { ffi::AImageReader_delete(self.as_ptr()) };
+        unsafe { AImageReader_delete(self.as_ptr()) }.expect(SAME_API_LEVEL);
     }
 }
 
@@ -397,12 +521,12 @@ impl Image {
         let mut result_ptr = MaybeUninit::uninit();
         let mut result_len = MaybeUninit::uninit();
         let status = unsafe {
-            ffi::AImage_getPlaneData(
+            AImage_getPlaneData(
                 self.as_ptr(),
                 plane_idx,
                 result_ptr.as_mut_ptr(),
                 result_len.as_mut_ptr(),
-            )
+            )?
         };
 
         MediaError::from_status(status).map(|()| unsafe {
@@ -412,43 +536,43 @@ impl Image {
 
     #[doc(alias = "AImage_getPlanePixelStride")]
     pub fn plane_pixel_stride(&self, plane_idx: i32) -> Result<i32> {
-        construct(|res| unsafe { ffi::AImage_getPlanePixelStride(self.as_ptr(), plane_idx, res) })
+        construct(|res| unsafe { AImage_getPlanePixelStride(self.as_ptr(), plane_idx, res) })
     }
 
     #[doc(alias = "AImage_getPlaneRowStride")]
     pub fn plane_row_stride(&self, plane_idx: i32) -> Result<i32> {
-        construct(|res| unsafe { ffi::AImage_getPlaneRowStride(self.as_ptr(), plane_idx, res) })
+        construct(|res| unsafe { AImage_getPlaneRowStride(self.as_ptr(), plane_idx, res) })
     }
 
     #[doc(alias = "AImage_getCropRect")]
     pub fn crop_rect(&self) -> Result<CropRect> {
-        construct(|res| unsafe { ffi::AImage_getCropRect(self.as_ptr(), res) })
+        construct(|res| unsafe { AImage_getCropRect(self.as_ptr(), res) })
     }
 
     #[doc(alias = "AImage_getWidth")]
     pub fn width(&self) -> Result<i32> {
-        construct(|res| unsafe { ffi::AImage_getWidth(self.as_ptr(), res) })
+        construct(|res| unsafe { AImage_getWidth(self.as_ptr(), res) })
     }
 
     #[doc(alias = "AImage_getHeight")]
     pub fn height(&self) -> Result<i32> {
-        construct(|res| unsafe { ffi::AImage_getHeight(self.as_ptr(), res) })
+        construct(|res| unsafe { AImage_getHeight(self.as_ptr(), res) })
     }
 
     #[doc(alias = "AImage_getFormat")]
     pub fn format(&self) -> Result<ImageFormat> {
-        let format = construct(|res| unsafe { ffi::AImage_getFormat(self.as_ptr(), res) })?;
+        let format = construct(|res| unsafe { AImage_getFormat(self.as_ptr(), res) })?;
         Ok(format.into())
     }
 
     #[doc(alias = "AImage_getTimestamp")]
     pub fn timestamp(&self) -> Result<i64> {
-        construct(|res| unsafe { ffi::AImage_getTimestamp(self.as_ptr(), res) })
+        construct(|res| unsafe { AImage_getTimestamp(self.as_ptr(), res) })
     }
 
     #[doc(alias = "AImage_getNumberOfPlanes")]
     pub fn number_of_planes(&self) -> Result<i32> {
-        construct(|res| unsafe { ffi::AImage_getNumberOfPlanes(self.as_ptr(), res) })
+        construct(|res| unsafe { AImage_getNumberOfPlanes(self.as_ptr(), res) })
     }
 
     /// Get the hardware buffer handle of the input image intended for GPU and/or hardware access.
@@ -469,30 +593,114 @@ impl Image {
     #[doc(alias = "AImage_getHardwareBuffer")]
     pub fn hardware_buffer(&self) -> Result<HardwareBuffer> {
         unsafe {
-            let ptr =
-                construct_never_null(|res| ffi::AImage_getHardwareBuffer(self.as_ptr(), res))?;
+            let ptr = construct_never_null(|res| AImage_getHardwareBuffer(self.as_ptr(), res))?;
             Ok(HardwareBuffer::from_ptr(ptr))
         }
     }
 
+    /// Returns [`Unsupported`] when the `weak-linking` feature is enabled and the device does not
+    /// support this function, in which case the [`Image`] is deleted right away without waiting
+    /// for `release_fence_fd`.
     #[cfg(feature = "api-level-26")]
     #[doc(alias = "AImage_deleteAsync")]
-    pub fn delete_async(self, release_fence_fd: OwnedFd) {
-        unsafe { ffi::AImage_deleteAsync(self.as_ptr(), release_fence_fd.into_raw_fd()) };
+    pub fn delete_async(self, release_fence_fd: OwnedFd) -> std::result::Result<(), Unsupported> {
+        unsafe { AImage_deleteAsync(self.as_ptr(), release_fence_fd.as_raw_fd())? };
+        // Both the image and the fence are now owned by the image reader
+        std::mem::forget(release_fence_fd);
         std::mem::forget(self);
+        Ok(())
     }
 
     #[cfg(feature = "api-level-34")]
     #[doc(alias = "AImage_getDataSpace")]
     pub fn data_space(&self) -> Result<DataSpace> {
-        construct(|res| unsafe { ffi::AImage_getDataSpace(self.as_ptr(), res) })
-            .map(DataSpace::from)
+        construct(|res| unsafe { AImage_getDataSpace(self.as_ptr(), res) }).map(DataSpace::from)
     }
+
+    /// Converts this [`ImageFormat::YUV_420_888`] image to tightly packed RGBA pixels in `dst`,
+    /// which must hold at least [`Image::width()`] * [`Image::height()`] * `4` bytes.
+    ///
+    /// Any arrangement of the chroma planes (such as I420, NV12 and NV21) is supported. See
+    /// [`Image::to_rgba8_cropped()`] for the YCbCr to RGB conversion that is used.
+    pub fn to_rgba8(&self, dst: &mut [u8]) -> std::result::Result<(), ImageConversionError> {
+        let region = Region::new(self.width()? as usize, self.height()? as usize);
+        self.convert_to_rgba8(region, dst)
+    }
+
+    /// Converts the [`Image::crop_rect()`] region of this [`ImageFormat::YUV_420_888`] image to
+    /// tightly packed RGBA pixels in `dst`, which must hold at least `4` bytes for every pixel in
+    /// the (clamped) crop rectangle.
+    ///
+    /// With `api-level-34`, the conversion follows the standard and range of
+    /// `Image::data_space()`, see `YuvConversion::from_data_space()`. Images with a
+    /// `DataSpace::Unknown` data space, and all images on lower API levels (also at runtime, with
+    /// the `weak-linking` feature), are converted as [`YuvConversion::JFIF`] which is what the
+    /// camera produces.
+    pub fn to_rgba8_cropped(
+        &self,
+        dst: &mut [u8],
+    ) -> std::result::Result<(), ImageConversionError> {
+        let (width, height) = (self.width()?, self.height()?);
+        let crop = self.crop_rect()?;
+        let (left, top) = (crop.left.clamp(0, width), crop.top.clamp(0, height));
+        let (right, bottom) = (
+            crop.right.clamp(left, width),
+            crop.bottom.clamp(top, height),
+        );
+        let region = Region {
+            x: left as usize,
+            y: top as usize,
+            width: (right - left) as usize,
+            height: (bottom - top) as usize,
+        };
+        self.convert_to_rgba8(region, dst)
+    }
+
+    fn convert_to_rgba8(
+        &self,
+        region: Region,
+        dst: &mut [u8],
+    ) -> std::result::Result<(), ImageConversionError> {
+        let format = self.format()?;
+        if format != ImageFormat::YUV_420_888 {
+            return Err(ImageConversionError::UnsupportedFormat(format));
+        }
+
+        #[cfg(feature = "api-level-34")]
+        let conversion = match self.data_space() {
+            Ok(DataSpace::Unknown) | Err(MediaError::ErrorUnsupported) => YuvConversion::JFIF,
+            Err(e) => return Err(e.into()),
+            Ok(data_space) => YuvConversion::from_data_space(
+                data_space,
+                self.width()? as usize,
+                self.height()? as usize,
+            ),
+        };
+        #[cfg(not(feature = "api-level-34"))]
+        let conversion = YuvConversion::JFIF;
+
+        let planes = YuvPlanes::from_image(self)?;
+        yuv_420_888_to_rgba8888(&planes, region, conversion, dst, region.width * 4)?;
+        Ok(())
+    }
+}
+
+/// Encapsulates possible errors returned by [`Image::to_rgba8()`] and
+/// [`Image::to_rgba8_cropped()`].
+#[derive(Debug, thiserror::Error)]
+pub enum ImageConversionError {
+    #[error(transparent)]
+    MediaError(#[from] MediaError),
+    #[error(transparent)]
+    PixelConversionError(#[from] PixelConversionError),
+    /// Only [`ImageFormat::YUV_420_888`] images can be converted.
+    #[error("Cannot convert images of format {0:?} to RGBA")]
+    UnsupportedFormat(ImageFormat),
 }
 
 impl Drop for Image {
     #[doc(alias = "AImage_delete")]
     fn drop(&mut self) {
-        unsafe { ffi::AImage_delete(self.as_ptr()) };
+        unsafe { AImage_delete(self.as_ptr()) }.expect(SAME_API_LEVEL);
     }
 }
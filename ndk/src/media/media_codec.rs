@@ -4,6 +4,8 @@
 
 #[deprecated = "MediaFormat should be referenced directly from the media_format module"]
 pub use super::media_format::MediaFormat;
+#[cfg(feature = "api-level-28")]
+use crate::api_level::{Unsupported, SAME_API_LEVEL};
 use crate::media_error::{MediaError, Result};
 use crate::native_window::NativeWindow;
 use crate::utils::abort_on_panic;
@@ -17,6 +19,51 @@ use std::{
     time::Duration,
 };
 
+#[cfg(feature = "api-level-26")]
+crate::api_level::weak_fn! {
+    #[cfg(feature = "api-level-26")]
+    fn AMediaCodec_createInputSurface(
+        codec: *mut ffi::AMediaCodec,
+        surface: *mut *mut ffi::ANativeWindow,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-26")]
+    fn AMediaCodec_createPersistentInputSurface(
+        surface: *mut *mut ffi::ANativeWindow,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-26")]
+    fn AMediaCodec_setInputSurface(
+        codec: *mut ffi::AMediaCodec,
+        surface: *mut ffi::ANativeWindow,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-26")]
+    fn AMediaCodec_setParameters(
+        codec: *mut ffi::AMediaCodec,
+        params: *const ffi::AMediaFormat,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-26")]
+    fn AMediaCodec_signalEndOfInputStream(codec: *mut ffi::AMediaCodec) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-28")]
+    fn AMediaCodec_setAsyncNotifyCallback(
+        codec: *mut ffi::AMediaCodec,
+        callback: ffi::AMediaCodecOnAsyncNotifyCallback,
+        userdata: *mut c_void,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-28")]
+    fn AMediaCodec_getInputFormat(codec: *mut ffi::AMediaCodec) -> *mut ffi::AMediaFormat;
+    #[cfg(feature = "api-level-28")]
+    fn AMediaCodec_getName(
+        codec: *mut ffi::AMediaCodec,
+        out_name: *mut *mut c_char,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-28")]
+    fn AMediaCodec_releaseName(codec: *mut ffi::AMediaCodec, name: *mut c_char);
+    #[cfg(feature = "api-level-28")]
+    fn AMediaCodec_getBufferFormat(
+        codec: *mut ffi::AMediaCodec,
+        index: usize,
+    ) -> *mut ffi::AMediaFormat;
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum MediaCodecDirection {
     Decoder,
@@ -255,9 +302,8 @@ impl MediaCodec {
             (None, ffi_callback, ptr::null_mut())
         };
 
-        let status = unsafe {
-            ffi::AMediaCodec_setAsyncNotifyCallback(self.as_ptr(), ffi_callback, user_data)
-        };
+        let status =
+            unsafe { AMediaCodec_setAsyncNotifyCallback(self.as_ptr(), ffi_callback, user_data)? };
         let result = MediaError::from_status(status);
 
         // This behavior is not documented, but the implementation always clears the callback on failure, so we must
@@ -298,9 +344,8 @@ impl MediaCodec {
     pub fn create_input_surface(&self) -> Result<NativeWindow> {
         use crate::media_error::construct_never_null;
         unsafe {
-            let ptr = construct_never_null(|res| {
-                ffi::AMediaCodec_createInputSurface(self.as_ptr(), res)
-            })?;
+            let ptr =
+                construct_never_null(|res| AMediaCodec_createInputSurface(self.as_ptr(), res))?;
             Ok(NativeWindow::from_ptr(ptr))
         }
     }
@@ -309,8 +354,7 @@ impl MediaCodec {
     pub fn create_persistent_input_surface() -> Result<NativeWindow> {
         use crate::media_error::construct_never_null;
         unsafe {
-            let ptr =
-                construct_never_null(|res| ffi::AMediaCodec_createPersistentInputSurface(res))?;
+            let ptr = construct_never_null(|res| AMediaCodec_createPersistentInputSurface(res))?;
             Ok(NativeWindow::from_ptr(ptr))
         }
     }
@@ -400,10 +444,10 @@ impl MediaCodec {
     }
 
     #[cfg(feature = "api-level-28")]
-    pub fn input_format(&self) -> MediaFormat {
-        let inner = NonNull::new(unsafe { ffi::AMediaCodec_getInputFormat(self.as_ptr()) })
+    pub fn input_format(&self) -> std::result::Result<MediaFormat, Unsupported> {
+        let inner = NonNull::new(unsafe { AMediaCodec_getInputFormat(self.as_ptr())? })
             .expect("AMediaCodec_getInputFormat returned NULL");
-        unsafe { MediaFormat::from_ptr(inner) }
+        Ok(unsafe { MediaFormat::from_ptr(inner) })
     }
 
     pub fn output_format(&self) -> MediaFormat {
@@ -416,9 +460,9 @@ impl MediaCodec {
     pub fn name(&self) -> Result<String> {
         use crate::media_error::construct;
         unsafe {
-            let name_ptr = construct(|name| ffi::AMediaCodec_getName(self.as_ptr(), name))?;
+            let name_ptr = construct(|name| AMediaCodec_getName(self.as_ptr(), name))?;
             let name = CStr::from_ptr(name_ptr).to_str().unwrap().to_owned();
-            ffi::AMediaCodec_releaseName(self.as_ptr(), name_ptr);
+            AMediaCodec_releaseName(self.as_ptr(), name_ptr).expect(SAME_API_LEVEL);
 
             Ok(name)
         }
@@ -490,8 +534,7 @@ impl MediaCodec {
 
     #[cfg(feature = "api-level-26")]
     pub fn set_input_surface(&self, surface: &NativeWindow) -> Result<()> {
-        let status =
-            unsafe { ffi::AMediaCodec_setInputSurface(self.as_ptr(), surface.ptr().as_ptr()) };
+        let status = unsafe { AMediaCodec_setInputSurface(self.as_ptr(), surface.ptr().as_ptr())? };
         MediaError::from_status(status)
     }
 
@@ -503,13 +546,13 @@ impl MediaCodec {
 
     #[cfg(feature = "api-level-26")]
     pub fn set_parameters(&self, params: MediaFormat) -> Result<()> {
-        let status = unsafe { ffi::AMediaCodec_setParameters(self.as_ptr(), params.as_ptr()) };
+        let status = unsafe { AMediaCodec_setParameters(self.as_ptr(), params.as_ptr())? };
         MediaError::from_status(status)
     }
 
     #[cfg(feature = "api-level-26")]
     pub fn set_signal_end_of_input_stream(&self) -> Result<()> {
-        let status = unsafe { ffi::AMediaCodec_signalEndOfInputStream(self.as_ptr()) };
+        let status = unsafe { AMediaCodec_signalEndOfInputStream(self.as_ptr())? };
         MediaError::from_status(status)
     }
 
@@ -572,12 +615,11 @@ impl OutputBuffer<'_> {
     }
 
     #[cfg(feature = "api-level-28")]
-    pub fn format(&self) -> MediaFormat {
-        let inner = NonNull::new(unsafe {
-            ffi::AMediaCodec_getBufferFormat(self.codec.as_ptr(), self.index)
-        })
-        .expect("AMediaCodec_getBufferFormat returned NULL");
-        unsafe { MediaFormat::from_ptr(inner) }
+    pub fn format(&self) -> std::result::Result<MediaFormat, Unsupported> {
+        let inner =
+            NonNull::new(unsafe { AMediaCodec_getBufferFormat(self.codec.as_ptr(), self.index)? })
+                .expect("AMediaCodec_getBufferFormat returned NULL");
+        Ok(unsafe { MediaFormat::from_ptr(inner) })
     }
 
     pub fn info(&self) -> &BufferInfo {
@@ -9,8 +9,49 @@ use std::{
     slice,
 };
 
+#[cfg(feature = "api-level-28")]
+use crate::api_level::{weak_fn, Unsupported};
 use crate::media_error::{MediaError, Result};
 
+#[cfg(feature = "api-level-28")]
+weak_fn! {
+    #[cfg(feature = "api-level-28")]
+    fn AMediaFormat_getDouble(
+        format: *mut ffi::AMediaFormat,
+        name: *const std::ffi::c_char,
+        out: *mut f64,
+    ) -> bool;
+    #[cfg(feature = "api-level-28")]
+    fn AMediaFormat_getRect(
+        format: *mut ffi::AMediaFormat,
+        name: *const std::ffi::c_char,
+        left: *mut i32,
+        top: *mut i32,
+        right: *mut i32,
+        bottom: *mut i32,
+    ) -> bool;
+    #[cfg(feature = "api-level-28")]
+    fn AMediaFormat_setDouble(format: *mut ffi::AMediaFormat, name: *const std::ffi::c_char, value: f64);
+    #[cfg(feature = "api-level-28")]
+    fn AMediaFormat_setRect(
+        format: *mut ffi::AMediaFormat,
+        name: *const std::ffi::c_char,
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    );
+    #[cfg(feature = "api-level-28")]
+    fn AMediaFormat_setSize(format: *mut ffi::AMediaFormat, name: *const std::ffi::c_char, value: usize);
+    #[cfg(feature = "api-level-29")]
+    fn AMediaFormat_copy(
+        to: *mut ffi::AMediaFormat,
+        from: *mut ffi::AMediaFormat,
+    ) -> ffi::media_status_t;
+    #[cfg(feature = "api-level-29")]
+    fn AMediaFormat_clear(format: *mut ffi::AMediaFormat);
+}
+
 /// A native [`AMediaFormat *`]
 ///
 /// [`AMediaFormat *`]: https://developer.android.com/ndk/reference/group/media#amediaformat
@@ -170,12 +211,14 @@ impl MediaFormat {
         }
     }
 
+    /// Returns [`None`] when the `weak-linking` feature is enabled and the device does not support
+    /// this function.
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AMediaFormat_getDouble")]
     pub fn f64(&self, key: &str) -> Option<f64> {
         let name = CString::new(key).unwrap();
         let mut out = 0.0;
-        if unsafe { ffi::AMediaFormat_getDouble(self.as_ptr(), name.as_ptr(), &mut out) } {
+        if unsafe { AMediaFormat_getDouble(self.as_ptr(), name.as_ptr(), &mut out).ok()? } {
             Some(out)
         } else {
             None
@@ -183,6 +226,9 @@ impl MediaFormat {
     }
 
     /// Returns (left, top, right, bottom)
+    ///
+    /// Returns [`None`] when the `weak-linking` feature is enabled and the device does not support
+    /// this function.
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AMediaFormat_getRect")]
     pub fn rect(&self, key: &str) -> Option<(i32, i32, i32, i32)> {
@@ -192,7 +238,7 @@ impl MediaFormat {
         let mut right = 0;
         let mut bottom = 0;
         if unsafe {
-            ffi::AMediaFormat_getRect(
+            AMediaFormat_getRect(
                 self.as_ptr(),
                 name.as_ptr(),
                 &mut left,
@@ -200,6 +246,7 @@ impl MediaFormat {
                 &mut right,
                 &mut bottom,
             )
+            .ok()?
         } {
             Some((left, top, right, bottom))
         } else {
@@ -209,30 +256,37 @@ impl MediaFormat {
 
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AMediaFormat_setDouble")]
-    pub fn set_f64(&mut self, key: &str, value: f64) {
+    pub fn set_f64(&mut self, key: &str, value: f64) -> std::result::Result<(), Unsupported> {
         let name = CString::new(key).unwrap();
-        unsafe { ffi::AMediaFormat_setDouble(self.as_ptr(), name.as_ptr(), value) }
+        unsafe { AMediaFormat_setDouble(self.as_ptr(), name.as_ptr(), value) }
     }
 
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AMediaFormat_setRect")]
-    pub fn set_rect(&mut self, key: &str, left: i32, top: i32, right: i32, bottom: i32) {
+    pub fn set_rect(
+        &mut self,
+        key: &str,
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    ) -> std::result::Result<(), Unsupported> {
         let name = CString::new(key).unwrap();
-        unsafe { ffi::AMediaFormat_setRect(self.as_ptr(), name.as_ptr(), left, top, right, bottom) }
+        unsafe { AMediaFormat_setRect(self.as_ptr(), name.as_ptr(), left, top, right, bottom) }
     }
 
     #[cfg(feature = "api-level-28")]
     #[doc(alias = "AMediaFormat_setSize")]
-    pub fn set_usize(&mut self, key: &str, value: usize) {
+    pub fn set_usize(&mut self, key: &str, value: usize) -> std::result::Result<(), Unsupported> {
         let name = CString::new(key).unwrap();
-        unsafe { ffi::AMediaFormat_setSize(self.as_ptr(), name.as_ptr(), value) }
+        unsafe { AMediaFormat_setSize(self.as_ptr(), name.as_ptr(), value) }
     }
 
     /// Copy one [`MediaFormat`] to another.
     #[cfg(feature = "api-level-29")]
     #[doc(alias = "AMediaFormat_copy")]
     pub fn copy(&self, to: &mut Self) -> Result<()> {
-        let status = unsafe { ffi::AMediaFormat_copy(to.as_ptr(), self.as_ptr()) };
+        let status = unsafe { AMediaFormat_copy(to.as_ptr(), self.as_ptr())? };
         MediaError::from_status(status)
     }
 
@@ -250,8 +304,8 @@ impl MediaFormat {
     /// Remove all key/value pairs from this [`MediaFormat`].
     #[cfg(feature = "api-level-29")]
     #[doc(alias = "AMediaFormat_clear")]
-    pub fn clear(&mut self) {
-        unsafe { ffi::AMediaFormat_clear(self.as_ptr()) }
+    pub fn clear(&mut self) -> std::result::Result<(), Unsupported> {
+        unsafe { AMediaFormat_clear(self.as_ptr()) }
     }
 }
 
@@ -10,6 +10,8 @@ use std::{fmt, mem::MaybeUninit, ptr::NonNull};
 
 use num_enum::{FromPrimitive, IntoPrimitive};
 
+use crate::api_level::Unsupported;
+
 pub type Result<T, E = MediaError> = std::result::Result<T, E>;
 
 /// Media Status codes for [`media_status_t`](https://developer.android.com/ndk/reference/group/media#group___media_1ga009a49041fe39f7bdc6d8b5cddbe760c)
@@ -88,6 +90,14 @@ impl fmt::Display for MediaError {
 
 impl std::error::Error for MediaError {}
 
+impl From<Unsupported> for MediaError {
+    /// Functions that do not exist on the current device are reported as
+    /// [`MediaError::ErrorUnsupported`].
+    fn from(_: Unsupported) -> Self {
+        Self::ErrorUnsupported
+    }
+}
+
 impl MediaError {
     /// Returns [`Ok`] on [`ffi::media_status_t::AMEDIA_OK`], [`Err`] otherwise (including positive
     /// values).
@@ -117,19 +127,39 @@ impl MediaError {
     }
 }
 
+/// The status returned by a function passed to [`construct()`], which may not exist on the current
+/// device.
+pub(crate) trait Status {
+    fn into_result(self) -> Result<()>;
+}
+
+impl Status for ffi::media_status_t {
+    fn into_result(self) -> Result<()> {
+        MediaError::from_status(self)
+    }
+}
+
+impl Status for std::result::Result<ffi::media_status_t, Unsupported> {
+    fn into_result(self) -> Result<()> {
+        MediaError::from_status(self?)
+    }
+}
+
 /// Calls the `with_ptr` construction function with a pointer to uninitialized stack memory,
 /// expecting `with_ptr` to initialize it or otherwise return an error code.
-pub(crate) fn construct<T>(with_ptr: impl FnOnce(*mut T) -> ffi::media_status_t) -> Result<T> {
+pub(crate) fn construct<T, S: Status>(with_ptr: impl FnOnce(*mut T) -> S) -> Result<T> {
     let mut result = MaybeUninit::uninit();
     let status = with_ptr(result.as_mut_ptr());
-    MediaError::from_status(status).map(|()| unsafe { result.assume_init() })
+    status
+        .into_result()
+        .map(|()| unsafe { result.assume_init() })
 }
 
 /// Calls the `with_ptr` construction function with a pointer to a pointer, and expects `with_ptr`
 /// to initialize the second pointer to a valid address.  That address is returned in the form of a
 /// [`NonNull`] object.
-pub(crate) fn construct_never_null<T>(
-    with_ptr: impl FnOnce(*mut *mut T) -> ffi::media_status_t,
+pub(crate) fn construct_never_null<T, S: Status>(
+    with_ptr: impl FnOnce(*mut *mut T) -> S,
 ) -> Result<NonNull<T>> {
     let result = construct(with_ptr)?;
     Ok(if cfg!(debug_assertions) {
@@ -4,12 +4,20 @@
 
 use super::hardware_buffer_format::HardwareBufferFormat;
 use std::{
+    any::Any,
+    cell::RefCell,
     ffi::{CStr, OsStr},
     os::{raw::c_void, unix::prelude::OsStrExt},
+    panic::{catch_unwind, AssertUnwindSafe},
     path::Path,
-    ptr::NonNull,
+    ptr::{self, NonNull},
+    sync::Once,
 };
 
+use crate::input_queue::InputQueue;
+use crate::native_window::{NativeWindow, Rect};
+use crate::utils::log_panic;
+
 bitflags::bitflags! {
     /// Window flags, as per the Java API at [`android.view.WindowManager.LayoutParams`].
     ///
@@ -55,8 +63,8 @@ bitflags::bitflags! {
 
 /// A native [`ANativeActivity *`]
 ///
-/// This is either provided in [`ffi::ANativeActivity_onCreate()`], or accessible through
-/// `ndk_glue::native_activity()`.
+/// This is either provided in [`ffi::ANativeActivity_onCreate()`], which can be implemented with
+/// [`entry!`], or accessible through `ndk_glue::native_activity()`.
 ///
 /// [`ANativeActivity *`]: https://developer.android.com/ndk/reference/struct/a-native-activity
 #[derive(Debug)]
@@ -239,3 +247,446 @@ impl NativeActivity {
         }
     }
 }
+
+/// Lifecycle, window and input callbacks of a [`NativeActivity`], installed with
+/// [`NativeActivity::set_callbacks()`].
+///
+/// All methods do nothing by default. They are called on the main thread of the activity, and a
+/// panic in any of them is caught and logged instead of unwinding into the Android framework.
+///
+/// See [`ANativeActivityCallbacks`] for details on each callback.
+///
+/// ```no_run
+/// # use ndk::native_activity::{NativeActivity, NativeActivityCallbacks};
+/// # use ndk::native_window::NativeWindow;
+/// struct App {
+///     window: Option<NativeWindow>,
+/// }
+///
+/// impl NativeActivityCallbacks for App {
+///     fn on_native_window_created(&mut self, _: &NativeActivity, window: NativeWindow) {
+///         self.window = Some(window);
+///     }
+///
+///     fn on_native_window_destroyed(&mut self, _: &NativeActivity, _: NativeWindow) {
+///         self.window = None;
+///     }
+/// }
+///
+/// # let activity: NativeActivity = unimplemented!();
+/// unsafe { activity.set_callbacks(App { window: None }) };
+/// ```
+///
+/// [`ANativeActivityCallbacks`]: https://developer.android.com/ndk/reference/struct/a-native-activity-callbacks
+#[doc(alias = "ANativeActivityCallbacks")]
+#[allow(unused_variables)]
+pub trait NativeActivityCallbacks {
+    /// The activity was started.
+    #[doc(alias = "onStart")]
+    fn on_start(&mut self, activity: &NativeActivity) {}
+
+    /// The activity was resumed.
+    #[doc(alias = "onResume")]
+    fn on_resume(&mut self, activity: &NativeActivity) {}
+
+    /// Returns the state to restore when the activity is recreated, which is passed to
+    /// `ANativeActivity_onCreate()`. Returning an empty [`Vec`] saves no state.
+    #[doc(alias = "onSaveInstanceState")]
+    fn on_save_instance_state(&mut self, activity: &NativeActivity) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// The activity was paused.
+    #[doc(alias = "onPause")]
+    fn on_pause(&mut self, activity: &NativeActivity) {}
+
+    /// The activity was stopped.
+    #[doc(alias = "onStop")]
+    fn on_stop(&mut self, activity: &NativeActivity) {}
+
+    /// The activity is being destroyed. The callbacks are dropped right after this returns, and
+    /// `activity` must no longer be used.
+    #[doc(alias = "onDestroy")]
+    fn on_destroy(&mut self, activity: &NativeActivity) {}
+
+    /// The window of the activity gained or lost input focus.
+    #[doc(alias = "onWindowFocusChanged")]
+    fn on_window_focus_changed(&mut self, activity: &NativeActivity, has_focus: bool) {}
+
+    /// The drawing surface of the activity was created. It stays valid until
+    /// [`NativeActivityCallbacks::on_native_window_destroyed()`] returns.
+    #[doc(alias = "onNativeWindowCreated")]
+    fn on_native_window_created(&mut self, activity: &NativeActivity, window: NativeWindow) {}
+
+    /// The drawing surface of the activity was resized.
+    #[doc(alias = "onNativeWindowResized")]
+    fn on_native_window_resized(&mut self, activity: &NativeActivity, window: NativeWindow) {}
+
+    /// The drawing surface has to be redrawn before this returns, to avoid transient artifacts.
+    #[doc(alias = "onNativeWindowRedrawNeeded")]
+    fn on_native_window_redraw_needed(&mut self, activity: &NativeActivity, window: NativeWindow) {}
+
+    /// The drawing surface is about to be destroyed. Drawing to it has to stop before this
+    /// returns.
+    #[doc(alias = "onNativeWindowDestroyed")]
+    fn on_native_window_destroyed(&mut self, activity: &NativeActivity, window: NativeWindow) {}
+
+    /// The input queue of the activity was created. It stays valid until
+    /// [`NativeActivityCallbacks::on_input_queue_destroyed()`] returns.
+    #[doc(alias = "onInputQueueCreated")]
+    fn on_input_queue_created(&mut self, activity: &NativeActivity, queue: InputQueue) {}
+
+    /// The input queue is about to be destroyed, and must no longer be used after this returns.
+    #[doc(alias = "onInputQueueDestroyed")]
+    fn on_input_queue_destroyed(&mut self, activity: &NativeActivity, queue: InputQueue) {}
+
+    /// The rectangle of the window in which content should be placed changed, for example
+    /// because the soft keyboard was shown.
+    #[doc(alias = "onContentRectChanged")]
+    fn on_content_rect_changed(&mut self, activity: &NativeActivity, rect: Rect) {}
+
+    /// The configuration of the device changed, see
+    /// [`Configuration`][crate::configuration::Configuration].
+    #[doc(alias = "onConfigurationChanged")]
+    fn on_configuration_changed(&mut self, activity: &NativeActivity) {}
+
+    /// The system is running low on memory, and the activity should free what it can.
+    #[doc(alias = "onLowMemory")]
+    fn on_low_memory(&mut self, activity: &NativeActivity) {}
+}
+
+/// The callbacks installed on each activity, keyed by its pointer.
+///
+/// An entry is [`None`] while its callbacks are running, so that they can replace themselves.
+type CallbacksSlot = (
+    *mut ffi::ANativeActivity,
+    Option<Box<dyn NativeActivityCallbacks>>,
+);
+
+thread_local! {
+    // The framework invokes all callbacks on the main thread of the activity
+    static CALLBACKS: RefCell<Vec<CallbacksSlot>> = RefCell::new(Vec::new());
+}
+
+/// Installed by [`entry!`] to log panics, including those caught around the callbacks.
+static PANIC_HOOK: Once = Once::new();
+
+/// Logs a panic caught around a callback, unless the panic hook of [`entry!`] already did.
+fn log_callback_panic(panic: Box<dyn Any + Send>) {
+    if !PANIC_HOOK.is_completed() {
+        log_panic(panic);
+    }
+}
+
+extern "C" {
+    // The framework releases saved instance state with `free()`
+    fn malloc(size: usize) -> *mut c_void;
+}
+
+/// Runs `f` with the callbacks installed on `activity`, catching and logging panics.
+///
+/// Returns [`Default::default()`] if no callbacks are installed or `f` panics.
+fn with_callbacks<R: Default>(
+    activity: *mut ffi::ANativeActivity,
+    f: impl FnOnce(&mut dyn NativeActivityCallbacks, &NativeActivity) -> R,
+) -> R {
+    let callbacks = CALLBACKS.with(|slots| {
+        slots
+            .borrow_mut()
+            .iter_mut()
+            .find(|(ptr, _)| *ptr == activity)
+            .and_then(|(_, callbacks)| callbacks.take())
+    });
+    let (Some(mut callbacks), Some(ptr)) = (callbacks, NonNull::new(activity)) else {
+        return R::default();
+    };
+
+    let activity = unsafe { NativeActivity::from_ptr(ptr) };
+    let result =
+        catch_unwind(AssertUnwindSafe(|| f(&mut *callbacks, &activity))).unwrap_or_else(|panic| {
+            log_callback_panic(panic);
+            R::default()
+        });
+
+    CALLBACKS.with(|slots| {
+        // Keep new callbacks that were installed while these ran
+        if let Some((_, slot @ None)) = slots
+            .borrow_mut()
+            .iter_mut()
+            .find(|(ptr, _)| *ptr == activity.ptr.as_ptr())
+        {
+            *slot = Some(callbacks);
+        }
+    });
+    result
+}
+
+/// Methods that relate to [`NativeActivityCallbacks`].
+impl NativeActivity {
+    /// Installs `callbacks` into the [`ANativeActivityCallbacks`] of this activity, replacing all
+    /// previously installed callbacks.
+    ///
+    /// The callbacks are dropped after [`NativeActivityCallbacks::on_destroy()`], or when they
+    /// are replaced.
+    ///
+    /// # Safety
+    /// This must be called on the main thread of the activity, typically from
+    /// `ANativeActivity_onCreate()`, as the framework reads the callbacks from that thread
+    /// without synchronization.
+    ///
+    /// [`ANativeActivityCallbacks`]: https://developer.android.com/ndk/reference/struct/a-native-activity-callbacks
+    pub unsafe fn set_callbacks(&self, callbacks: impl NativeActivityCallbacks + 'static) {
+        unsafe extern "C" fn on_start(activity: *mut ffi::ANativeActivity) {
+            with_callbacks(activity, |callbacks, activity| callbacks.on_start(activity))
+        }
+
+        unsafe extern "C" fn on_resume(activity: *mut ffi::ANativeActivity) {
+            with_callbacks(activity, |callbacks, activity| {
+                callbacks.on_resume(activity)
+            })
+        }
+
+        unsafe extern "C" fn on_save_instance_state(
+            activity: *mut ffi::ANativeActivity,
+            out_size: *mut usize,
+        ) -> *mut c_void {
+            let state = with_callbacks(activity, |callbacks, activity| {
+                callbacks.on_save_instance_state(activity)
+            });
+            let buffer = if state.is_empty() {
+                ptr::null_mut()
+            } else {
+                unsafe { malloc(state.len()) }
+            };
+            if buffer.is_null() {
+                unsafe { *out_size = 0 };
+            } else {
+                unsafe {
+                    ptr::copy_nonoverlapping(state.as_ptr(), buffer.cast(), state.len());
+                    *out_size = state.len();
+                }
+            }
+            buffer
+        }
+
+        unsafe extern "C" fn on_pause(activity: *mut ffi::ANativeActivity) {
+            with_callbacks(activity, |callbacks, activity| callbacks.on_pause(activity))
+        }
+
+        unsafe extern "C" fn on_stop(activity: *mut ffi::ANativeActivity) {
+            with_callbacks(activity, |callbacks, activity| callbacks.on_stop(activity))
+        }
+
+        unsafe extern "C" fn on_destroy(activity: *mut ffi::ANativeActivity) {
+            with_callbacks(activity, |callbacks, activity| {
+                callbacks.on_destroy(activity)
+            });
+            let removed = CALLBACKS.with(|slots| {
+                let mut slots = slots.borrow_mut();
+                let index = slots.iter().position(|(ptr, _)| *ptr == activity)?;
+                Some(slots.swap_remove(index))
+            });
+            // Drop the callbacks outside of the borrow, in case their destructor panics
+            if let Err(panic) = catch_unwind(AssertUnwindSafe(|| drop(removed))) {
+                log_callback_panic(panic);
+            }
+        }
+
+        unsafe extern "C" fn on_window_focus_changed(
+            activity: *mut ffi::ANativeActivity,
+            has_focus: i32,
+        ) {
+            with_callbacks(activity, |callbacks, activity| {
+                callbacks.on_window_focus_changed(activity, has_focus != 0)
+            })
+        }
+
+        /// Generates a callback that passes an acquired [`NativeWindow`] to `$method`.
+        macro_rules! window_callback {
+            ($name:ident, $method:ident) => {
+                unsafe extern "C" fn $name(
+                    activity: *mut ffi::ANativeActivity,
+                    window: *mut ffi::ANativeWindow,
+                ) {
+                    let Some(window) = NonNull::new(window) else {
+                        return;
+                    };
+                    let window = unsafe { NativeWindow::clone_from_ptr(window) };
+                    with_callbacks(activity, |callbacks, activity| {
+                        callbacks.$method(activity, window)
+                    })
+                }
+            };
+        }
+        window_callback!(on_native_window_created, on_native_window_created);
+        window_callback!(on_native_window_resized, on_native_window_resized);
+        window_callback!(
+            on_native_window_redraw_needed,
+            on_native_window_redraw_needed
+        );
+        window_callback!(on_native_window_destroyed, on_native_window_destroyed);
+
+        unsafe extern "C" fn on_input_queue_created(
+            activity: *mut ffi::ANativeActivity,
+            queue: *mut ffi::AInputQueue,
+        ) {
+            let Some(queue) = NonNull::new(queue) else {
+                return;
+            };
+            let queue = unsafe { InputQueue::from_ptr(queue) };
+            with_callbacks(activity, |callbacks, activity| {
+                callbacks.on_input_queue_created(activity, queue)
+            })
+        }
+
+        unsafe extern "C" fn on_input_queue_destroyed(
+            activity: *mut ffi::ANativeActivity,
+            queue: *mut ffi::AInputQueue,
+        ) {
+            let Some(queue) = NonNull::new(queue) else {
+                return;
+            };
+            let queue = unsafe { InputQueue::from_ptr(queue) };
+            with_callbacks(activity, |callbacks, activity| {
+                callbacks.on_input_queue_destroyed(activity, queue)
+            })
+        }
+
+        unsafe extern "C" fn on_content_rect_changed(
+            activity: *mut ffi::ANativeActivity,
+            rect: *const ffi::ARect,
+        ) {
+            let rect = unsafe { *rect };
+            with_callbacks(activity, |callbacks, activity| {
+                callbacks.on_content_rect_changed(activity, rect)
+            })
+        }
+
+        unsafe extern "C" fn on_configuration_changed(activity: *mut ffi::ANativeActivity) {
+            with_callbacks(activity, |callbacks, activity| {
+                callbacks.on_configuration_changed(activity)
+            })
+        }
+
+        unsafe extern "C" fn on_low_memory(activity: *mut ffi::ANativeActivity) {
+            with_callbacks(activity, |callbacks, activity| {
+                callbacks.on_low_memory(activity)
+            })
+        }
+
+        let activity = self.ptr.as_ptr();
+        let callbacks: Box<dyn NativeActivityCallbacks> = Box::new(callbacks);
+        let replaced = CALLBACKS.with(|slots| {
+            let mut slots = slots.borrow_mut();
+            match slots.iter_mut().find(|(ptr, _)| *ptr == activity) {
+                Some((_, slot)) => slot.replace(callbacks),
+                None => {
+                    slots.push((activity, Some(callbacks)));
+                    None
+                }
+            }
+        });
+        drop(replaced);
+
+        let raw = unsafe { &mut *self.ptr.as_ref().callbacks };
+        raw.onStart = Some(on_start);
+        raw.onResume = Some(on_resume);
+        raw.onSaveInstanceState = Some(on_save_instance_state);
+        raw.onPause = Some(on_pause);
+        raw.onStop = Some(on_stop);
+        raw.onDestroy = Some(on_destroy);
+        raw.onWindowFocusChanged = Some(on_window_focus_changed);
+        raw.onNativeWindowCreated = Some(on_native_window_created);
+        raw.onNativeWindowResized = Some(on_native_window_resized);
+        raw.onNativeWindowRedrawNeeded = Some(on_native_window_redraw_needed);
+        raw.onNativeWindowDestroyed = Some(on_native_window_destroyed);
+        raw.onInputQueueCreated = Some(on_input_queue_created);
+        raw.onInputQueueDestroyed = Some(on_input_queue_destroyed);
+        raw.onContentRectChanged = Some(on_content_rect_changed);
+        raw.onConfigurationChanged = Some(on_configuration_changed);
+        raw.onLowMemory = Some(on_low_memory);
+    }
+}
+
+/// Defines the `ANativeActivity_onCreate()` entry point that Android calls when a
+/// [`android.app.NativeActivity`] is created, forwarding to a safe function.
+///
+/// The function receives the [`NativeActivity`] and the state that was returned from
+/// [`NativeActivityCallbacks::on_save_instance_state()`] before the activity was last destroyed,
+/// if any. Before it is called, a panic hook is installed that writes panic messages to logcat;
+/// a panic escaping the function aborts the process.
+///
+/// ```no_run
+/// use ndk::native_activity::{NativeActivity, NativeActivityCallbacks};
+///
+/// struct App;
+///
+/// impl NativeActivityCallbacks for App {}
+///
+/// fn on_create(activity: NativeActivity, _saved_state: Option<&[u8]>) {
+///     // The framework calls `on_create` and all callbacks on the main thread
+///     unsafe { activity.set_callbacks(App) };
+/// }
+///
+/// ndk::native_activity::entry!(on_create);
+/// ```
+///
+/// [`android.app.NativeActivity`]: https://developer.android.com/reference/android/app/NativeActivity
+#[doc(inline)]
+pub use crate::__native_activity_entry as entry;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __native_activity_entry {
+    ($on_create:expr) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn ANativeActivity_onCreate(
+            activity: *mut ::std::ffi::c_void,
+            saved_state: *mut ::std::ffi::c_void,
+            saved_state_size: usize,
+        ) {
+            unsafe {
+                $crate::native_activity::__on_create(
+                    activity,
+                    saved_state,
+                    saved_state_size,
+                    $on_create,
+                )
+            }
+        }
+    };
+}
+
+/// The implementation of the `ANativeActivity_onCreate()` generated by [`entry!`].
+///
+/// # Safety
+/// The arguments must be those passed to `ANativeActivity_onCreate()`.
+#[doc(hidden)]
+pub unsafe fn __on_create(
+    activity: *mut c_void,
+    saved_state: *mut c_void,
+    saved_state_size: usize,
+    on_create: fn(NativeActivity, Option<&[u8]>),
+) {
+    PANIC_HOOK.call_once(|| {
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // stderr is not connected to anything in an app process
+            log_panic(Box::new(info.to_string()));
+            default_hook(info);
+        }));
+    });
+
+    // The panic hook already logged the panic
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let activity = NonNull::new(activity.cast()).expect("ANativeActivity must not be NULL");
+        let activity = unsafe { NativeActivity::from_ptr(activity) };
+        let saved_state = (!saved_state.is_null() && saved_state_size > 0).then(|| unsafe {
+            std::slice::from_raw_parts(saved_state.cast::<u8>(), saved_state_size)
+        });
+        on_create(activity, saved_state)
+    }));
+    if result.is_err() {
+        std::process::abort();
+    }
+}
@@ -7,9 +7,41 @@ use std::{ffi::c_void, io, mem::MaybeUninit, ptr::NonNull};
 use jni_sys::{jobject, JNIEnv};
 
 use super::{hardware_buffer_format::HardwareBufferFormat, utils::status_to_io_result};
+#[cfg(feature = "api-level-26")]
+use crate::api_level::{weak_fn, Unsupported};
 #[cfg(all(feature = "nativewindow", feature = "api-level-28"))]
 use crate::data_space::DataSpace;
 
+#[cfg(feature = "api-level-26")]
+weak_fn! {
+    fn ANativeWindow_toSurface(env: *mut JNIEnv, window: *mut ffi::ANativeWindow) -> jobject;
+}
+
+#[cfg(feature = "nativewindow")]
+weak_fn! {
+    #[cfg(feature = "api-level-26")]
+    fn ANativeWindow_setBuffersTransform(window: *mut ffi::ANativeWindow, transform: i32) -> i32;
+    #[cfg(feature = "api-level-28")]
+    fn ANativeWindow_setBuffersDataSpace(window: *mut ffi::ANativeWindow, data_space: i32) -> i32;
+    #[cfg(feature = "api-level-28")]
+    fn ANativeWindow_getBuffersDataSpace(window: *mut ffi::ANativeWindow) -> i32;
+    #[cfg(feature = "api-level-30")]
+    fn ANativeWindow_setFrameRate(
+        window: *mut ffi::ANativeWindow,
+        frame_rate: f32,
+        compatibility: i8,
+    ) -> i32;
+    #[cfg(feature = "api-level-31")]
+    fn ANativeWindow_setFrameRateWithChangeStrategy(
+        window: *mut ffi::ANativeWindow,
+        frame_rate: f32,
+        compatibility: i8,
+        change_frame_rate_strategy: i8,
+    ) -> i32;
+    #[cfg(feature = "api-level-30")]
+    fn ANativeWindow_tryAllocateBuffers(window: *mut ffi::ANativeWindow);
+}
+
 pub type Rect = ffi::ARect;
 
 // [`NativeWindow`] represents the producer end of an image queue
@@ -134,7 +166,7 @@ impl NativeWindow {
     #[doc(alias = "ANativeWindow_setBuffersTransform")]
     pub fn set_buffers_transform(&self, transform: NativeWindowTransform) -> io::Result<()> {
         let status =
-            unsafe { ffi::ANativeWindow_setBuffersTransform(self.ptr.as_ptr(), transform.bits()) };
+            unsafe { ANativeWindow_setBuffersTransform(self.ptr.as_ptr(), transform.bits())? };
         status_to_io_result(status)
     }
 
@@ -149,7 +181,7 @@ impl NativeWindow {
     #[doc(alias = "ANativeWindow_setBuffersDataSpace")]
     pub fn set_buffers_data_space(&self, data_space: DataSpace) -> io::Result<()> {
         let status =
-            unsafe { ffi::ANativeWindow_setBuffersDataSpace(self.ptr.as_ptr(), data_space.into()) };
+            unsafe { ANativeWindow_setBuffersDataSpace(self.ptr.as_ptr(), data_space.into())? };
         status_to_io_result(status)
     }
 
@@ -157,7 +189,7 @@ impl NativeWindow {
     #[cfg(all(feature = "nativewindow", feature = "api-level-28"))]
     #[doc(alias = "ANativeWindow_getBuffersDataSpace")]
     pub fn buffers_data_space(&self) -> io::Result<DataSpace> {
-        let status = unsafe { ffi::ANativeWindow_getBuffersDataSpace(self.ptr.as_ptr()) };
+        let status = unsafe { ANativeWindow_getBuffersDataSpace(self.ptr.as_ptr())? };
         if status >= 0 {
             Ok(status.into())
         } else {
@@ -181,7 +213,7 @@ impl NativeWindow {
         compatibility: FrameRateCompatibility,
     ) -> io::Result<()> {
         let status = unsafe {
-            ffi::ANativeWindow_setFrameRate(self.ptr.as_ptr(), frame_rate, compatibility as i8)
+            ANativeWindow_setFrameRate(self.ptr.as_ptr(), frame_rate, compatibility as i8)?
         };
         status_to_io_result(status)
     }
@@ -225,12 +257,12 @@ impl NativeWindow {
         change_frame_rate_strategy: ChangeFrameRateStrategy,
     ) -> io::Result<()> {
         let status = unsafe {
-            ffi::ANativeWindow_setFrameRateWithChangeStrategy(
+            ANativeWindow_setFrameRateWithChangeStrategy(
                 self.ptr.as_ptr(),
                 frame_rate,
                 compatibility as i8,
                 change_frame_rate_strategy as i8,
-            )
+            )?
         };
         status_to_io_result(status)
     }
@@ -241,8 +273,8 @@ impl NativeWindow {
     /// instance if an implementation disallows allocation of new buffers, or if there is
     /// insufficient memory in the system to preallocate additional buffers
     #[cfg(all(feature = "nativewindow", feature = "api-level-30"))]
-    pub fn try_allocate_buffers(&self) {
-        unsafe { ffi::ANativeWindow_tryAllocateBuffers(self.ptr.as_ptr()) }
+    pub fn try_allocate_buffers(&self) -> Result<(), Unsupported> {
+        unsafe { ANativeWindow_tryAllocateBuffers(self.ptr.as_ptr()) }
     }
 
     /// Return the [`NativeWindow`] associated with a JNI [`android.view.Surface`] pointer.
@@ -264,8 +296,8 @@ impl NativeWindow {
     ///
     /// [`android.view.Surface`]: https://developer.android.com/reference/android/view/Surface
     #[cfg(feature = "api-level-26")]
-    pub unsafe fn to_surface(&self, env: *mut JNIEnv) -> jobject {
-        ffi::ANativeWindow_toSurface(env, self.ptr().as_ptr())
+    pub unsafe fn to_surface(&self, env: *mut JNIEnv) -> Result<jobject, Unsupported> {
+        ANativeWindow_toSurface(env, self.ptr().as_ptr())
     }
 
     /// Lock the window's next drawing surface for writing.
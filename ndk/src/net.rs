@@ -0,0 +1,420 @@
+//! Bindings for [`<android/multinetwork.h>`]
+//!
+//! These functions allow binding sockets, the current process, and DNS lookups to a specific
+//! [`android.net.Network`] (for example Wi-Fi versus cellular), identified by its
+//! [`NetworkHandle`].
+//!
+//! [`<android/multinetwork.h>`]: https://developer.android.com/ndk/reference/group/networking
+//! [`android.net.Network`]: https://developer.android.com/reference/android/net/Network
+#![cfg(feature = "api-level-23")]
+
+#[cfg(feature = "api-level-31")]
+use std::mem::MaybeUninit;
+use std::{
+    ffi::{c_char, c_int, CStr},
+    io::{Error, ErrorKind, Result},
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    // TODO: Import from std::os::fd::{} since Rust 1.66
+    os::unix::io::{AsFd, AsRawFd},
+    ptr,
+};
+#[cfg(feature = "api-level-29")]
+use std::{
+    mem::ManuallyDrop,
+    os::unix::io::{BorrowedFd, RawFd},
+};
+
+use crate::api_level::weak_fn;
+#[cfg(feature = "api-level-29")]
+use crate::{
+    api_level::SAME_API_LEVEL,
+    looper::{FdEvent, LooperError, ThreadLooper},
+};
+
+weak_fn! {
+    fn android_setsocknetwork(network: ffi::net_handle_t, fd: c_int) -> c_int;
+    fn android_setprocnetwork(network: ffi::net_handle_t) -> c_int;
+    fn android_getaddrinfofornetwork(
+        network: ffi::net_handle_t,
+        node: *const c_char,
+        service: *const c_char,
+        hints: *const ffi::addrinfo,
+        res: *mut *mut ffi::addrinfo,
+    ) -> c_int;
+    #[cfg(feature = "api-level-31")]
+    fn android_getprocnetwork(network: *mut ffi::net_handle_t) -> c_int;
+    #[cfg(feature = "api-level-31")]
+    fn android_getprocdns(network: *mut ffi::net_handle_t) -> c_int;
+    #[cfg(feature = "api-level-31")]
+    fn android_setprocdns(network: ffi::net_handle_t) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn android_res_nquery(
+        network: ffi::net_handle_t,
+        dname: *const c_char,
+        ns_class: c_int,
+        ns_type: c_int,
+        flags: u32,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn android_res_nsend(
+        network: ffi::net_handle_t,
+        msg: *const u8,
+        msglen: usize,
+        flags: u32,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn android_res_nresult(fd: c_int, rcode: *mut c_int, answer: *mut u8, anslen: usize) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn android_res_cancel(nsend_fd: c_int);
+}
+
+/// An opaque handle identifying an [`android.net.Network`].
+///
+/// Obtain the raw value for a specific network in Java/Kotlin via
+/// [`Network.getNetworkHandle()`], and pass it to [`NetworkHandle::from_raw()`].
+///
+/// [`android.net.Network`]: https://developer.android.com/reference/android/net/Network
+/// [`Network.getNetworkHandle()`]: https://developer.android.com/reference/android/net/Network#getNetworkHandle()
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[doc(alias = "net_handle_t")]
+pub struct NetworkHandle(ffi::net_handle_t);
+
+impl NetworkHandle {
+    /// The special network handle value that stands for "no network in particular".
+    ///
+    /// Binding to this handle clears any previous binding and reverts to the default network.
+    #[doc(alias = "NETWORK_UNSPECIFIED")]
+    pub const UNSPECIFIED: Self = Self(0);
+
+    /// Wraps a raw handle value as returned by [`Network.getNetworkHandle()`].
+    ///
+    /// [`Network.getNetworkHandle()`]: https://developer.android.com/reference/android/net/Network#getNetworkHandle()
+    pub const fn from_raw(handle: u64) -> Self {
+        Self(handle)
+    }
+
+    /// Returns the raw handle value, which can be passed to Java/Kotlin
+    /// [`Network.fromNetworkHandle()`].
+    ///
+    /// [`Network.fromNetworkHandle()`]: https://developer.android.com/reference/android/net/Network#fromNetworkHandle(long)
+    pub const fn to_raw(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the network that the current process is bound to, as previously set with
+    /// [`set_process_network()`].
+    ///
+    /// Returns [`NetworkHandle::UNSPECIFIED`] if the process is not bound to any network.
+    #[cfg(feature = "api-level-31")]
+    #[doc(alias = "android_getprocnetwork")]
+    pub fn process_default() -> Result<Self> {
+        let mut network = MaybeUninit::uninit();
+        let status = unsafe { android_getprocnetwork(network.as_mut_ptr())? };
+        if status < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(Self(unsafe { network.assume_init() }))
+        }
+    }
+
+    /// Returns the network that DNS lookups from the current process are bound to, as previously
+    /// set with [`set_process_dns_network()`].
+    ///
+    /// Returns [`NetworkHandle::UNSPECIFIED`] if DNS lookups are not bound to any network.
+    #[cfg(feature = "api-level-31")]
+    #[doc(alias = "android_getprocdns")]
+    pub fn process_dns() -> Result<Self> {
+        let mut network = MaybeUninit::uninit();
+        let status = unsafe { android_getprocdns(network.as_mut_ptr())? };
+        if status < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(Self(unsafe { network.assume_init() }))
+        }
+    }
+}
+
+fn status_to_result(status: c_int) -> Result<()> {
+    if status < 0 {
+        Err(Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Binds `socket` to `network`.
+///
+/// All traffic sent over the socket will be routed over the given network, regardless of the
+/// process default network. Pass [`NetworkHandle::UNSPECIFIED`] to clear a previous binding.
+///
+/// The socket must not have been connected yet.
+#[doc(alias = "android_setsocknetwork")]
+pub fn bind_socket(network: NetworkHandle, socket: impl AsFd) -> Result<()> {
+    status_to_result(unsafe { android_setsocknetwork(network.0, socket.as_fd().as_raw_fd())? })
+}
+
+/// Binds the current process to `network`.
+///
+/// All sockets created afterwards, and all DNS lookups, are routed over the given network unless
+/// explicitly bound elsewhere. This is the NDK equivalent of Java/Kotlin
+/// [`ConnectivityManager.bindProcessToNetwork()`]. Pass [`NetworkHandle::UNSPECIFIED`] to clear a
+/// previous binding.
+///
+/// [`ConnectivityManager.bindProcessToNetwork()`]: https://developer.android.com/reference/android/net/ConnectivityManager#bindProcessToNetwork(android.net.Network)
+#[doc(alias = "android_setprocnetwork")]
+pub fn set_process_network(network: NetworkHandle) -> Result<()> {
+    status_to_result(unsafe { android_setprocnetwork(network.0)? })
+}
+
+/// Binds only the DNS lookups of the current process to `network`.
+///
+/// Pass [`NetworkHandle::UNSPECIFIED`] to clear a previous binding.
+#[cfg(feature = "api-level-31")]
+#[doc(alias = "android_setprocdns")]
+pub fn set_process_dns_network(network: NetworkHandle) -> Result<()> {
+    status_to_result(unsafe { android_setprocdns(network.0)? })
+}
+
+/// Turns an `EAI_*` error code returned by `getaddrinfo()` into an [`Error`].
+fn gai_error(code: c_int) -> Error {
+    if code == ffi::EAI_SYSTEM as c_int {
+        return Error::last_os_error();
+    }
+    let msg = unsafe { CStr::from_ptr(ffi::gai_strerror(code)) };
+    Error::new(
+        ErrorKind::Other,
+        format!("getaddrinfo failed: {}", msg.to_string_lossy()),
+    )
+}
+
+/// Converts a raw [`ffi::sockaddr`] of `len` bytes into a [`SocketAddr`], if it is an IPv4 or
+/// IPv6 address.
+///
+/// # Safety
+/// `addr` must point to at least `len` initialized bytes.
+unsafe fn sockaddr_to_socket_addr(addr: *const ffi::sockaddr, len: usize) -> Option<SocketAddr> {
+    // `struct sockaddr_in` and `struct sockaddr_in6` are not part of the generated bindings; their
+    // fields are decoded by offset instead.
+    let bytes = std::slice::from_raw_parts(addr.cast::<u8>(), len);
+    let family = (*addr).sa_family as u32;
+    // sin_port / sin6_port directly follow the 16-bit family and are in network byte order
+    let port = u16::from_be_bytes(bytes.get(2..4)?.try_into().unwrap());
+    if family == ffi::AF_INET {
+        let ip: [u8; 4] = bytes.get(4..8)?.try_into().unwrap();
+        Some(SocketAddrV4::new(Ipv4Addr::from(ip), port).into())
+    } else if family == ffi::AF_INET6 {
+        let flowinfo = u32::from_be_bytes(bytes.get(4..8)?.try_into().unwrap());
+        let ip: [u8; 16] = bytes.get(8..24)?.try_into().unwrap();
+        let scope_id = u32::from_ne_bytes(bytes.get(24..28)?.try_into().unwrap());
+        Some(SocketAddrV6::new(Ipv6Addr::from(ip), port, flowinfo, scope_id).into())
+    } else {
+        None
+    }
+}
+
+/// Performs a `getaddrinfo()` lookup of `node` (and optional `service`, a port number or service
+/// name) on the given `network`, regardless of the process default network.
+///
+/// Only stream-socket results are requested, so that every resolved address is returned exactly
+/// once.
+#[doc(alias = "android_getaddrinfofornetwork")]
+pub fn getaddrinfo_for_network(
+    network: NetworkHandle,
+    node: &CStr,
+    service: Option<&CStr>,
+) -> Result<Vec<SocketAddr>> {
+    let mut hints: ffi::addrinfo = unsafe { std::mem::zeroed() };
+    hints.ai_family = ffi::AF_UNSPEC as c_int;
+    hints.ai_socktype = ffi::SOCK_STREAM as c_int;
+
+    let mut res = ptr::null_mut();
+    let status = unsafe {
+        android_getaddrinfofornetwork(
+            network.0,
+            node.as_ptr(),
+            service.map_or(ptr::null(), |s| s.as_ptr()),
+            &hints,
+            &mut res,
+        )?
+    };
+    if status != 0 {
+        return Err(gai_error(status));
+    }
+
+    let mut addrs = Vec::new();
+    let mut info = res;
+    while let Some(i) = unsafe { info.as_ref() } {
+        if !i.ai_addr.is_null() {
+            if let Some(addr) = unsafe { sockaddr_to_socket_addr(i.ai_addr, i.ai_addrlen as usize) }
+            {
+                addrs.push(addr);
+            }
+        }
+        info = i.ai_next;
+    }
+    unsafe { ffi::freeaddrinfo(res) };
+    Ok(addrs)
+}
+
+#[cfg(feature = "api-level-29")]
+bitflags::bitflags! {
+    /// Flags for [`res_nquery()`] and [`res_nsend()`].
+    #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+    #[doc(alias = "ResNsendFlags")]
+    pub struct ResolvFlags : u32 {
+        /// Send a single request to a single resolver and fail on timeout or network errors.
+        #[doc(alias = "ANDROID_RESOLV_NO_RETRY")]
+        const NO_RETRY = ffi::ResNsendFlags::ANDROID_RESOLV_NO_RETRY.0;
+        /// Do not cache the result of the lookup.
+        ///
+        /// The lookup may return a result that is already in the cache, unless
+        /// [`ResolvFlags::NO_CACHE_LOOKUP`] is also set.
+        #[doc(alias = "ANDROID_RESOLV_NO_CACHE_STORE")]
+        const NO_CACHE_STORE = ffi::ResNsendFlags::ANDROID_RESOLV_NO_CACHE_STORE.0;
+        /// Don't lookup the request in the cache, and do not store the result in the cache.
+        #[doc(alias = "ANDROID_RESOLV_NO_CACHE_LOOKUP")]
+        const NO_CACHE_LOOKUP = ffi::ResNsendFlags::ANDROID_RESOLV_NO_CACHE_LOOKUP.0;
+
+        // https://docs.rs/bitflags/latest/bitflags/#externally-defined-flags
+        const _ = !0;
+    }
+}
+
+/// Look up the `ns_class`/`ns_type` records (for example `1`/`1` for `ns_c_in`/`ns_t_a`) of
+/// `dname` on `network`, asynchronously.
+///
+/// The returned [`DnsQuery`] becomes readable once the answer is available; see
+/// [`DnsQuery::result()`] and [`DnsQuery::register()`].
+#[cfg(feature = "api-level-29")]
+#[doc(alias = "android_res_nquery")]
+pub fn res_nquery(
+    network: NetworkHandle,
+    dname: &CStr,
+    ns_class: i32,
+    ns_type: i32,
+    flags: ResolvFlags,
+) -> Result<DnsQuery> {
+    let fd =
+        unsafe { android_res_nquery(network.0, dname.as_ptr(), ns_class, ns_type, flags.bits())? };
+    DnsQuery::from_status(fd)
+}
+
+/// Send the raw DNS query `msg` on `network`, asynchronously.
+///
+/// The returned [`DnsQuery`] becomes readable once the answer is available; see
+/// [`DnsQuery::result()`] and [`DnsQuery::register()`].
+#[cfg(feature = "api-level-29")]
+#[doc(alias = "android_res_nsend")]
+pub fn res_nsend(network: NetworkHandle, msg: &[u8], flags: ResolvFlags) -> Result<DnsQuery> {
+    let fd = unsafe { android_res_nsend(network.0, msg.as_ptr(), msg.len(), flags.bits())? };
+    DnsQuery::from_status(fd)
+}
+
+/// The answer to a [`DnsQuery`].
+#[cfg(feature = "api-level-29")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DnsResponse {
+    /// The DNS response code (`RCODE`) of the answer.
+    pub rcode: i32,
+    /// The raw DNS answer message.
+    pub answer: Vec<u8>,
+}
+
+/// A pending asynchronous DNS query started by [`res_nquery()`] or [`res_nsend()`].
+///
+/// The query is backed by a file descriptor that becomes readable once the answer is available.
+/// It is cancelled on [`drop()`] unless its result has been read.
+#[cfg(feature = "api-level-29")]
+#[derive(Debug)]
+pub struct DnsQuery {
+    fd: RawFd,
+}
+
+#[cfg(feature = "api-level-29")]
+impl DnsQuery {
+    /// Maximum size of a DNS answer, matching Bionic's `MAXPACKET`.
+    const MAX_ANSWER_SIZE: usize = 8 * 1024;
+
+    fn from_status(fd: c_int) -> Result<Self> {
+        if fd < 0 {
+            Err(Error::from_raw_os_error(-fd))
+        } else {
+            Ok(Self { fd })
+        }
+    }
+
+    /// Reads the answer to this query, blocking until it is available.
+    ///
+    /// Use [`register()`][Self::register()] to receive the answer on a [`ThreadLooper`] without
+    /// blocking.
+    #[doc(alias = "android_res_nresult")]
+    pub fn result(self) -> Result<DnsResponse> {
+        let this = ManuallyDrop::new(self);
+        let mut rcode = 0;
+        let mut answer = vec![0; Self::MAX_ANSWER_SIZE];
+        // Consumes (closes) the file descriptor, regardless of the outcome
+        let len = unsafe {
+            android_res_nresult(this.fd, &mut rcode, answer.as_mut_ptr(), answer.len())
+                .expect(SAME_API_LEVEL)
+        };
+        if len < 0 {
+            Err(Error::from_raw_os_error(-len))
+        } else {
+            answer.truncate(len as usize);
+            Ok(DnsResponse { rcode, answer })
+        }
+    }
+
+    /// Cancels this query, equivalent to [`drop()`]ping it.
+    #[doc(alias = "android_res_cancel")]
+    pub fn cancel(self) {
+        drop(self)
+    }
+
+    /// Registers this query on `looper`, invoking `callback` on the looper thread with the answer
+    /// once it is available.
+    ///
+    /// The query is consumed; it is cancelled if the callback is never invoked because the file
+    /// descriptor reported an error.
+    pub fn register<F: FnOnce(Result<DnsResponse>) + 'static>(
+        self,
+        looper: &ThreadLooper,
+        callback: F,
+    ) -> std::result::Result<(), LooperError> {
+        // SAFETY: The file descriptor stays open until the answer has been read (or the query
+        // cancelled) inside the callback, after which the callback unregisters itself.
+        let fd = unsafe { BorrowedFd::borrow_raw(self.fd) };
+        let foreign = looper.as_foreign().clone();
+        let mut pending = Some((self, callback));
+        looper.add_fd_with_callback(fd, FdEvent::INPUT, move |fd, _events| {
+            // Unregister before the file descriptor is closed by reading the result
+            let _ = foreign.remove_fd(fd);
+            if let Some((query, callback)) = pending.take() {
+                callback(query.result());
+            }
+            false
+        })
+    }
+}
+
+#[cfg(feature = "api-level-29")]
+impl AsFd for DnsQuery {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.fd) }
+    }
+}
+
+#[cfg(feature = "api-level-29")]
+impl AsRawFd for DnsQuery {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+#[cfg(feature = "api-level-29")]
+impl Drop for DnsQuery {
+    #[doc(alias = "android_res_cancel")]
+    fn drop(&mut self) {
+        unsafe { android_res_cancel(self.fd) }.expect(SAME_API_LEVEL)
+    }
+}
@@ -0,0 +1,1824 @@
+//! Bindings for the [Neural Networks API] (NNAPI)
+//!
+//! NNAPI runs computationally intensive machine learning models on the CPU, GPU or dedicated
+//! accelerators of a device. Models are described with a [`ModelBuilder`] in terms of operands and
+//! operations, compiled for the available hardware into a [`Compilation`], and evaluated with an
+//! [`Execution`].
+//!
+//! [Neural Networks API]: https://developer.android.com/ndk/reference/group/neural-networks
+#![cfg(feature = "nnapi")]
+
+#[cfg(feature = "api-level-30")]
+use std::os::unix::io::{FromRawFd, OwnedFd};
+#[cfg(feature = "api-level-29")]
+use std::{
+    ffi::{c_char, CStr},
+    time::Duration,
+};
+use std::{
+    ffi::{c_int, c_void},
+    fmt,
+    marker::PhantomData,
+    mem::{size_of_val, MaybeUninit},
+    // TODO: Import from std::os::fd::{} since Rust 1.66
+    os::unix::io::{AsRawFd, BorrowedFd},
+    ptr::{self, NonNull},
+};
+
+use num_enum::{FromPrimitive, IntoPrimitive};
+
+#[cfg(feature = "api-level-29")]
+use crate::api_level::SAME_API_LEVEL;
+use crate::api_level::{weak_fn, Unsupported};
+#[cfg(feature = "api-level-29")]
+use crate::hardware_buffer::HardwareBuffer;
+use crate::shared_memory::SharedMemory;
+
+weak_fn! {
+    #[cfg(feature = "api-level-28")]
+    fn ANeuralNetworksModel_relaxComputationFloat32toFloat16(
+        model: *mut ffi::ANeuralNetworksModel,
+        allow: bool,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworks_getDeviceCount(num_devices: *mut u32) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworks_getDevice(
+        dev_index: u32,
+        device: *mut *mut ffi::ANeuralNetworksDevice,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksDevice_getName(
+        device: *const ffi::ANeuralNetworksDevice,
+        name: *mut *const c_char,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksDevice_getType(
+        device: *const ffi::ANeuralNetworksDevice,
+        ty: *mut i32,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksDevice_getVersion(
+        device: *const ffi::ANeuralNetworksDevice,
+        version: *mut *const c_char,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksDevice_getFeatureLevel(
+        device: *const ffi::ANeuralNetworksDevice,
+        feature_level: *mut i64,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksMemory_createFromAHardwareBuffer(
+        ahwb: *const ffi::AHardwareBuffer,
+        memory: *mut *mut ffi::ANeuralNetworksMemory,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksModel_setOperandSymmPerChannelQuantParams(
+        model: *mut ffi::ANeuralNetworksModel,
+        index: i32,
+        channel_quant: *const ffi::ANeuralNetworksSymmPerChannelQuantParams,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksModel_getSupportedOperationsForDevices(
+        model: *const ffi::ANeuralNetworksModel,
+        devices: *const *const ffi::ANeuralNetworksDevice,
+        num_devices: u32,
+        supported_ops: *mut bool,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksCompilation_createForDevices(
+        model: *mut ffi::ANeuralNetworksModel,
+        devices: *const *const ffi::ANeuralNetworksDevice,
+        num_devices: u32,
+        compilation: *mut *mut ffi::ANeuralNetworksCompilation,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksCompilation_setCaching(
+        compilation: *mut ffi::ANeuralNetworksCompilation,
+        cache_dir: *const c_char,
+        token: *const u8,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksBurst_create(
+        compilation: *mut ffi::ANeuralNetworksCompilation,
+        burst: *mut *mut ffi::ANeuralNetworksBurst,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksBurst_free(burst: *mut ffi::ANeuralNetworksBurst);
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksExecution_setMeasureTiming(
+        execution: *mut ffi::ANeuralNetworksExecution,
+        measure: bool,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksExecution_compute(
+        execution: *mut ffi::ANeuralNetworksExecution,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksExecution_burstCompute(
+        execution: *mut ffi::ANeuralNetworksExecution,
+        burst: *mut ffi::ANeuralNetworksBurst,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksExecution_getDuration(
+        execution: *const ffi::ANeuralNetworksExecution,
+        duration_code: i32,
+        duration: *mut u64,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksExecution_getOutputOperandRank(
+        execution: *mut ffi::ANeuralNetworksExecution,
+        index: i32,
+        rank: *mut u32,
+    ) -> c_int;
+    #[cfg(feature = "api-level-29")]
+    fn ANeuralNetworksExecution_getOutputOperandDimensions(
+        execution: *mut ffi::ANeuralNetworksExecution,
+        index: i32,
+        dimensions: *mut u32,
+    ) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn ANeuralNetworksDevice_wait(device: *const ffi::ANeuralNetworksDevice) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn ANeuralNetworksMemory_copy(
+        src: *const ffi::ANeuralNetworksMemory,
+        dst: *const ffi::ANeuralNetworksMemory,
+    ) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn ANeuralNetworksCompilation_setPriority(
+        compilation: *mut ffi::ANeuralNetworksCompilation,
+        priority: c_int,
+    ) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn ANeuralNetworksCompilation_setTimeout(
+        compilation: *mut ffi::ANeuralNetworksCompilation,
+        duration: u64,
+    ) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn ANeuralNetworksExecution_setTimeout(
+        execution: *mut ffi::ANeuralNetworksExecution,
+        duration: u64,
+    ) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn ANeuralNetworksExecution_setLoopTimeout(
+        execution: *mut ffi::ANeuralNetworksExecution,
+        duration: u64,
+    ) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn ANeuralNetworksExecution_startComputeWithDependencies(
+        execution: *mut ffi::ANeuralNetworksExecution,
+        dependencies: *const *const ffi::ANeuralNetworksEvent,
+        num_dependencies: u32,
+        duration: u64,
+        event: *mut *mut ffi::ANeuralNetworksEvent,
+    ) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn ANeuralNetworksEvent_createFromSyncFenceFd(
+        sync_fence_fd: c_int,
+        event: *mut *mut ffi::ANeuralNetworksEvent,
+    ) -> c_int;
+    #[cfg(feature = "api-level-30")]
+    fn ANeuralNetworksEvent_getSyncFenceFd(
+        event: *const ffi::ANeuralNetworksEvent,
+        sync_fence_fd: *mut c_int,
+    ) -> c_int;
+    #[cfg(feature = "api-level-31")]
+    fn ANeuralNetworks_getRuntimeFeatureLevel() -> i64;
+    #[cfg(feature = "api-level-31")]
+    fn ANeuralNetworksExecution_setReusable(
+        execution: *mut ffi::ANeuralNetworksExecution,
+        reusable: bool,
+    ) -> c_int;
+}
+
+/// Result codes returned by NNAPI functions.
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[doc(alias = "ResultCode")]
+#[non_exhaustive]
+pub enum NeuralNetworksError {
+    #[doc(alias = "ANEURALNETWORKS_OUT_OF_MEMORY")]
+    OutOfMemory = ffi::ResultCode::ANEURALNETWORKS_OUT_OF_MEMORY.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_INCOMPLETE")]
+    Incomplete = ffi::ResultCode::ANEURALNETWORKS_INCOMPLETE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_UNEXPECTED_NULL")]
+    UnexpectedNull = ffi::ResultCode::ANEURALNETWORKS_UNEXPECTED_NULL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_BAD_DATA")]
+    BadData = ffi::ResultCode::ANEURALNETWORKS_BAD_DATA.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_OP_FAILED")]
+    OpFailed = ffi::ResultCode::ANEURALNETWORKS_OP_FAILED.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_BAD_STATE")]
+    BadState = ffi::ResultCode::ANEURALNETWORKS_BAD_STATE.0 as i32,
+    /// Not all dimensions of a model operand were specified, and could not be deduced.
+    #[doc(alias = "ANEURALNETWORKS_UNMAPPABLE")]
+    Unmappable = ffi::ResultCode::ANEURALNETWORKS_UNMAPPABLE.0 as i32,
+    /// An output buffer provided to an [`Execution`] is too small to hold the result.
+    #[doc(alias = "ANEURALNETWORKS_OUTPUT_INSUFFICIENT_SIZE")]
+    OutputInsufficientSize = ffi::ResultCode::ANEURALNETWORKS_OUTPUT_INSUFFICIENT_SIZE.0 as i32,
+    /// A device that was used to create a [`Compilation`] is no longer available.
+    #[doc(alias = "ANEURALNETWORKS_UNAVAILABLE_DEVICE")]
+    UnavailableDevice = ffi::ResultCode::ANEURALNETWORKS_UNAVAILABLE_DEVICE.0 as i32,
+    /// A timeout or deadline was missed, but retrying may succeed.
+    #[doc(alias = "ANEURALNETWORKS_MISSED_DEADLINE_TRANSIENT")]
+    MissedDeadlineTransient = ffi::ResultCode::ANEURALNETWORKS_MISSED_DEADLINE_TRANSIENT.0 as i32,
+    /// A timeout or deadline was missed, and retrying will not succeed.
+    #[doc(alias = "ANEURALNETWORKS_MISSED_DEADLINE_PERSISTENT")]
+    MissedDeadlinePersistent = ffi::ResultCode::ANEURALNETWORKS_MISSED_DEADLINE_PERSISTENT.0 as i32,
+    /// Resources were temporarily exhausted, but retrying may succeed.
+    #[doc(alias = "ANEURALNETWORKS_RESOURCE_EXHAUSTED_TRANSIENT")]
+    ResourceExhaustedTransient =
+        ffi::ResultCode::ANEURALNETWORKS_RESOURCE_EXHAUSTED_TRANSIENT.0 as i32,
+    /// Resources were exhausted, and retrying will not succeed.
+    #[doc(alias = "ANEURALNETWORKS_RESOURCE_EXHAUSTED_PERSISTENT")]
+    ResourceExhaustedPersistent =
+        ffi::ResultCode::ANEURALNETWORKS_RESOURCE_EXHAUSTED_PERSISTENT.0 as i32,
+    /// The driver service that was executing the request died.
+    #[doc(alias = "ANEURALNETWORKS_DEAD_OBJECT")]
+    DeadObject = ffi::ResultCode::ANEURALNETWORKS_DEAD_OBJECT.0 as i32,
+
+    /// This error code is unknown to the [`ndk`][crate] crate.
+    // Use the NO_ERROR discriminant, as it is never converted into an error.
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32) = ffi::ResultCode::ANEURALNETWORKS_NO_ERROR.0 as i32,
+}
+
+impl fmt::Display for NeuralNetworksError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for NeuralNetworksError {}
+
+impl From<Unsupported> for NeuralNetworksError {
+    /// Functions that do not exist on the current device are reported as
+    /// [`NeuralNetworksError::OpFailed`].
+    fn from(_: Unsupported) -> Self {
+        Self::OpFailed
+    }
+}
+
+impl NeuralNetworksError {
+    /// Returns [`Ok`] on [`ffi::ResultCode::ANEURALNETWORKS_NO_ERROR`], [`Err`] otherwise.
+    pub(crate) fn from_status(status: c_int) -> Result<()> {
+        match status {
+            0 => Ok(()),
+            x => Err(Self::from(x)),
+        }
+    }
+}
+
+pub type Result<T, E = NeuralNetworksError> = std::result::Result<T, E>;
+
+/// The status returned by a function passed to [`construct_never_null()`], which may not exist on
+/// the current device.
+trait Status {
+    fn into_result(self) -> Result<()>;
+}
+
+impl Status for c_int {
+    fn into_result(self) -> Result<()> {
+        NeuralNetworksError::from_status(self)
+    }
+}
+
+impl Status for std::result::Result<c_int, Unsupported> {
+    fn into_result(self) -> Result<()> {
+        NeuralNetworksError::from_status(self?)
+    }
+}
+
+/// Calls the `with_ptr` construction function with a pointer to a pointer, and expects `with_ptr`
+/// to initialize the second pointer to a valid address.
+fn construct_never_null<T, S: Status>(
+    with_ptr: impl FnOnce(*mut *mut T) -> S,
+) -> Result<NonNull<T>> {
+    let mut result = MaybeUninit::uninit();
+    with_ptr(result.as_mut_ptr()).into_result()?;
+    Ok(NonNull::new(unsafe { result.assume_init() }).expect("result should never be null"))
+}
+
+/// Operand data types.
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[non_exhaustive]
+pub enum OperandCode {
+    /// A 32 bit floating point scalar value.
+    #[doc(alias = "ANEURALNETWORKS_FLOAT32")]
+    Float32 = ffi::OperandCode::ANEURALNETWORKS_FLOAT32.0 as i32,
+    /// A signed 32 bit integer scalar value.
+    #[doc(alias = "ANEURALNETWORKS_INT32")]
+    Int32 = ffi::OperandCode::ANEURALNETWORKS_INT32.0 as i32,
+    /// An unsigned 32 bit integer scalar value.
+    #[doc(alias = "ANEURALNETWORKS_UINT32")]
+    Uint32 = ffi::OperandCode::ANEURALNETWORKS_UINT32.0 as i32,
+    /// A tensor of 32 bit floating point values.
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_FLOAT32")]
+    TensorFloat32 = ffi::OperandCode::ANEURALNETWORKS_TENSOR_FLOAT32.0 as i32,
+    /// A tensor of 32 bit integer values.
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_INT32")]
+    TensorInt32 = ffi::OperandCode::ANEURALNETWORKS_TENSOR_INT32.0 as i32,
+    /// A tensor of 8 bit unsigned integers that represent real numbers, using
+    /// [`OperandType::scale`] and [`OperandType::zero_point`].
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_QUANT8_ASYMM")]
+    TensorQuant8Asymm = ffi::OperandCode::ANEURALNETWORKS_TENSOR_QUANT8_ASYMM.0 as i32,
+    /// An 8 bit boolean scalar value.
+    #[doc(alias = "ANEURALNETWORKS_BOOL")]
+    Bool = ffi::OperandCode::ANEURALNETWORKS_BOOL.0 as i32,
+    /// A tensor of 16 bit signed integers that represent real numbers, using
+    /// [`OperandType::scale`].
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_QUANT16_SYMM")]
+    TensorQuant16Symm = ffi::OperandCode::ANEURALNETWORKS_TENSOR_QUANT16_SYMM.0 as i32,
+    /// A tensor of IEEE 754 16 bit floating point values.
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_FLOAT16")]
+    TensorFloat16 = ffi::OperandCode::ANEURALNETWORKS_TENSOR_FLOAT16.0 as i32,
+    /// A tensor of 8 bit boolean values.
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_BOOL8")]
+    TensorBool8 = ffi::OperandCode::ANEURALNETWORKS_TENSOR_BOOL8.0 as i32,
+    /// An IEEE 754 16 bit floating point scalar value.
+    #[doc(alias = "ANEURALNETWORKS_FLOAT16")]
+    Float16 = ffi::OperandCode::ANEURALNETWORKS_FLOAT16.0 as i32,
+    /// A tensor of 8 bit signed integers that represent real numbers, quantized per channel
+    /// with [`ModelBuilder::set_operand_symm_per_channel_quant_params()`].
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_QUANT8_SYMM_PER_CHANNEL")]
+    TensorQuant8SymmPerChannel =
+        ffi::OperandCode::ANEURALNETWORKS_TENSOR_QUANT8_SYMM_PER_CHANNEL.0 as i32,
+    /// A tensor of 16 bit unsigned integers that represent real numbers, using
+    /// [`OperandType::scale`] and [`OperandType::zero_point`].
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_QUANT16_ASYMM")]
+    TensorQuant16Asymm = ffi::OperandCode::ANEURALNETWORKS_TENSOR_QUANT16_ASYMM.0 as i32,
+    /// A tensor of 8 bit signed integers that represent real numbers, using
+    /// [`OperandType::scale`].
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_QUANT8_SYMM")]
+    TensorQuant8Symm = ffi::OperandCode::ANEURALNETWORKS_TENSOR_QUANT8_SYMM.0 as i32,
+    /// A tensor of 8 bit signed integers that represent real numbers, using
+    /// [`OperandType::scale`] and [`OperandType::zero_point`].
+    #[doc(alias = "ANEURALNETWORKS_TENSOR_QUANT8_ASYMM_SIGNED")]
+    TensorQuant8AsymmSigned = ffi::OperandCode::ANEURALNETWORKS_TENSOR_QUANT8_ASYMM_SIGNED.0 as i32,
+    /// A reference to a model, for use with control flow operations.
+    #[doc(alias = "ANEURALNETWORKS_MODEL")]
+    Model = ffi::OperandCode::ANEURALNETWORKS_MODEL.0 as i32,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// Operation types.
+///
+/// See the [NDK documentation] for the operands expected by each operation.
+///
+/// [NDK documentation]: https://developer.android.com/ndk/reference/group/neural-networks#operationcode
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[doc(alias = "ANeuralNetworksOperationType")]
+#[non_exhaustive]
+pub enum OperationCode {
+    #[doc(alias = "ANEURALNETWORKS_ADD")]
+    Add = ffi::OperationCode::ANEURALNETWORKS_ADD.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_AVERAGE_POOL_2D")]
+    AveragePool2d = ffi::OperationCode::ANEURALNETWORKS_AVERAGE_POOL_2D.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_CONCATENATION")]
+    Concatenation = ffi::OperationCode::ANEURALNETWORKS_CONCATENATION.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_CONV_2D")]
+    Conv2d = ffi::OperationCode::ANEURALNETWORKS_CONV_2D.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_DEPTHWISE_CONV_2D")]
+    DepthwiseConv2d = ffi::OperationCode::ANEURALNETWORKS_DEPTHWISE_CONV_2D.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_DEPTH_TO_SPACE")]
+    DepthToSpace = ffi::OperationCode::ANEURALNETWORKS_DEPTH_TO_SPACE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_DEQUANTIZE")]
+    Dequantize = ffi::OperationCode::ANEURALNETWORKS_DEQUANTIZE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_EMBEDDING_LOOKUP")]
+    EmbeddingLookup = ffi::OperationCode::ANEURALNETWORKS_EMBEDDING_LOOKUP.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_FLOOR")]
+    Floor = ffi::OperationCode::ANEURALNETWORKS_FLOOR.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_FULLY_CONNECTED")]
+    FullyConnected = ffi::OperationCode::ANEURALNETWORKS_FULLY_CONNECTED.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_HASHTABLE_LOOKUP")]
+    HashtableLookup = ffi::OperationCode::ANEURALNETWORKS_HASHTABLE_LOOKUP.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_L2_NORMALIZATION")]
+    L2Normalization = ffi::OperationCode::ANEURALNETWORKS_L2_NORMALIZATION.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_L2_POOL_2D")]
+    L2Pool2d = ffi::OperationCode::ANEURALNETWORKS_L2_POOL_2D.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LOCAL_RESPONSE_NORMALIZATION")]
+    LocalResponseNormalization =
+        ffi::OperationCode::ANEURALNETWORKS_LOCAL_RESPONSE_NORMALIZATION.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LOGISTIC")]
+    Logistic = ffi::OperationCode::ANEURALNETWORKS_LOGISTIC.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LSH_PROJECTION")]
+    LshProjection = ffi::OperationCode::ANEURALNETWORKS_LSH_PROJECTION.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LSTM")]
+    Lstm = ffi::OperationCode::ANEURALNETWORKS_LSTM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_MAX_POOL_2D")]
+    MaxPool2d = ffi::OperationCode::ANEURALNETWORKS_MAX_POOL_2D.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_MUL")]
+    Mul = ffi::OperationCode::ANEURALNETWORKS_MUL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RELU")]
+    Relu = ffi::OperationCode::ANEURALNETWORKS_RELU.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RELU1")]
+    Relu1 = ffi::OperationCode::ANEURALNETWORKS_RELU1.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RELU6")]
+    Relu6 = ffi::OperationCode::ANEURALNETWORKS_RELU6.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RESHAPE")]
+    Reshape = ffi::OperationCode::ANEURALNETWORKS_RESHAPE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RESIZE_BILINEAR")]
+    ResizeBilinear = ffi::OperationCode::ANEURALNETWORKS_RESIZE_BILINEAR.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RNN")]
+    Rnn = ffi::OperationCode::ANEURALNETWORKS_RNN.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SOFTMAX")]
+    Softmax = ffi::OperationCode::ANEURALNETWORKS_SOFTMAX.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SPACE_TO_DEPTH")]
+    SpaceToDepth = ffi::OperationCode::ANEURALNETWORKS_SPACE_TO_DEPTH.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SVDF")]
+    Svdf = ffi::OperationCode::ANEURALNETWORKS_SVDF.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_TANH")]
+    Tanh = ffi::OperationCode::ANEURALNETWORKS_TANH.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_BATCH_TO_SPACE_ND")]
+    BatchToSpaceNd = ffi::OperationCode::ANEURALNETWORKS_BATCH_TO_SPACE_ND.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_DIV")]
+    Div = ffi::OperationCode::ANEURALNETWORKS_DIV.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_MEAN")]
+    Mean = ffi::OperationCode::ANEURALNETWORKS_MEAN.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_PAD")]
+    Pad = ffi::OperationCode::ANEURALNETWORKS_PAD.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SPACE_TO_BATCH_ND")]
+    SpaceToBatchNd = ffi::OperationCode::ANEURALNETWORKS_SPACE_TO_BATCH_ND.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SQUEEZE")]
+    Squeeze = ffi::OperationCode::ANEURALNETWORKS_SQUEEZE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_STRIDED_SLICE")]
+    StridedSlice = ffi::OperationCode::ANEURALNETWORKS_STRIDED_SLICE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SUB")]
+    Sub = ffi::OperationCode::ANEURALNETWORKS_SUB.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_TRANSPOSE")]
+    Transpose = ffi::OperationCode::ANEURALNETWORKS_TRANSPOSE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_ABS")]
+    Abs = ffi::OperationCode::ANEURALNETWORKS_ABS.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_ARGMAX")]
+    Argmax = ffi::OperationCode::ANEURALNETWORKS_ARGMAX.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_ARGMIN")]
+    Argmin = ffi::OperationCode::ANEURALNETWORKS_ARGMIN.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_AXIS_ALIGNED_BBOX_TRANSFORM")]
+    AxisAlignedBboxTransform =
+        ffi::OperationCode::ANEURALNETWORKS_AXIS_ALIGNED_BBOX_TRANSFORM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_BIDIRECTIONAL_SEQUENCE_LSTM")]
+    BidirectionalSequenceLstm =
+        ffi::OperationCode::ANEURALNETWORKS_BIDIRECTIONAL_SEQUENCE_LSTM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_BIDIRECTIONAL_SEQUENCE_RNN")]
+    BidirectionalSequenceRnn =
+        ffi::OperationCode::ANEURALNETWORKS_BIDIRECTIONAL_SEQUENCE_RNN.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_BOX_WITH_NMS_LIMIT")]
+    BoxWithNmsLimit = ffi::OperationCode::ANEURALNETWORKS_BOX_WITH_NMS_LIMIT.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_CAST")]
+    Cast = ffi::OperationCode::ANEURALNETWORKS_CAST.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_CHANNEL_SHUFFLE")]
+    ChannelShuffle = ffi::OperationCode::ANEURALNETWORKS_CHANNEL_SHUFFLE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_DETECTION_POSTPROCESSING")]
+    DetectionPostprocessing = ffi::OperationCode::ANEURALNETWORKS_DETECTION_POSTPROCESSING.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_EQUAL")]
+    Equal = ffi::OperationCode::ANEURALNETWORKS_EQUAL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_EXP")]
+    Exp = ffi::OperationCode::ANEURALNETWORKS_EXP.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_EXPAND_DIMS")]
+    ExpandDims = ffi::OperationCode::ANEURALNETWORKS_EXPAND_DIMS.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_GATHER")]
+    Gather = ffi::OperationCode::ANEURALNETWORKS_GATHER.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_GENERATE_PROPOSALS")]
+    GenerateProposals = ffi::OperationCode::ANEURALNETWORKS_GENERATE_PROPOSALS.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_GREATER")]
+    Greater = ffi::OperationCode::ANEURALNETWORKS_GREATER.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_GREATER_EQUAL")]
+    GreaterEqual = ffi::OperationCode::ANEURALNETWORKS_GREATER_EQUAL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_GROUPED_CONV_2D")]
+    GroupedConv2d = ffi::OperationCode::ANEURALNETWORKS_GROUPED_CONV_2D.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_HEATMAP_MAX_KEYPOINT")]
+    HeatmapMaxKeypoint = ffi::OperationCode::ANEURALNETWORKS_HEATMAP_MAX_KEYPOINT.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_INSTANCE_NORMALIZATION")]
+    InstanceNormalization = ffi::OperationCode::ANEURALNETWORKS_INSTANCE_NORMALIZATION.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LESS")]
+    Less = ffi::OperationCode::ANEURALNETWORKS_LESS.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LESS_EQUAL")]
+    LessEqual = ffi::OperationCode::ANEURALNETWORKS_LESS_EQUAL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LOG")]
+    Log = ffi::OperationCode::ANEURALNETWORKS_LOG.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LOGICAL_AND")]
+    LogicalAnd = ffi::OperationCode::ANEURALNETWORKS_LOGICAL_AND.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LOGICAL_NOT")]
+    LogicalNot = ffi::OperationCode::ANEURALNETWORKS_LOGICAL_NOT.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LOGICAL_OR")]
+    LogicalOr = ffi::OperationCode::ANEURALNETWORKS_LOGICAL_OR.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_LOG_SOFTMAX")]
+    LogSoftmax = ffi::OperationCode::ANEURALNETWORKS_LOG_SOFTMAX.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_MAXIMUM")]
+    Maximum = ffi::OperationCode::ANEURALNETWORKS_MAXIMUM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_MINIMUM")]
+    Minimum = ffi::OperationCode::ANEURALNETWORKS_MINIMUM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_NEG")]
+    Neg = ffi::OperationCode::ANEURALNETWORKS_NEG.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_NOT_EQUAL")]
+    NotEqual = ffi::OperationCode::ANEURALNETWORKS_NOT_EQUAL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_PAD_V2")]
+    PadV2 = ffi::OperationCode::ANEURALNETWORKS_PAD_V2.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_POW")]
+    Pow = ffi::OperationCode::ANEURALNETWORKS_POW.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_PRELU")]
+    Prelu = ffi::OperationCode::ANEURALNETWORKS_PRELU.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_QUANTIZE")]
+    Quantize = ffi::OperationCode::ANEURALNETWORKS_QUANTIZE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_QUANTIZED_16BIT_LSTM")]
+    Quantized16bitLstm = ffi::OperationCode::ANEURALNETWORKS_QUANTIZED_16BIT_LSTM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RANDOM_MULTINOMIAL")]
+    RandomMultinomial = ffi::OperationCode::ANEURALNETWORKS_RANDOM_MULTINOMIAL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_REDUCE_ALL")]
+    ReduceAll = ffi::OperationCode::ANEURALNETWORKS_REDUCE_ALL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_REDUCE_ANY")]
+    ReduceAny = ffi::OperationCode::ANEURALNETWORKS_REDUCE_ANY.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_REDUCE_MAX")]
+    ReduceMax = ffi::OperationCode::ANEURALNETWORKS_REDUCE_MAX.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_REDUCE_MIN")]
+    ReduceMin = ffi::OperationCode::ANEURALNETWORKS_REDUCE_MIN.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_REDUCE_PROD")]
+    ReduceProd = ffi::OperationCode::ANEURALNETWORKS_REDUCE_PROD.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_REDUCE_SUM")]
+    ReduceSum = ffi::OperationCode::ANEURALNETWORKS_REDUCE_SUM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_ROI_ALIGN")]
+    RoiAlign = ffi::OperationCode::ANEURALNETWORKS_ROI_ALIGN.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_ROI_POOLING")]
+    RoiPooling = ffi::OperationCode::ANEURALNETWORKS_ROI_POOLING.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RSQRT")]
+    Rsqrt = ffi::OperationCode::ANEURALNETWORKS_RSQRT.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SELECT")]
+    Select = ffi::OperationCode::ANEURALNETWORKS_SELECT.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SIN")]
+    Sin = ffi::OperationCode::ANEURALNETWORKS_SIN.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SLICE")]
+    Slice = ffi::OperationCode::ANEURALNETWORKS_SLICE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SPLIT")]
+    Split = ffi::OperationCode::ANEURALNETWORKS_SPLIT.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_SQRT")]
+    Sqrt = ffi::OperationCode::ANEURALNETWORKS_SQRT.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_TILE")]
+    Tile = ffi::OperationCode::ANEURALNETWORKS_TILE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_TOPK_V2")]
+    TopkV2 = ffi::OperationCode::ANEURALNETWORKS_TOPK_V2.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_TRANSPOSE_CONV_2D")]
+    TransposeConv2d = ffi::OperationCode::ANEURALNETWORKS_TRANSPOSE_CONV_2D.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_UNIDIRECTIONAL_SEQUENCE_LSTM")]
+    UnidirectionalSequenceLstm =
+        ffi::OperationCode::ANEURALNETWORKS_UNIDIRECTIONAL_SEQUENCE_LSTM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_UNIDIRECTIONAL_SEQUENCE_RNN")]
+    UnidirectionalSequenceRnn =
+        ffi::OperationCode::ANEURALNETWORKS_UNIDIRECTIONAL_SEQUENCE_RNN.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RESIZE_NEAREST_NEIGHBOR")]
+    ResizeNearestNeighbor = ffi::OperationCode::ANEURALNETWORKS_RESIZE_NEAREST_NEIGHBOR.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_QUANTIZED_LSTM")]
+    QuantizedLstm = ffi::OperationCode::ANEURALNETWORKS_QUANTIZED_LSTM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_IF")]
+    If = ffi::OperationCode::ANEURALNETWORKS_IF.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_WHILE")]
+    While = ffi::OperationCode::ANEURALNETWORKS_WHILE.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_ELU")]
+    Elu = ffi::OperationCode::ANEURALNETWORKS_ELU.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_HARD_SWISH")]
+    HardSwish = ffi::OperationCode::ANEURALNETWORKS_HARD_SWISH.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_FILL")]
+    Fill = ffi::OperationCode::ANEURALNETWORKS_FILL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_RANK")]
+    Rank = ffi::OperationCode::ANEURALNETWORKS_RANK.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_BATCH_MATMUL")]
+    BatchMatmul = ffi::OperationCode::ANEURALNETWORKS_BATCH_MATMUL.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_PACK")]
+    Pack = ffi::OperationCode::ANEURALNETWORKS_PACK.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_MIRROR_PAD")]
+    MirrorPad = ffi::OperationCode::ANEURALNETWORKS_MIRROR_PAD.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_REVERSE")]
+    Reverse = ffi::OperationCode::ANEURALNETWORKS_REVERSE.0 as i32,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// Fused activation function types, passed as [`OperandCode::Int32`] scalar operand to many
+/// operations.
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[non_exhaustive]
+pub enum FuseCode {
+    /// No fused activation function.
+    #[doc(alias = "ANEURALNETWORKS_FUSED_NONE")]
+    None = ffi::FuseCode::ANEURALNETWORKS_FUSED_NONE.0 as i32,
+    /// Fused ReLU activation function.
+    #[doc(alias = "ANEURALNETWORKS_FUSED_RELU")]
+    Relu = ffi::FuseCode::ANEURALNETWORKS_FUSED_RELU.0 as i32,
+    /// Fused ReLU1 activation function.
+    #[doc(alias = "ANEURALNETWORKS_FUSED_RELU1")]
+    Relu1 = ffi::FuseCode::ANEURALNETWORKS_FUSED_RELU1.0 as i32,
+    /// Fused ReLU6 activation function.
+    #[doc(alias = "ANEURALNETWORKS_FUSED_RELU6")]
+    Relu6 = ffi::FuseCode::ANEURALNETWORKS_FUSED_RELU6.0 as i32,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// Implicit padding algorithms, passed as [`OperandCode::Int32`] scalar operand to convolution
+/// and pooling operations.
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[non_exhaustive]
+pub enum PaddingCode {
+    /// Pad so that the output has the same spatial size as the input (with a stride of `1`).
+    #[doc(alias = "ANEURALNETWORKS_PADDING_SAME")]
+    Same = ffi::PaddingCode::ANEURALNETWORKS_PADDING_SAME.0 as i32,
+    /// No padding.
+    #[doc(alias = "ANEURALNETWORKS_PADDING_VALID")]
+    Valid = ffi::PaddingCode::ANEURALNETWORKS_PADDING_VALID.0 as i32,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// Execution preferences, see [`CompilationBuilder::set_preference()`].
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[doc(alias = "PreferenceCode")]
+#[non_exhaustive]
+pub enum ExecutionPreference {
+    /// Prefer executing in a way that minimizes battery drain.
+    #[doc(alias = "ANEURALNETWORKS_PREFER_LOW_POWER")]
+    LowPower = ffi::PreferenceCode::ANEURALNETWORKS_PREFER_LOW_POWER.0 as i32,
+    /// Prefer returning a single answer as fast as possible, even if this causes more power
+    /// consumption. This is the default.
+    #[doc(alias = "ANEURALNETWORKS_PREFER_FAST_SINGLE_ANSWER")]
+    FastSingleAnswer = ffi::PreferenceCode::ANEURALNETWORKS_PREFER_FAST_SINGLE_ANSWER.0 as i32,
+    /// Prefer maximizing the throughput of successive frames, for example when processing
+    /// successive frames coming from the camera.
+    #[doc(alias = "ANEURALNETWORKS_PREFER_SUSTAINED_SPEED")]
+    SustainedSpeed = ffi::PreferenceCode::ANEURALNETWORKS_PREFER_SUSTAINED_SPEED.0 as i32,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// Relative execution priority, see [`CompilationBuilder::set_priority()`].
+#[cfg(feature = "api-level-30")]
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[doc(alias = "PriorityCode")]
+#[non_exhaustive]
+pub enum Priority {
+    #[doc(alias = "ANEURALNETWORKS_PRIORITY_LOW")]
+    Low = ffi::PriorityCode::ANEURALNETWORKS_PRIORITY_LOW.0 as i32,
+    /// The default priority.
+    #[doc(alias = "ANEURALNETWORKS_PRIORITY_MEDIUM")]
+    #[doc(alias = "ANEURALNETWORKS_PRIORITY_DEFAULT")]
+    Medium = ffi::PriorityCode::ANEURALNETWORKS_PRIORITY_MEDIUM.0 as i32,
+    #[doc(alias = "ANEURALNETWORKS_PRIORITY_HIGH")]
+    High = ffi::PriorityCode::ANEURALNETWORKS_PRIORITY_HIGH.0 as i32,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// Device types, see [`Device::device_type()`].
+#[cfg(feature = "api-level-29")]
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[doc(alias = "DeviceTypeCode")]
+#[non_exhaustive]
+pub enum DeviceType {
+    /// The device type cannot be provided.
+    #[doc(alias = "ANEURALNETWORKS_DEVICE_UNKNOWN")]
+    Unknown = ffi::DeviceTypeCode::ANEURALNETWORKS_DEVICE_UNKNOWN.0 as i32,
+    /// The device does not fall into any category below.
+    #[doc(alias = "ANEURALNETWORKS_DEVICE_OTHER")]
+    Other = ffi::DeviceTypeCode::ANEURALNETWORKS_DEVICE_OTHER.0 as i32,
+    /// The device runs NNAPI models on single or multi-core CPU.
+    #[doc(alias = "ANEURALNETWORKS_DEVICE_CPU")]
+    Cpu = ffi::DeviceTypeCode::ANEURALNETWORKS_DEVICE_CPU.0 as i32,
+    /// The device can run NNAPI models and also accelerate graphics APIs such as OpenGL ES and
+    /// Vulkan.
+    #[doc(alias = "ANEURALNETWORKS_DEVICE_GPU")]
+    Gpu = ffi::DeviceTypeCode::ANEURALNETWORKS_DEVICE_GPU.0 as i32,
+    /// Dedicated accelerator for Machine Learning workloads.
+    #[doc(alias = "ANEURALNETWORKS_DEVICE_ACCELERATOR")]
+    Accelerator = ffi::DeviceTypeCode::ANEURALNETWORKS_DEVICE_ACCELERATOR.0 as i32,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// Different duration measurements, see [`Execution::duration()`].
+#[cfg(feature = "api-level-29")]
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive, IntoPrimitive)]
+#[non_exhaustive]
+pub enum DurationCode {
+    /// Execution time on hardware (not driver, which runs on host processor).
+    #[doc(alias = "ANEURALNETWORKS_DURATION_ON_HARDWARE")]
+    OnHardware = ffi::DurationCode::ANEURALNETWORKS_DURATION_ON_HARDWARE.0 as i32,
+    /// Execution time in driver (including time on hardware). Excludes overhead such as that of
+    /// the runtime itself and the IPC needed for the runtime to communicate with the driver.
+    #[doc(alias = "ANEURALNETWORKS_DURATION_IN_DRIVER")]
+    InDriver = ffi::DurationCode::ANEURALNETWORKS_DURATION_IN_DRIVER.0 as i32,
+    /// Execution time on hardware, after all dependencies have been signaled.
+    #[doc(alias = "ANEURALNETWORKS_FENCED_DURATION_ON_HARDWARE")]
+    FencedOnHardware = ffi::DurationCode::ANEURALNETWORKS_FENCED_DURATION_ON_HARDWARE.0 as i32,
+    /// Execution time in driver, after all dependencies have been signaled.
+    #[doc(alias = "ANEURALNETWORKS_FENCED_DURATION_IN_DRIVER")]
+    FencedInDriver = ffi::DurationCode::ANEURALNETWORKS_FENCED_DURATION_IN_DRIVER.0 as i32,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+/// The type of an operand in a model, see [`ModelBuilder::add_operand()`].
+#[derive(Clone, Debug, PartialEq)]
+#[doc(alias = "ANeuralNetworksOperandType")]
+pub struct OperandType {
+    /// The data type of the operand.
+    pub code: OperandCode,
+    /// The dimensions of a tensor, empty for scalars.
+    ///
+    /// A dimension of `0` means that its size is unspecified, and will be deduced or provided at
+    /// execution time.
+    pub dimensions: Vec<u32>,
+    /// The quantization scale, only used by quantized tensor types.
+    pub scale: f32,
+    /// The quantization zero point, only used by asymmetric quantized tensor types.
+    pub zero_point: i32,
+}
+
+impl OperandType {
+    /// Creates the type of a scalar operand.
+    pub fn scalar(code: OperandCode) -> Self {
+        Self::tensor(code, &[])
+    }
+
+    /// Creates the type of a tensor operand with the given `dimensions`.
+    pub fn tensor(code: OperandCode, dimensions: &[u32]) -> Self {
+        Self {
+            code,
+            dimensions: dimensions.to_vec(),
+            scale: 0.0,
+            zero_point: 0,
+        }
+    }
+
+    /// Sets the quantization `scale` and `zero_point` of a quantized tensor type.
+    pub fn quantized(mut self, scale: f32, zero_point: i32) -> Self {
+        self.scale = scale;
+        self.zero_point = zero_point;
+        self
+    }
+
+    /// Returns the FFI representation, which borrows [`Self::dimensions`].
+    fn as_ffi(&self) -> ffi::ANeuralNetworksOperandType {
+        ffi::ANeuralNetworksOperandType {
+            type_: self.code.into(),
+            dimensionCount: self.dimensions.len() as u32,
+            dimensions: if self.dimensions.is_empty() {
+                ptr::null()
+            } else {
+                self.dimensions.as_ptr()
+            },
+            scale: self.scale,
+            zeroPoint: self.zero_point,
+        }
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// An element type of operand values and input buffers, see [`ModelBuilder::set_operand_value()`]
+/// and [`Execution::set_input()`].
+///
+/// This trait is sealed and implemented for the primitive types of the [`OperandCode`]s, none of
+/// which contain padding bytes.
+pub trait OperandElement: Copy + private::Sealed {}
+
+/// An [`OperandElement`] for which every bit pattern is valid, so that NNAPI can write it to
+/// output buffers, see [`Execution::set_output()`].
+pub trait OutputElement: OperandElement {}
+
+macro_rules! operand_elements {
+    ($($ty:ty),*) => {$(
+        impl private::Sealed for $ty {}
+        impl OperandElement for $ty {}
+        impl OutputElement for $ty {}
+    )*};
+}
+
+operand_elements!(f32, i32, u32, i8, u8, i16, u16);
+
+impl private::Sealed for bool {}
+impl OperandElement for bool {}
+
+/// Returns the feature level of the NNAPI runtime on this device, which can be compared against
+/// [`Device::feature_level()`].
+#[cfg(feature = "api-level-31")]
+#[doc(alias = "ANeuralNetworks_getRuntimeFeatureLevel")]
+pub fn runtime_feature_level() -> std::result::Result<i64, Unsupported> {
+    unsafe { ANeuralNetworks_getRuntimeFeatureLevel() }
+}
+
+/// Returns all NNAPI [`Device`]s available on this system.
+#[cfg(feature = "api-level-29")]
+#[doc(alias = "ANeuralNetworks_getDeviceCount")]
+#[doc(alias = "ANeuralNetworks_getDevice")]
+pub fn devices() -> Result<Vec<Device>> {
+    let mut count = 0;
+    NeuralNetworksError::from_status(unsafe { ANeuralNetworks_getDeviceCount(&mut count)? })?;
+    (0..count)
+        .map(|i| {
+            construct_never_null(|res| unsafe { ANeuralNetworks_getDevice(i, res) })
+                .map(|ptr| Device { ptr })
+        })
+        .collect()
+}
+
+/// A device (CPU, GPU, accelerator) that can run NNAPI models, obtained from [`devices()`].
+#[cfg(feature = "api-level-29")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[doc(alias = "ANeuralNetworksDevice")]
+pub struct Device {
+    ptr: NonNull<ffi::ANeuralNetworksDevice>,
+}
+
+// SAFETY: Devices are immutable process-wide singletons owned by the NNAPI runtime.
+#[cfg(feature = "api-level-29")]
+unsafe impl Send for Device {}
+#[cfg(feature = "api-level-29")]
+unsafe impl Sync for Device {}
+
+#[cfg(feature = "api-level-29")]
+impl Device {
+    /// The name of the device, which is unique for every device on the system.
+    #[doc(alias = "ANeuralNetworksDevice_getName")]
+    pub fn name(&self) -> Result<&'static CStr> {
+        let mut name = ptr::null();
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksDevice_getName(self.ptr.as_ptr(), &mut name)?
+        })?;
+        // SAFETY: The name remains valid for the lifetime of the application
+        Ok(unsafe { CStr::from_ptr(name) })
+    }
+
+    /// The type of the device.
+    #[doc(alias = "ANeuralNetworksDevice_getType")]
+    pub fn device_type(&self) -> Result<DeviceType> {
+        let mut ty = 0;
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksDevice_getType(self.ptr.as_ptr(), &mut ty)?
+        })?;
+        Ok(ty.into())
+    }
+
+    /// The version of the driver implementation of the device.
+    #[doc(alias = "ANeuralNetworksDevice_getVersion")]
+    pub fn version(&self) -> Result<&'static CStr> {
+        let mut version = ptr::null();
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksDevice_getVersion(self.ptr.as_ptr(), &mut version)?
+        })?;
+        // SAFETY: The version remains valid for the lifetime of the application
+        Ok(unsafe { CStr::from_ptr(version) })
+    }
+
+    /// The NNAPI feature level supported by the driver of this device, for example `29` when all
+    /// features of API level 29 are supported.
+    #[doc(alias = "ANeuralNetworksDevice_getFeatureLevel")]
+    pub fn feature_level(&self) -> Result<i64> {
+        let mut feature_level = 0;
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksDevice_getFeatureLevel(self.ptr.as_ptr(), &mut feature_level)?
+        })?;
+        Ok(feature_level)
+    }
+
+    /// Blocks until the device is available, for example after it has restarted.
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "ANeuralNetworksDevice_wait")]
+    pub fn wait(&self) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe { ANeuralNetworksDevice_wait(self.ptr.as_ptr())? })
+    }
+}
+
+/// Shared memory that can hold constant operand values of a model, or the inputs and outputs of
+/// an [`Execution`].
+///
+/// Using shared memory avoids copies of large constants, and allows hardware buffers to be passed
+/// to a driver directly.
+#[derive(Debug)]
+#[doc(alias = "ANeuralNetworksMemory")]
+pub struct Memory {
+    ptr: NonNull<ffi::ANeuralNetworksMemory>,
+}
+
+// SAFETY: A memory object is an immutable reference to a shared region that is safe to use from
+// any thread.
+unsafe impl Send for Memory {}
+unsafe impl Sync for Memory {}
+
+impl Memory {
+    /// Creates a memory object from `size` bytes of the file descriptor `fd`, starting at
+    /// `offset`.
+    ///
+    /// `protect` specifies the `PROT_*` flags with which the region is mapped. The file descriptor
+    /// is duplicated and may be closed after this call.
+    #[doc(alias = "ANeuralNetworksMemory_createFromFd")]
+    pub fn from_fd(size: usize, protect: i32, fd: BorrowedFd<'_>, offset: usize) -> Result<Self> {
+        let ptr = construct_never_null(|res| unsafe {
+            ffi::ANeuralNetworksMemory_createFromFd(size, protect, fd.as_raw_fd(), offset, res)
+        })?;
+        Ok(Self { ptr })
+    }
+
+    /// Creates a memory object from the entire region of `shared_memory`.
+    ///
+    /// `protect` specifies the `PROT_*` flags with which the region is mapped, and should match
+    /// what was configured with [`SharedMemory::set_prot()`].
+    pub fn from_shared_memory(shared_memory: &SharedMemory, protect: i32) -> Result<Self> {
+        use std::os::unix::io::AsFd;
+        Self::from_fd(shared_memory.size()?, protect, shared_memory.as_fd(), 0)
+    }
+
+    /// Creates a memory object from a [`HardwareBuffer`].
+    ///
+    /// Only [`HardwareBufferFormat::BLOB`][crate::hardware_buffer_format::HardwareBufferFormat::BLOB]
+    /// buffers can be used for model constants and with [`Execution`]s to any device; other
+    /// formats can only be used with [`Execution`]s on devices supporting them.
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksMemory_createFromAHardwareBuffer")]
+    pub fn from_hardware_buffer(buffer: &HardwareBuffer) -> Result<Self> {
+        let ptr = construct_never_null(|res| unsafe {
+            ANeuralNetworksMemory_createFromAHardwareBuffer(buffer.as_ptr(), res)
+        })?;
+        Ok(Self { ptr })
+    }
+
+    /// Copies the contents of this memory object into `dst`.
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "ANeuralNetworksMemory_copy")]
+    pub fn copy_to(&self, dst: &Memory) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksMemory_copy(self.ptr.as_ptr(), dst.ptr.as_ptr())?
+        })
+    }
+}
+
+impl Drop for Memory {
+    #[doc(alias = "ANeuralNetworksMemory_free")]
+    fn drop(&mut self) {
+        unsafe { ffi::ANeuralNetworksMemory_free(self.ptr.as_ptr()) }
+    }
+}
+
+/// Builds a [`Model`] out of operands and operations.
+///
+/// Operands are added with [`add_operand()`][Self::add_operand()], which returns their index.
+/// Constant operands receive their value through [`set_operand_value()`][Self::set_operand_value()]
+/// or [`set_operand_value_from_memory()`][Self::set_operand_value_from_memory()]. Operations
+/// connect operands with [`add_operation()`][Self::add_operation()], and the model inputs and
+/// outputs are declared with
+/// [`identify_inputs_and_outputs()`][Self::identify_inputs_and_outputs()].
+///
+/// ```no_run
+/// # use ndk::nnapi::*;
+/// # fn main() -> Result<()> {
+/// let mut builder = ModelBuilder::new()?;
+/// let tensor = OperandType::tensor(OperandCode::TensorFloat32, &[1, 4]);
+/// let a = builder.add_operand(&tensor)?;
+/// let b = builder.add_operand(&tensor)?;
+/// let activation = builder.add_operand(&OperandType::scalar(OperandCode::Int32))?;
+/// let sum = builder.add_operand(&tensor)?;
+/// builder.set_operand_value(b, &[1.0f32, 2.0, 3.0, 4.0])?;
+/// builder.set_operand_value(activation, &[i32::from(FuseCode::None)])?;
+/// builder.add_operation(OperationCode::Add, &[a, b, activation], &[sum])?;
+/// builder.identify_inputs_and_outputs(&[a], &[sum])?;
+/// let model = builder.finish()?;
+///
+/// let compilation = CompilationBuilder::new(&model)?.finish()?;
+/// let input = [1.0f32; 4];
+/// let mut output = [0.0f32; 4];
+/// let mut execution = Execution::new(&compilation)?;
+/// execution.set_input(0, &input)?;
+/// execution.set_output(0, &mut output)?;
+/// execution.compute()?;
+/// drop(execution);
+/// assert_eq!(output, [2.0, 3.0, 4.0, 5.0]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+#[doc(alias = "ANeuralNetworksModel")]
+pub struct ModelBuilder<'m> {
+    model: Model<'m>,
+}
+
+impl<'m> ModelBuilder<'m> {
+    /// Creates an empty model.
+    #[doc(alias = "ANeuralNetworksModel_create")]
+    pub fn new() -> Result<Self> {
+        let ptr = construct_never_null(|res| unsafe { ffi::ANeuralNetworksModel_create(res) })?;
+        Ok(Self {
+            model: Model {
+                ptr,
+                operand_count: 0,
+                operation_count: 0,
+                values: Vec::new(),
+                _memories: PhantomData,
+            },
+        })
+    }
+
+    fn as_ptr(&self) -> *mut ffi::ANeuralNetworksModel {
+        self.model.ptr.as_ptr()
+    }
+
+    /// Adds an operand of the given type to the model, and returns its index.
+    ///
+    /// Operands are numbered in the order in which they are added, starting at `0`.
+    #[doc(alias = "ANeuralNetworksModel_addOperand")]
+    pub fn add_operand(&mut self, operand_type: &OperandType) -> Result<u32> {
+        let ty = operand_type.as_ffi();
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksModel_addOperand(self.as_ptr(), &ty)
+        })?;
+        let index = self.model.operand_count;
+        self.model.operand_count += 1;
+        Ok(index)
+    }
+
+    /// Sets the constant value of the operand at `index` to the contents of `value`.
+    ///
+    /// The value is copied and kept alive together with the model, regardless of its size.
+    #[doc(alias = "ANeuralNetworksModel_setOperandValue")]
+    pub fn set_operand_value<T: OperandElement>(&mut self, index: u32, value: &[T]) -> Result<()> {
+        let len = size_of_val(value);
+        // SAFETY: `OperandElement` types have no padding, so all of their bytes are initialized
+        let bytes: Box<[u8]> =
+            unsafe { std::slice::from_raw_parts(value.as_ptr().cast::<u8>(), len) }.into();
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksModel_setOperandValue(
+                self.as_ptr(),
+                index as i32,
+                bytes.as_ptr().cast(),
+                len,
+            )
+        })?;
+        // Values larger than ANEURALNETWORKS_MAX_SIZE_OF_IMMEDIATELY_COPIED_VALUES are referenced
+        // rather than copied by NNAPI, and have to outlive the model.
+        self.model.values.push(bytes);
+        Ok(())
+    }
+
+    /// Marks the operand at `index` as optional, by giving it no value.
+    #[doc(alias = "ANeuralNetworksModel_setOperandValue")]
+    pub fn set_operand_value_omitted(&mut self, index: u32) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksModel_setOperandValue(self.as_ptr(), index as i32, ptr::null(), 0)
+        })
+    }
+
+    /// Sets the constant value of the operand at `index` to `length` bytes of `memory`, starting
+    /// at `offset`.
+    ///
+    /// The contents of `memory` are not copied and must not be modified until all executions
+    /// using this model have completed.
+    #[doc(alias = "ANeuralNetworksModel_setOperandValueFromMemory")]
+    pub fn set_operand_value_from_memory(
+        &mut self,
+        index: u32,
+        memory: &'m Memory,
+        offset: usize,
+        length: usize,
+    ) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksModel_setOperandValueFromMemory(
+                self.as_ptr(),
+                index as i32,
+                memory.ptr.as_ptr(),
+                offset,
+                length,
+            )
+        })
+    }
+
+    /// Sets the per-channel quantization `scales` of an
+    /// [`OperandCode::TensorQuant8SymmPerChannel`] operand at `index`, along dimension
+    /// `channel_dim`.
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksModel_setOperandSymmPerChannelQuantParams")]
+    pub fn set_operand_symm_per_channel_quant_params(
+        &mut self,
+        index: u32,
+        channel_dim: u32,
+        scales: &[f32],
+    ) -> Result<()> {
+        let params = ffi::ANeuralNetworksSymmPerChannelQuantParams {
+            channelDim: channel_dim,
+            scaleCount: scales.len() as u32,
+            scales: scales.as_ptr(),
+        };
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksModel_setOperandSymmPerChannelQuantParams(
+                self.as_ptr(),
+                index as i32,
+                &params,
+            )?
+        })
+    }
+
+    /// Adds an operation of type `operation` to the model, consuming the operands at indices
+    /// `inputs` and producing the operands at indices `outputs`.
+    #[doc(alias = "ANeuralNetworksModel_addOperation")]
+    pub fn add_operation(
+        &mut self,
+        operation: OperationCode,
+        inputs: &[u32],
+        outputs: &[u32],
+    ) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksModel_addOperation(
+                self.as_ptr(),
+                operation.into(),
+                inputs.len() as u32,
+                inputs.as_ptr(),
+                outputs.len() as u32,
+                outputs.as_ptr(),
+            )
+        })?;
+        self.model.operation_count += 1;
+        Ok(())
+    }
+
+    /// Specifies which operands are the inputs and outputs of the model.
+    ///
+    /// The position of an operand in `inputs` and `outputs` is the index by which it is referred
+    /// to in [`Execution::set_input()`] and [`Execution::set_output()`].
+    #[doc(alias = "ANeuralNetworksModel_identifyInputsAndOutputs")]
+    pub fn identify_inputs_and_outputs(&mut self, inputs: &[u32], outputs: &[u32]) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksModel_identifyInputsAndOutputs(
+                self.as_ptr(),
+                inputs.len() as u32,
+                inputs.as_ptr(),
+                outputs.len() as u32,
+                outputs.as_ptr(),
+            )
+        })
+    }
+
+    /// Specifies whether [`OperandCode::TensorFloat32`] is allowed to be calculated with range
+    /// and/or precision as low as that of the IEEE 754 16 bit floating-point format.
+    #[cfg(feature = "api-level-28")]
+    #[doc(alias = "ANeuralNetworksModel_relaxComputationFloat32toFloat16")]
+    pub fn relax_computation_float32_to_float16(&mut self, allow: bool) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksModel_relaxComputationFloat32toFloat16(self.as_ptr(), allow)?
+        })
+    }
+
+    /// Indicates that the model has been fully defined, and returns the immutable [`Model`].
+    #[doc(alias = "ANeuralNetworksModel_finish")]
+    pub fn finish(self) -> Result<Model<'m>> {
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksModel_finish(self.as_ptr())
+        })?;
+        Ok(self.model)
+    }
+}
+
+/// A finished model, built by [`ModelBuilder`].
+///
+/// The lifetime `'m` ties the model to the [`Memory`] objects that hold its constant values.
+#[derive(Debug)]
+#[doc(alias = "ANeuralNetworksModel")]
+pub struct Model<'m> {
+    ptr: NonNull<ffi::ANeuralNetworksModel>,
+    operand_count: u32,
+    operation_count: usize,
+    values: Vec<Box<[u8]>>,
+    _memories: PhantomData<&'m Memory>,
+}
+
+// SAFETY: A finished model can no longer be modified, and NNAPI allows it to be used from multiple
+// threads concurrently.
+unsafe impl Send for Model<'_> {}
+unsafe impl Sync for Model<'_> {}
+
+impl Model<'_> {
+    /// Returns, for every operation in the model in the order in which they were added, whether
+    /// it is supported by any of the given `devices`.
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksModel_getSupportedOperationsForDevices")]
+    pub fn supported_operations_for_devices(&self, devices: &[Device]) -> Result<Vec<bool>> {
+        let devices = devices
+            .iter()
+            .map(|d| d.ptr.as_ptr().cast_const())
+            .collect::<Vec<_>>();
+        let mut supported = vec![false; self.operation_count];
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksModel_getSupportedOperationsForDevices(
+                self.ptr.as_ptr(),
+                devices.as_ptr(),
+                devices.len() as u32,
+                supported.as_mut_ptr(),
+            )?
+        })?;
+        Ok(supported)
+    }
+}
+
+impl Drop for Model<'_> {
+    #[doc(alias = "ANeuralNetworksModel_free")]
+    fn drop(&mut self) {
+        unsafe { ffi::ANeuralNetworksModel_free(self.ptr.as_ptr()) }
+    }
+}
+
+/// Configures the compilation of a [`Model`] for the hardware of this device, resulting in a
+/// [`Compilation`].
+#[derive(Debug)]
+#[doc(alias = "ANeuralNetworksCompilation")]
+pub struct CompilationBuilder<'m> {
+    compilation: Compilation<'m>,
+}
+
+impl<'m> CompilationBuilder<'m> {
+    fn from_ptr(ptr: NonNull<ffi::ANeuralNetworksCompilation>) -> Self {
+        Self {
+            compilation: Compilation {
+                ptr,
+                _model: PhantomData,
+            },
+        }
+    }
+
+    fn as_ptr(&self) -> *mut ffi::ANeuralNetworksCompilation {
+        self.compilation.ptr.as_ptr()
+    }
+
+    /// Creates a compilation of `model`, for whichever devices the runtime deems best.
+    #[doc(alias = "ANeuralNetworksCompilation_create")]
+    pub fn new(model: &'m Model<'_>) -> Result<Self> {
+        let ptr = construct_never_null(|res| unsafe {
+            ffi::ANeuralNetworksCompilation_create(model.ptr.as_ptr(), res)
+        })?;
+        Ok(Self::from_ptr(ptr))
+    }
+
+    /// Creates a compilation of `model` for the given set of `devices`.
+    ///
+    /// If more than one device is specified, the compilation will distribute the workload
+    /// automatically across the devices.
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksCompilation_createForDevices")]
+    pub fn for_devices(model: &'m Model<'_>, devices: &[Device]) -> Result<Self> {
+        let devices = devices
+            .iter()
+            .map(|d| d.ptr.as_ptr().cast_const())
+            .collect::<Vec<_>>();
+        let ptr = construct_never_null(|res| unsafe {
+            ANeuralNetworksCompilation_createForDevices(
+                model.ptr.as_ptr(),
+                devices.as_ptr(),
+                devices.len() as u32,
+                res,
+            )
+        })?;
+        Ok(Self::from_ptr(ptr))
+    }
+
+    /// Sets the execution preference.
+    ///
+    /// The default is [`ExecutionPreference::FastSingleAnswer`].
+    #[doc(alias = "ANeuralNetworksCompilation_setPreference")]
+    pub fn set_preference(&mut self, preference: ExecutionPreference) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksCompilation_setPreference(self.as_ptr(), preference.into())
+        })
+    }
+
+    /// Sets the execution priority, relative to other executions from the same application.
+    ///
+    /// The default is [`Priority::Medium`].
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "ANeuralNetworksCompilation_setPriority")]
+    pub fn set_priority(&mut self, priority: Priority) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksCompilation_setPriority(self.as_ptr(), priority.into())?
+        })
+    }
+
+    /// Sets the maximum expected duration for compiling the model.
+    ///
+    /// Only allowed when the compilation was created with [`Self::for_devices()`] for a single
+    /// device.
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "ANeuralNetworksCompilation_setTimeout")]
+    pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksCompilation_setTimeout(
+                self.as_ptr(),
+                timeout
+                    .as_nanos()
+                    .try_into()
+                    .expect("Supplied duration is too large"),
+            )?
+        })
+    }
+
+    /// Sets the compilation caching signature and the cache directory.
+    ///
+    /// `cache_dir` is a directory private to the application, and `token` must uniquely
+    /// identify the model within the application.
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksCompilation_setCaching")]
+    pub fn set_caching(
+        &mut self,
+        cache_dir: &CStr,
+        token: &[u8; ffi::ANEURALNETWORKS_BYTE_SIZE_OF_CACHE_TOKEN as usize],
+    ) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksCompilation_setCaching(
+                self.as_ptr(),
+                cache_dir.as_ptr(),
+                token.as_ptr(),
+            )?
+        })
+    }
+
+    /// Compiles the model, and returns the finished [`Compilation`].
+    #[doc(alias = "ANeuralNetworksCompilation_finish")]
+    pub fn finish(self) -> Result<Compilation<'m>> {
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksCompilation_finish(self.as_ptr())
+        })?;
+        Ok(self.compilation)
+    }
+}
+
+/// A [`Model`] compiled for the hardware of this device, created with [`CompilationBuilder`].
+#[derive(Debug)]
+#[doc(alias = "ANeuralNetworksCompilation")]
+pub struct Compilation<'m> {
+    ptr: NonNull<ffi::ANeuralNetworksCompilation>,
+    _model: PhantomData<&'m Model<'m>>,
+}
+
+// SAFETY: A finished compilation can no longer be modified, and NNAPI allows it to be used from
+// multiple threads concurrently.
+unsafe impl Send for Compilation<'_> {}
+unsafe impl Sync for Compilation<'_> {}
+
+impl Drop for Compilation<'_> {
+    #[doc(alias = "ANeuralNetworksCompilation_free")]
+    fn drop(&mut self) {
+        unsafe { ffi::ANeuralNetworksCompilation_free(self.ptr.as_ptr()) }
+    }
+}
+
+/// A reusable execution context for low-latency, back-to-back executions of a [`Compilation`]
+/// through [`Execution::burst_compute()`].
+#[cfg(feature = "api-level-29")]
+#[derive(Debug)]
+#[doc(alias = "ANeuralNetworksBurst")]
+pub struct Burst<'c> {
+    ptr: NonNull<ffi::ANeuralNetworksBurst>,
+    _compilation: PhantomData<&'c Compilation<'c>>,
+}
+
+#[cfg(feature = "api-level-29")]
+impl<'c> Burst<'c> {
+    #[doc(alias = "ANeuralNetworksBurst_create")]
+    pub fn new(compilation: &'c Compilation<'_>) -> Result<Self> {
+        let ptr = construct_never_null(|res| unsafe {
+            ANeuralNetworksBurst_create(compilation.ptr.as_ptr(), res)
+        })?;
+        Ok(Self {
+            ptr,
+            _compilation: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "api-level-29")]
+impl Drop for Burst<'_> {
+    #[doc(alias = "ANeuralNetworksBurst_free")]
+    fn drop(&mut self) {
+        unsafe { ANeuralNetworksBurst_free(self.ptr.as_ptr()) }.expect(SAME_API_LEVEL)
+    }
+}
+
+/// A single evaluation of a [`Compilation`] on a set of inputs.
+///
+/// The lifetime `'a` ties the execution to its [`Compilation`] as well as to the input and output
+/// buffers and [`Memory`] objects, which are accessed by NNAPI until the computation completes.
+/// Output buffers can be read again after the execution is dropped.
+#[derive(Debug)]
+#[doc(alias = "ANeuralNetworksExecution")]
+pub struct Execution<'a> {
+    ptr: NonNull<ffi::ANeuralNetworksExecution>,
+    _buffers: PhantomData<&'a mut [u8]>,
+}
+
+// SAFETY: NNAPI allows an execution to be used from any thread, as long as only one thread uses it
+// at a time.
+unsafe impl Send for Execution<'_> {}
+
+impl<'a> Execution<'a> {
+    #[doc(alias = "ANeuralNetworksExecution_create")]
+    pub fn new(compilation: &'a Compilation<'_>) -> Result<Self> {
+        let ptr = construct_never_null(|res| unsafe {
+            ffi::ANeuralNetworksExecution_create(compilation.ptr.as_ptr(), res)
+        })?;
+        Ok(Self {
+            ptr,
+            _buffers: PhantomData,
+        })
+    }
+
+    fn as_ptr(&self) -> *mut ffi::ANeuralNetworksExecution {
+        self.ptr.as_ptr()
+    }
+
+    /// Associates `buffer` with the input at `index` of the model.
+    ///
+    /// `index` refers to the position in the `inputs` list passed to
+    /// [`ModelBuilder::identify_inputs_and_outputs()`], not to the operand index.
+    #[doc(alias = "ANeuralNetworksExecution_setInput")]
+    pub fn set_input<T: OperandElement>(&mut self, index: u32, buffer: &'a [T]) -> Result<()> {
+        self.set_input_with_type(index, None, buffer)
+    }
+
+    /// Associates `buffer` with the input at `index` of the model, completing any unspecified
+    /// dimensions of the model operand with those of `operand_type`.
+    #[doc(alias = "ANeuralNetworksExecution_setInput")]
+    pub fn set_input_with_type<T: OperandElement>(
+        &mut self,
+        index: u32,
+        operand_type: Option<&OperandType>,
+        buffer: &'a [T],
+    ) -> Result<()> {
+        let ty = operand_type.map(OperandType::as_ffi);
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksExecution_setInput(
+                self.as_ptr(),
+                index as i32,
+                ty.as_ref().map_or(ptr::null(), |t| t),
+                buffer.as_ptr().cast(),
+                size_of_val(buffer),
+            )
+        })
+    }
+
+    /// Associates `length` bytes of `memory`, starting at `offset`, with the input at `index` of
+    /// the model.
+    #[doc(alias = "ANeuralNetworksExecution_setInputFromMemory")]
+    pub fn set_input_from_memory(
+        &mut self,
+        index: u32,
+        operand_type: Option<&OperandType>,
+        memory: &'a Memory,
+        offset: usize,
+        length: usize,
+    ) -> Result<()> {
+        let ty = operand_type.map(OperandType::as_ffi);
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksExecution_setInputFromMemory(
+                self.as_ptr(),
+                index as i32,
+                ty.as_ref().map_or(ptr::null(), |t| t),
+                memory.ptr.as_ptr(),
+                offset,
+                length,
+            )
+        })
+    }
+
+    /// Associates `buffer` with the output at `index` of the model.
+    ///
+    /// `index` refers to the position in the `outputs` list passed to
+    /// [`ModelBuilder::identify_inputs_and_outputs()`], not to the operand index.
+    #[doc(alias = "ANeuralNetworksExecution_setOutput")]
+    pub fn set_output<T: OutputElement>(&mut self, index: u32, buffer: &'a mut [T]) -> Result<()> {
+        self.set_output_with_type(index, None, buffer)
+    }
+
+    /// Associates `buffer` with the output at `index` of the model, completing any unspecified
+    /// dimensions of the model operand with those of `operand_type`.
+    #[doc(alias = "ANeuralNetworksExecution_setOutput")]
+    pub fn set_output_with_type<T: OutputElement>(
+        &mut self,
+        index: u32,
+        operand_type: Option<&OperandType>,
+        buffer: &'a mut [T],
+    ) -> Result<()> {
+        let ty = operand_type.map(OperandType::as_ffi);
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksExecution_setOutput(
+                self.as_ptr(),
+                index as i32,
+                ty.as_ref().map_or(ptr::null(), |t| t),
+                buffer.as_mut_ptr().cast::<c_void>(),
+                size_of_val(buffer),
+            )
+        })
+    }
+
+    /// Associates `length` bytes of `memory`, starting at `offset`, with the output at `index`
+    /// of the model.
+    #[doc(alias = "ANeuralNetworksExecution_setOutputFromMemory")]
+    pub fn set_output_from_memory(
+        &mut self,
+        index: u32,
+        operand_type: Option<&OperandType>,
+        memory: &'a Memory,
+        offset: usize,
+        length: usize,
+    ) -> Result<()> {
+        let ty = operand_type.map(OperandType::as_ffi);
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksExecution_setOutputFromMemory(
+                self.as_ptr(),
+                index as i32,
+                ty.as_ref().map_or(ptr::null(), |t| t),
+                memory.ptr.as_ptr(),
+                offset,
+                length,
+            )
+        })
+    }
+
+    /// Specifies whether duration of the execution is to be measured, see [`Self::duration()`].
+    ///
+    /// Only allowed for compilations created with [`CompilationBuilder::for_devices()`] for a
+    /// single device.
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksExecution_setMeasureTiming")]
+    pub fn set_measure_timing(&mut self, measure: bool) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksExecution_setMeasureTiming(self.as_ptr(), measure)?
+        })
+    }
+
+    /// Sets the maximum expected duration of this execution.
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "ANeuralNetworksExecution_setTimeout")]
+    pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksExecution_setTimeout(
+                self.as_ptr(),
+                timeout
+                    .as_nanos()
+                    .try_into()
+                    .expect("Supplied duration is too large"),
+            )?
+        })
+    }
+
+    /// Sets the maximum duration of `WHILE` loops in this execution.
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "ANeuralNetworksExecution_setLoopTimeout")]
+    pub fn set_loop_timeout(&mut self, timeout: Duration) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksExecution_setLoopTimeout(
+                self.as_ptr(),
+                timeout
+                    .as_nanos()
+                    .try_into()
+                    .expect("Supplied duration is too large"),
+            )?
+        })
+    }
+
+    /// Specifies whether the execution can be computed more than once.
+    ///
+    /// By default, an execution can only be computed once.
+    #[cfg(feature = "api-level-31")]
+    #[doc(alias = "ANeuralNetworksExecution_setReusable")]
+    pub fn set_reusable(&mut self, reusable: bool) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksExecution_setReusable(self.as_ptr(), reusable)?
+        })
+    }
+
+    /// Performs the computation synchronously, blocking until it has completed.
+    ///
+    /// With the `weak-linking` feature, this falls back to waiting on an asynchronous computation
+    /// on devices before API level 29.
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksExecution_compute")]
+    pub fn compute(&mut self) -> Result<()> {
+        match unsafe { ANeuralNetworksExecution_compute(self.as_ptr()) } {
+            Ok(status) => NeuralNetworksError::from_status(status),
+            // SAFETY: The event is waited on right away
+            Err(_) => unsafe { self.start_compute() }?.wait(),
+        }
+    }
+
+    /// Performs the computation synchronously, blocking until it has completed.
+    ///
+    /// Before API level 29 the computation can only be started asynchronously, this waits on the
+    /// resulting event instead.
+    #[cfg(not(feature = "api-level-29"))]
+    #[doc(alias = "ANeuralNetworksExecution_startCompute")]
+    pub fn compute(&mut self) -> Result<()> {
+        // SAFETY: The event is waited on right away
+        unsafe { self.start_compute() }?.wait()
+    }
+
+    /// Performs the computation synchronously on a [`Burst`] of the same [`Compilation`].
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksExecution_burstCompute")]
+    pub fn burst_compute(&mut self, burst: &mut Burst<'_>) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksExecution_burstCompute(self.as_ptr(), burst.ptr.as_ptr())?
+        })
+    }
+
+    /// Starts the computation asynchronously, returning an [`Event`] that is signaled once it has
+    /// completed.
+    ///
+    /// # Safety
+    /// NNAPI keeps accessing the input and output buffers of this execution until the computation
+    /// has completed, which dropping the returned [`Event`] or calling [`Event::wait()`] waits for.
+    /// The event must therefore not be leaked, for example with [`std::mem::forget()`], as that
+    /// would release the borrow of the buffers while they are still in use.
+    #[doc(alias = "ANeuralNetworksExecution_startCompute")]
+    pub unsafe fn start_compute(&mut self) -> Result<Event<'_>> {
+        let ptr = construct_never_null(|res| unsafe {
+            ffi::ANeuralNetworksExecution_startCompute(self.as_ptr(), res)
+        })?;
+        Ok(Event {
+            ptr,
+            _execution: PhantomData,
+        })
+    }
+
+    /// Starts the computation once all `dependencies` have been signaled, returning an [`Event`]
+    /// that is signaled once it has completed.
+    ///
+    /// The returned event is backed by a sync fence when supported by the driver, which can be
+    /// retrieved with [`Event::sync_fence_fd()`] and passed on to other APIs such as
+    /// [`crate::sync`]. `duration` is the maximum expected duration of the computation after the
+    /// dependencies have been signaled, or [`None`] for no limit.
+    ///
+    /// Only allowed for compilations created with [`CompilationBuilder::for_devices()`] for a
+    /// single device.
+    ///
+    /// # Safety
+    /// The returned [`Event`] must not be leaked, see [`Self::start_compute()`].
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "ANeuralNetworksExecution_startComputeWithDependencies")]
+    pub unsafe fn start_compute_with_dependencies(
+        &mut self,
+        dependencies: &[&Event<'_>],
+        duration: Option<Duration>,
+    ) -> Result<Event<'_>> {
+        let dependencies = dependencies
+            .iter()
+            .map(|e| e.ptr.as_ptr().cast_const())
+            .collect::<Vec<_>>();
+        let duration = duration.map_or(0, |d| {
+            d.as_nanos()
+                .try_into()
+                .expect("Supplied duration is too large")
+        });
+        let ptr = construct_never_null(|res| unsafe {
+            ANeuralNetworksExecution_startComputeWithDependencies(
+                self.as_ptr(),
+                dependencies.as_ptr(),
+                dependencies.len() as u32,
+                duration,
+                res,
+            )
+        })?;
+        Ok(Event {
+            ptr,
+            _execution: PhantomData,
+        })
+    }
+
+    /// Returns the duration of the latest computation, or [`None`] if it was not measured.
+    ///
+    /// Requires [`Self::set_measure_timing()`] to have been enabled before computing.
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksExecution_getDuration")]
+    pub fn duration(&self, duration_code: DurationCode) -> Result<Option<Duration>> {
+        let mut duration = 0;
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksExecution_getDuration(
+                self.as_ptr(),
+                duration_code.into(),
+                &mut duration,
+            )?
+        })?;
+        Ok(match duration {
+            u64::MAX => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        })
+    }
+
+    /// Returns the dimensions of the output at `index`, after the computation has completed.
+    ///
+    /// This is useful when the output shape was not fully specified in the model.
+    #[cfg(feature = "api-level-29")]
+    #[doc(alias = "ANeuralNetworksExecution_getOutputOperandRank")]
+    #[doc(alias = "ANeuralNetworksExecution_getOutputOperandDimensions")]
+    pub fn output_operand_dimensions(&self, index: u32) -> Result<Vec<u32>> {
+        let mut rank = 0;
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksExecution_getOutputOperandRank(self.as_ptr(), index as i32, &mut rank)?
+        })?;
+        let mut dimensions = vec![0; rank as usize];
+        if rank > 0 {
+            NeuralNetworksError::from_status(unsafe {
+                ANeuralNetworksExecution_getOutputOperandDimensions(
+                    self.as_ptr(),
+                    index as i32,
+                    dimensions.as_mut_ptr(),
+                )?
+            })?;
+        }
+        Ok(dimensions)
+    }
+}
+
+impl Drop for Execution<'_> {
+    #[doc(alias = "ANeuralNetworksExecution_free")]
+    fn drop(&mut self) {
+        unsafe { ffi::ANeuralNetworksExecution_free(self.ptr.as_ptr()) }
+    }
+}
+
+/// Signals the completion of an asynchronous computation started with
+/// [`Execution::start_compute()`], or wraps a sync fence.
+#[derive(Debug)]
+#[doc(alias = "ANeuralNetworksEvent")]
+pub struct Event<'e> {
+    ptr: NonNull<ffi::ANeuralNetworksEvent>,
+    _execution: PhantomData<&'e mut ()>,
+}
+
+impl Event<'static> {
+    /// Creates an event from a sync fence, for use as a dependency in
+    /// [`Execution::start_compute_with_dependencies()`].
+    ///
+    /// The file descriptor is duplicated and may be closed after this call.
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "ANeuralNetworksEvent_createFromSyncFenceFd")]
+    pub fn from_sync_fence_fd(sync_fence_fd: BorrowedFd<'_>) -> Result<Self> {
+        let ptr = construct_never_null(|res| unsafe {
+            ANeuralNetworksEvent_createFromSyncFenceFd(sync_fence_fd.as_raw_fd(), res)
+        })?;
+        Ok(Self {
+            ptr,
+            _execution: PhantomData,
+        })
+    }
+}
+
+impl Event<'_> {
+    /// Blocks until the computation associated with this event has completed, and returns its
+    /// result.
+    #[doc(alias = "ANeuralNetworksEvent_wait")]
+    pub fn wait(self) -> Result<()> {
+        NeuralNetworksError::from_status(unsafe {
+            ffi::ANeuralNetworksEvent_wait(self.ptr.as_ptr())
+        })
+    }
+
+    /// Returns a new sync fence file descriptor that is signaled when the computation has
+    /// completed, compatible with [`crate::sync`].
+    ///
+    /// Fails with [`NeuralNetworksError::BadData`] if this event is not backed by a sync fence.
+    #[cfg(feature = "api-level-30")]
+    #[doc(alias = "ANeuralNetworksEvent_getSyncFenceFd")]
+    pub fn sync_fence_fd(&self) -> Result<OwnedFd> {
+        let mut fd = -1;
+        NeuralNetworksError::from_status(unsafe {
+            ANeuralNetworksEvent_getSyncFenceFd(self.ptr.as_ptr(), &mut fd)?
+        })?;
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+}
+
+impl Drop for Event<'_> {
+    /// Destroys the event, waiting for the associated computation to complete first.
+    #[doc(alias = "ANeuralNetworksEvent_free")]
+    fn drop(&mut self) {
+        unsafe { ffi::ANeuralNetworksEvent_free(self.ptr.as_ptr()) }
+    }
+}
@@ -14,7 +14,71 @@
 use std::io::ErrorKind;
 use std::{io::Result, ptr::NonNull, time::Duration};
 
-use crate::utils::status_to_io_result;
+use crate::{
+    api_level::{weak_fn, SAME_API_LEVEL},
+    utils::status_to_io_result,
+};
+
+weak_fn! {
+    fn APerformanceHint_getManager() -> *mut ffi::APerformanceHintManager;
+    fn APerformanceHint_createSession(
+        manager: *mut ffi::APerformanceHintManager,
+        thread_ids: *const i32,
+        size: usize,
+        initial_target_work_duration_nanos: i64,
+    ) -> *mut ffi::APerformanceHintSession;
+    fn APerformanceHint_getPreferredUpdateRateNanos(
+        manager: *mut ffi::APerformanceHintManager,
+    ) -> i64;
+    fn APerformanceHint_updateTargetWorkDuration(
+        session: *mut ffi::APerformanceHintSession,
+        target_duration_nanos: i64,
+    ) -> i32;
+    fn APerformanceHint_reportActualWorkDuration(
+        session: *mut ffi::APerformanceHintSession,
+        actual_duration_nanos: i64,
+    ) -> i32;
+    fn APerformanceHint_closeSession(session: *mut ffi::APerformanceHintSession);
+}
+
+#[cfg(feature = "api-level-34")]
+weak_fn! {
+    fn APerformanceHint_setThreads(
+        session: *mut ffi::APerformanceHintSession,
+        thread_ids: *const i32,
+        size: usize,
+    ) -> i32;
+}
+
+#[cfg(feature = "api-level-35")]
+weak_fn! {
+    fn APerformanceHint_setPreferPowerEfficiency(
+        session: *mut ffi::APerformanceHintSession,
+        enabled: bool,
+    ) -> i32;
+    fn APerformanceHint_reportActualWorkDuration2(
+        session: *mut ffi::APerformanceHintSession,
+        work_duration: *mut ffi::AWorkDuration,
+    ) -> i32;
+    fn AWorkDuration_create() -> *mut ffi::AWorkDuration;
+    fn AWorkDuration_release(work_duration: *mut ffi::AWorkDuration);
+    fn AWorkDuration_setWorkPeriodStartTimestampNanos(
+        work_duration: *mut ffi::AWorkDuration,
+        work_period_start_timestamp_nanos: i64,
+    );
+    fn AWorkDuration_setActualTotalDurationNanos(
+        work_duration: *mut ffi::AWorkDuration,
+        actual_total_duration_nanos: i64,
+    );
+    fn AWorkDuration_setActualCpuDurationNanos(
+        work_duration: *mut ffi::AWorkDuration,
+        actual_cpu_duration_nanos: i64,
+    );
+    fn AWorkDuration_setActualGpuDurationNanos(
+        work_duration: *mut ffi::AWorkDuration,
+        actual_gpu_duration_nanos: i64,
+    );
+}
 
 /// An opaque type representing a handle to a performance hint manager.
 ///
@@ -37,10 +101,11 @@ unsafe impl Sync for PerformanceHintManager {}
 impl PerformanceHintManager {
     /// Retrieve a reference to the performance hint manager.
     ///
-    /// Returns [`None`] on failure.
+    /// Returns [`None`] on failure, or when the `weak-linking` feature is enabled and the
+    /// device does not support performance hints.
     #[doc(alias = "APerformanceHint_getManager")]
     pub fn new() -> Option<Self> {
-        NonNull::new(unsafe { ffi::APerformanceHint_getManager() }).map(|ptr| Self { ptr })
+        NonNull::new(unsafe { APerformanceHint_getManager() }.ok()?).map(|ptr| Self { ptr })
     }
 
     /// Creates a session for the given set of threads and sets their initial target work duration.
@@ -57,7 +122,7 @@ impl PerformanceHintManager {
         initial_target_work_duration: Duration,
     ) -> Option<PerformanceHintSession> {
         NonNull::new(unsafe {
-            ffi::APerformanceHint_createSession(
+            APerformanceHint_createSession(
                 self.ptr.as_ptr(),
                 thread_ids.as_ptr(),
                 thread_ids.len(),
@@ -66,6 +131,7 @@ impl PerformanceHintManager {
                     .try_into()
                     .expect("Supplied duration is too large"),
             )
+            .expect(SAME_API_LEVEL)
         })
         .map(|ptr| PerformanceHintSession { ptr })
     }
@@ -76,7 +142,8 @@ impl PerformanceHintManager {
     #[doc(alias = "APerformanceHint_getPreferredUpdateRateNanos")]
     pub fn preferred_update_rate(&self) -> Duration {
         Duration::from_nanos(unsafe {
-            ffi::APerformanceHint_getPreferredUpdateRateNanos(self.ptr.as_ptr())
+            APerformanceHint_getPreferredUpdateRateNanos(self.ptr.as_ptr())
+                .expect(SAME_API_LEVEL)
                 .try_into()
                 .expect("getPreferredUpdateRateNanos should not return negative")
         })
@@ -117,13 +184,13 @@ impl PerformanceHintSession {
     #[doc(alias = "APerformanceHint_updateTargetWorkDuration")]
     pub fn update_target_work_duration(&self, target_duration: Duration) -> Result<()> {
         status_to_io_result(unsafe {
-            ffi::APerformanceHint_updateTargetWorkDuration(
+            APerformanceHint_updateTargetWorkDuration(
                 self.ptr.as_ptr(),
                 target_duration
                     .as_nanos()
                     .try_into()
                     .expect("Supplied duration is too large"),
-            )
+            )?
         })
     }
 
@@ -140,13 +207,13 @@ impl PerformanceHintSession {
     #[doc(alias = "APerformanceHint_reportActualWorkDuration")]
     pub fn report_actual_work_duration(&self, actual_duration: Duration) -> Result<()> {
         status_to_io_result(unsafe {
-            ffi::APerformanceHint_reportActualWorkDuration(
+            APerformanceHint_reportActualWorkDuration(
                 self.ptr.as_ptr(),
                 actual_duration
                     .as_nanos()
                     .try_into()
                     .expect("Supplied duration is too large"),
-            )
+            )?
         })
     }
 
@@ -165,11 +232,7 @@ impl PerformanceHintSession {
     #[doc(alias = "APerformanceHint_setThreads")]
     pub fn set_threads(&self, thread_ids: &[i32]) -> Result<()> {
         status_to_io_result(unsafe {
-            ffi::APerformanceHint_setThreads(
-                self.ptr.as_ptr(),
-                thread_ids.as_ptr(),
-                thread_ids.len(),
-            )
+            APerformanceHint_setThreads(self.ptr.as_ptr(), thread_ids.as_ptr(), thread_ids.len())?
         })
     }
 
@@ -184,7 +247,7 @@ impl PerformanceHintSession {
     #[doc(alias = "APerformanceHint_setPreferPowerEfficiency")]
     pub fn set_prefer_power_efficiency(&self, enabled: bool) -> Result<()> {
         status_to_io_result(unsafe {
-            ffi::APerformanceHint_setPreferPowerEfficiency(self.ptr.as_ptr(), enabled)
+            APerformanceHint_setPreferPowerEfficiency(self.ptr.as_ptr(), enabled)?
         })
     }
 
@@ -209,10 +272,10 @@ impl PerformanceHintSession {
     #[doc(alias = "APerformanceHint_reportActualWorkDuration2")]
     pub fn report_actual_work_duration2(&self, work_duration: &WorkDuration) -> Result<()> {
         status_to_io_result(unsafe {
-            ffi::APerformanceHint_reportActualWorkDuration2(
+            APerformanceHint_reportActualWorkDuration2(
                 self.ptr.as_ptr(),
                 work_duration.ptr.as_ptr(),
-            )
+            )?
         })
     }
 }
@@ -222,7 +285,7 @@ impl Drop for PerformanceHintSession {
     /// [`PerformanceHintManager::create_session()`].
     #[doc(alias = "APerformanceHint_closeSession")]
     fn drop(&mut self) {
-        unsafe { ffi::APerformanceHint_closeSession(self.ptr.as_ptr()) }
+        unsafe { APerformanceHint_closeSession(self.ptr.as_ptr()) }.expect(SAME_API_LEVEL)
     }
 }
 
@@ -237,12 +300,15 @@ pub struct WorkDuration {
 impl WorkDuration {
     /// Creates a new [`WorkDuration`]. When the client finishes using [`WorkDuration`], it will
     /// automatically be released on [`drop()`].
+    ///
+    /// Returns [`Unsupported`][crate::api_level::Unsupported] when the `weak-linking` feature is
+    /// enabled and the device does not support [`WorkDuration`].
     #[doc(alias = "AWorkDuration_create")]
-    pub fn new() -> Self {
-        Self {
-            ptr: NonNull::new(unsafe { ffi::AWorkDuration_create() })
-                .expect("AWorkDuration_create should not return NULL"),
-        }
+    pub fn new() -> std::result::Result<Self, crate::api_level::Unsupported> {
+        let ptr = unsafe { AWorkDuration_create()? };
+        Ok(Self {
+            ptr: NonNull::new(ptr).expect("AWorkDuration_create should not return NULL"),
+        })
     }
 
     /// Sets the work period start timestamp in nanoseconds.
@@ -253,13 +319,14 @@ impl WorkDuration {
     #[doc(alias = "AWorkDuration_setWorkPeriodStartTimestampNanos")]
     pub fn set_work_period_start_timestamp(&self, work_period_start_timestamp: Duration) {
         unsafe {
-            ffi::AWorkDuration_setWorkPeriodStartTimestampNanos(
+            AWorkDuration_setWorkPeriodStartTimestampNanos(
                 self.ptr.as_ptr(),
                 work_period_start_timestamp
                     .as_nanos()
                     .try_into()
                     .expect("Supplied timestamp is too large"),
             )
+            .expect(SAME_API_LEVEL)
         }
     }
 
@@ -270,13 +337,14 @@ impl WorkDuration {
     #[doc(alias = "AWorkDuration_setActualTotalDurationNanos")]
     pub fn set_actual_total_duration(&self, actual_total_duration: Duration) {
         unsafe {
-            ffi::AWorkDuration_setActualTotalDurationNanos(
+            AWorkDuration_setActualTotalDurationNanos(
                 self.ptr.as_ptr(),
                 actual_total_duration
                     .as_nanos()
                     .try_into()
                     .expect("Supplied duration is too large"),
             )
+            .expect(SAME_API_LEVEL)
         }
     }
 
@@ -287,13 +355,14 @@ impl WorkDuration {
     #[doc(alias = "AWorkDuration_setActualCpuDurationNanos")]
     pub fn set_actual_cpu_duration(&self, actual_cpu_duration: Duration) {
         unsafe {
-            ffi::AWorkDuration_setActualCpuDurationNanos(
+            AWorkDuration_setActualCpuDurationNanos(
                 self.ptr.as_ptr(),
                 actual_cpu_duration
                     .as_nanos()
                     .try_into()
                     .expect("Supplied duration is too large"),
             )
+            .expect(SAME_API_LEVEL)
         }
     }
 
@@ -304,28 +373,23 @@ impl WorkDuration {
     #[doc(alias = "AWorkDuration_setActualGpuDurationNanos")]
     pub fn set_actual_gpu_duration(&self, actual_gpu_duration: Duration) {
         unsafe {
-            ffi::AWorkDuration_setActualGpuDurationNanos(
+            AWorkDuration_setActualGpuDurationNanos(
                 self.ptr.as_ptr(),
                 actual_gpu_duration
                     .as_nanos()
                     .try_into()
                     .expect("Supplied duration is too large"),
             )
+            .expect(SAME_API_LEVEL)
         }
     }
 }
 
-impl Default for WorkDuration {
-    #[doc(alias = "AWorkDuration_create")]
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
+#[cfg(feature = "api-level-35")]
 impl Drop for WorkDuration {
     /// Destroys [`WorkDuration`] and free all resources associated to it.
     #[doc(alias = "AWorkDuration_release")]
     fn drop(&mut self) {
-        unsafe { ffi::AWorkDuration_release(self.ptr.as_ptr()) }
+        unsafe { AWorkDuration_release(self.ptr.as_ptr()) }.expect(SAME_API_LEVEL)
     }
 }
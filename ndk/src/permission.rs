@@ -0,0 +1,212 @@
+//! Bindings for [`APermissionManager`]
+//!
+//! Allows native code to check whether a process has been granted a runtime permission, before
+//! opening devices (like the camera or microphone) that would otherwise fail in an opaque way.
+//!
+//! [`APermissionManager`]: https://developer.android.com/ndk/reference/group/permission
+#![cfg(feature = "api-level-31")]
+
+use std::ffi::CStr;
+
+use num_enum::{FromPrimitive, IntoPrimitive};
+
+use crate::api_level::{weak_fn, Unsupported};
+
+weak_fn! {
+    fn APermissionManager_checkPermission(
+        permission: *const std::os::raw::c_char,
+        pid: ffi::pid_t,
+        uid: ffi::uid_t,
+        out_result: *mut i32,
+    ) -> i32;
+}
+
+/// Common [`android.Manifest.permission`] names, for use with [`check_permission()`].
+///
+/// Any other permission name can be passed to [`check_permission()`] as a [`CStr`] directly.
+///
+/// [`android.Manifest.permission`]: https://developer.android.com/reference/android/Manifest.permission
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Permission {
+    /// Allows an app to access approximate location.
+    #[doc(alias = "ACCESS_COARSE_LOCATION")]
+    AccessCoarseLocation,
+    /// Allows an app to access precise location.
+    #[doc(alias = "ACCESS_FINE_LOCATION")]
+    AccessFineLocation,
+    /// Allows an application to recognize physical activity.
+    #[doc(alias = "ACTIVITY_RECOGNITION")]
+    ActivityRecognition,
+    /// Allows applications to connect to paired bluetooth devices.
+    #[doc(alias = "BLUETOOTH_CONNECT")]
+    BluetoothConnect,
+    /// Required to be able to discover and pair nearby Bluetooth devices.
+    #[doc(alias = "BLUETOOTH_SCAN")]
+    BluetoothScan,
+    /// Allows an application to access data from sensors that the user uses to measure what is
+    /// happening inside their body, such as heart rate.
+    #[doc(alias = "BODY_SENSORS")]
+    BodySensors,
+    /// Required to be able to access the camera device.
+    #[doc(alias = "CAMERA")]
+    Camera,
+    /// Allows applications to open network sockets.
+    #[doc(alias = "INTERNET")]
+    Internet,
+    /// Allows an app to post notifications.
+    #[doc(alias = "POST_NOTIFICATIONS")]
+    PostNotifications,
+    /// Allows an application to read the user's contacts data.
+    #[doc(alias = "READ_CONTACTS")]
+    ReadContacts,
+    /// Allows an application to read from external storage.
+    #[doc(alias = "READ_EXTERNAL_STORAGE")]
+    ReadExternalStorage,
+    /// Allows an application to read audio files from external storage.
+    #[doc(alias = "READ_MEDIA_AUDIO")]
+    ReadMediaAudio,
+    /// Allows an application to read image files from external storage.
+    #[doc(alias = "READ_MEDIA_IMAGES")]
+    ReadMediaImages,
+    /// Allows an application to read video files from external storage.
+    #[doc(alias = "READ_MEDIA_VIDEO")]
+    ReadMediaVideo,
+    /// Allows an application to record audio.
+    #[doc(alias = "RECORD_AUDIO")]
+    RecordAudio,
+    /// Allows access to the vibrator.
+    #[doc(alias = "VIBRATE")]
+    Vibrate,
+    /// Allows using `PowerManager` `WakeLocks` to keep processor from sleeping or screen from
+    /// dimming.
+    #[doc(alias = "WAKE_LOCK")]
+    WakeLock,
+    /// Allows an application to write to external storage.
+    #[doc(alias = "WRITE_EXTERNAL_STORAGE")]
+    WriteExternalStorage,
+}
+
+impl Permission {
+    /// Returns the fully qualified permission name, e.g. `android.permission.CAMERA`.
+    pub fn name(self) -> &'static CStr {
+        let name: &'static [u8] = match self {
+            Self::AccessCoarseLocation => b"android.permission.ACCESS_COARSE_LOCATION\0",
+            Self::AccessFineLocation => b"android.permission.ACCESS_FINE_LOCATION\0",
+            Self::ActivityRecognition => b"android.permission.ACTIVITY_RECOGNITION\0",
+            Self::BluetoothConnect => b"android.permission.BLUETOOTH_CONNECT\0",
+            Self::BluetoothScan => b"android.permission.BLUETOOTH_SCAN\0",
+            Self::BodySensors => b"android.permission.BODY_SENSORS\0",
+            Self::Camera => b"android.permission.CAMERA\0",
+            Self::Internet => b"android.permission.INTERNET\0",
+            Self::PostNotifications => b"android.permission.POST_NOTIFICATIONS\0",
+            Self::ReadContacts => b"android.permission.READ_CONTACTS\0",
+            Self::ReadExternalStorage => b"android.permission.READ_EXTERNAL_STORAGE\0",
+            Self::ReadMediaAudio => b"android.permission.READ_MEDIA_AUDIO\0",
+            Self::ReadMediaImages => b"android.permission.READ_MEDIA_IMAGES\0",
+            Self::ReadMediaVideo => b"android.permission.READ_MEDIA_VIDEO\0",
+            Self::RecordAudio => b"android.permission.RECORD_AUDIO\0",
+            Self::Vibrate => b"android.permission.VIBRATE\0",
+            Self::WakeLock => b"android.permission.WAKE_LOCK\0",
+            Self::WriteExternalStorage => b"android.permission.WRITE_EXTERNAL_STORAGE\0",
+        };
+        // TODO: Use C-string literals since MSRV 1.77
+        CStr::from_bytes_with_nul(name).unwrap()
+    }
+}
+
+impl AsRef<CStr> for Permission {
+    fn as_ref(&self) -> &CStr {
+        self.name()
+    }
+}
+
+/// Whether a permission is granted, as returned by [`check_permission()`].
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[non_exhaustive]
+pub enum PermissionStatus {
+    /// The permission is denied.
+    #[doc(alias = "PERMISSION_MANAGER_PERMISSION_DENIED")]
+    Denied = ffi::PERMISSION_MANAGER_PERMISSION_DENIED,
+    /// The permission is granted.
+    #[doc(alias = "PERMISSION_MANAGER_PERMISSION_GRANTED")]
+    Granted = ffi::PERMISSION_MANAGER_PERMISSION_GRANTED,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    __Unknown(i32),
+}
+
+impl PermissionStatus {
+    /// Returns [`true`] if the permission is [`Granted`][Self::Granted].
+    pub fn is_granted(self) -> bool {
+        self == Self::Granted
+    }
+}
+
+/// Errors returned by [`check_permission()`].
+#[repr(i32)]
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, FromPrimitive, IntoPrimitive, thiserror::Error,
+)]
+#[non_exhaustive]
+pub enum PermissionError {
+    /// The permission manager service is not available.
+    #[doc(alias = "PERMISSION_MANAGER_STATUS_SERVICE_UNAVAILABLE")]
+    #[error("Permission manager service is unavailable")]
+    ServiceUnavailable = ffi::PERMISSION_MANAGER_STATUS_SERVICE_UNAVAILABLE,
+    /// An unknown error occurred while checking the permission.
+    #[doc(alias = "PERMISSION_MANAGER_STATUS_ERROR_UNKNOWN")]
+    #[error("Unknown error while checking permission")]
+    Unknown = ffi::PERMISSION_MANAGER_STATUS_ERROR_UNKNOWN,
+
+    #[doc(hidden)]
+    #[num_enum(catch_all)]
+    #[error("Unknown permission manager status {0}")]
+    __Unknown(i32),
+}
+
+impl From<Unsupported> for PermissionError {
+    /// Functions that do not exist on the current device are reported as
+    /// [`PermissionError::ServiceUnavailable`].
+    fn from(_: Unsupported) -> Self {
+        Self::ServiceUnavailable
+    }
+}
+
+/// Checks whether the package with the given `pid` and `uid` has been granted `permission`.
+///
+/// `permission` is either a [`Permission`] or any other fully qualified permission name such as
+/// `android.permission.CAMERA`.
+///
+/// Note that the Java API of [`Context.checkPermission()`] is usually faster due to caching, thus
+/// is preferred over this API wherever possible.
+///
+/// [`Context.checkPermission()`]: https://developer.android.com/reference/android/content/Context#checkPermission(java.lang.String,%20int,%20int)
+#[doc(alias = "APermissionManager_checkPermission")]
+pub fn check_permission(
+    permission: impl AsRef<CStr>,
+    pid: i32,
+    uid: u32,
+) -> Result<PermissionStatus, PermissionError> {
+    let mut result = ffi::PERMISSION_MANAGER_PERMISSION_DENIED;
+    let status = unsafe {
+        APermissionManager_checkPermission(permission.as_ref().as_ptr(), pid, uid, &mut result)?
+    };
+    match status {
+        ffi::PERMISSION_MANAGER_STATUS_OK => Ok(result.into()),
+        x => Err(x.into()),
+    }
+}
+
+/// Checks whether the current process has been granted `permission`.
+///
+/// Shorthand for [`check_permission()`] with the `pid` and `uid` of the current process.
+pub fn check_self_permission(
+    permission: impl AsRef<CStr>,
+) -> Result<PermissionStatus, PermissionError> {
+    let pid = std::process::id() as i32;
+    let uid = unsafe { ffi::getuid() };
+    check_permission(permission, pid, uid)
+}
@@ -0,0 +1,740 @@
+//! Conversions between the pixel format enums of the different NDK APIs, descriptions of their
+//! memory layout, and pure-Rust conversion of pixel data between common formats.
+//!
+//! [`HardwareBufferFormat`], [`BitmapFormat`] and [`ImageFormat`] describe the same pixel formats
+//! with different values. Where a format exists in two of these enums, it can be converted with
+//! [`TryFrom`]:
+//!
+//! ```
+//! # use ndk::hardware_buffer_format::HardwareBufferFormat;
+//! # use ndk::bitmap::BitmapFormat;
+//! let format = BitmapFormat::try_from(HardwareBufferFormat::R5G6B5_UNORM).unwrap();
+//! assert_eq!(format, BitmapFormat::RGB_565);
+//! ```
+//!
+//! The conversion functions in this module operate on [`Plane`]s, which are obtained from
+//! [`Image::plane_data()`] or [`HardwareBuffer::lock_planes()`], and do not call into the NDK.
+//!
+//! [`BitmapFormat`]: crate::bitmap::BitmapFormat
+//! [`ImageFormat`]: crate::media::image_reader::ImageFormat
+//! [`Image::plane_data()`]: crate::media::image_reader::Image::plane_data()
+//! [`HardwareBuffer::lock_planes()`]: crate::hardware_buffer::HardwareBuffer::lock_planes()
+
+use std::fmt;
+
+#[cfg(feature = "bitmap")]
+use crate::bitmap::BitmapFormat;
+#[cfg(feature = "api-level-28")]
+use crate::data_space::DataSpace;
+#[cfg(feature = "api-level-26")]
+use crate::hardware_buffer::LockedPlaneInfo;
+use crate::hardware_buffer_format::HardwareBufferFormat;
+#[cfg(all(feature = "media", feature = "api-level-24"))]
+use crate::media::image_reader::{Image, ImageFormat};
+
+/// The error returned by [`TryFrom`] conversions between pixel format enums, when the format has
+/// no equivalent in the target enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("{0:?} has no equivalent format")]
+pub struct UnmappedFormat<F: fmt::Debug>(pub F);
+
+/// Implements [`TryFrom`] in both directions for the listed pairs of variants.
+#[cfg(any(feature = "bitmap", all(feature = "media", feature = "api-level-24")))]
+macro_rules! format_mapping {
+    ($a:ident <=> $b:ident { $($(#[$meta:meta])* $va:ident <=> $vb:ident,)* }) => {
+        impl TryFrom<$a> for $b {
+            type Error = UnmappedFormat<$a>;
+
+            fn try_from(value: $a) -> Result<Self, Self::Error> {
+                Ok(match value {
+                    $($(#[$meta])* $a::$va => Self::$vb,)*
+                    _ => return Err(UnmappedFormat(value)),
+                })
+            }
+        }
+
+        impl TryFrom<$b> for $a {
+            type Error = UnmappedFormat<$b>;
+
+            fn try_from(value: $b) -> Result<Self, Self::Error> {
+                Ok(match value {
+                    $($(#[$meta])* $b::$vb => Self::$va,)*
+                    _ => return Err(UnmappedFormat(value)),
+                })
+            }
+        }
+    };
+}
+
+#[cfg(feature = "bitmap")]
+format_mapping!(HardwareBufferFormat <=> BitmapFormat {
+    R8G8B8A8_UNORM <=> RGBA_8888,
+    R5G6B5_UNORM <=> RGB_565,
+    #[cfg(feature = "api-level-26")]
+    R16G16B16A16_FLOAT <=> RGBA_F16,
+    #[cfg(feature = "api-level-26")]
+    R10G10B10A2_UNORM <=> RGBA_1010102,
+    #[cfg(feature = "api-level-26")]
+    R8_UNORM <=> A_8,
+});
+
+#[cfg(all(feature = "media", feature = "api-level-24"))]
+format_mapping!(HardwareBufferFormat <=> ImageFormat {
+    R8G8B8A8_UNORM <=> RGBA_8888,
+    R8G8B8X8_UNORM <=> RGBX_8888,
+    #[cfg(feature = "api-level-26")]
+    R8G8B8_UNORM <=> RGB_888,
+    R5G6B5_UNORM <=> RGB_565,
+    #[cfg(feature = "api-level-26")]
+    R16G16B16A16_FLOAT <=> RGBA_FP16,
+    #[cfg(feature = "api-level-26")]
+    Y8Cb8Cr8_420 <=> YUV_420_888,
+});
+
+#[cfg(all(feature = "bitmap", feature = "media", feature = "api-level-24"))]
+format_mapping!(BitmapFormat <=> ImageFormat {
+    RGBA_8888 <=> RGBA_8888,
+    RGB_565 <=> RGB_565,
+    RGBA_F16 <=> RGBA_FP16,
+});
+
+/// Describes the memory layout of one plane of a pixel format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PlaneLayout {
+    /// The number of bytes that the samples of one pixel occupy in this plane.
+    ///
+    /// The pixel stride of a locked plane may be larger, for example when the chroma samples of
+    /// two planes are interleaved.
+    pub bytes_per_pixel: u8,
+    /// The number of significant bits of every component in this plane, in the order of the
+    /// format name.
+    pub component_bits: &'static [u8],
+    /// The number of horizontally adjacent pixels that share a sample in this plane.
+    pub horizontal_subsampling: u8,
+    /// The number of vertically adjacent pixels that share a sample in this plane.
+    pub vertical_subsampling: u8,
+}
+
+impl PlaneLayout {
+    const fn full(bytes_per_pixel: u8, component_bits: &'static [u8]) -> Self {
+        Self {
+            bytes_per_pixel,
+            component_bits,
+            horizontal_subsampling: 1,
+            vertical_subsampling: 1,
+        }
+    }
+
+    const fn subsampled_420(bytes_per_pixel: u8, component_bits: &'static [u8]) -> Self {
+        Self {
+            bytes_per_pixel,
+            component_bits,
+            horizontal_subsampling: 2,
+            vertical_subsampling: 2,
+        }
+    }
+}
+
+/// Layouts shared by the formats of the different enums.
+mod layouts {
+    // Which layouts are referenced depends on the enabled features and API level
+    #![allow(dead_code)]
+
+    use super::PlaneLayout;
+
+    pub(super) const RGBA_8888: &[PlaneLayout] = &[PlaneLayout::full(4, &[8, 8, 8, 8])];
+    pub(super) const RGB_888: &[PlaneLayout] = &[PlaneLayout::full(3, &[8, 8, 8])];
+    pub(super) const RGB_565: &[PlaneLayout] = &[PlaneLayout::full(2, &[5, 6, 5])];
+    pub(super) const RGBA_F16: &[PlaneLayout] = &[PlaneLayout::full(8, &[16, 16, 16, 16])];
+    pub(super) const RGBA_1010102: &[PlaneLayout] = &[PlaneLayout::full(4, &[10, 10, 10, 2])];
+    pub(super) const SINGLE_8: &[PlaneLayout] = &[PlaneLayout::full(1, &[8])];
+    pub(super) const SINGLE_16: &[PlaneLayout] = &[PlaneLayout::full(2, &[16])];
+    pub(super) const YUV_420_8: &[PlaneLayout] = &[
+        PlaneLayout::full(1, &[8]),
+        PlaneLayout::subsampled_420(1, &[8]),
+        PlaneLayout::subsampled_420(1, &[8]),
+    ];
+    pub(super) const YUV_420_10: &[PlaneLayout] = &[
+        PlaneLayout::full(2, &[10]),
+        PlaneLayout::subsampled_420(2, &[10]),
+        PlaneLayout::subsampled_420(2, &[10]),
+    ];
+    pub(super) const D24: &[PlaneLayout] = &[PlaneLayout::full(3, &[24])];
+    pub(super) const D24_S8: &[PlaneLayout] = &[PlaneLayout::full(4, &[24, 8])];
+    pub(super) const D32: &[PlaneLayout] = &[PlaneLayout::full(4, &[32])];
+    pub(super) const D32_S8: &[PlaneLayout] = &[PlaneLayout::full(5, &[32, 8])];
+    pub(super) const RGBA_4444: &[PlaneLayout] = &[PlaneLayout::full(2, &[4, 4, 4, 4])];
+}
+
+use layouts::*;
+
+impl HardwareBufferFormat {
+    /// Returns the layout of every plane of this format, or [`None`] for formats without a
+    /// defined layout such as [`HardwareBufferFormat::BLOB`].
+    ///
+    /// Planar YUV formats are described with separate Y, Cb and Cr planes, matching
+    /// [`HardwareBuffer::lock_planes()`][crate::hardware_buffer::HardwareBuffer::lock_planes()].
+    pub fn plane_layouts(self) -> Option<&'static [PlaneLayout]> {
+        Some(match self {
+            Self::R8G8B8A8_UNORM | Self::R8G8B8X8_UNORM => RGBA_8888,
+            #[cfg(feature = "api-level-26")]
+            Self::R8G8B8_UNORM => RGB_888,
+            Self::R5G6B5_UNORM => RGB_565,
+            #[cfg(feature = "api-level-26")]
+            Self::R16G16B16A16_FLOAT => RGBA_F16,
+            #[cfg(feature = "api-level-26")]
+            Self::R10G10B10A2_UNORM => RGBA_1010102,
+            #[cfg(feature = "api-level-26")]
+            Self::D16_UNORM => SINGLE_16,
+            #[cfg(feature = "api-level-26")]
+            Self::D24_UNORM => D24,
+            #[cfg(feature = "api-level-26")]
+            Self::D24_UNORM_S8_UINT => D24_S8,
+            #[cfg(feature = "api-level-26")]
+            Self::D32_FLOAT => D32,
+            #[cfg(feature = "api-level-26")]
+            Self::D32_FLOAT_S8_UINT => D32_S8,
+            #[cfg(feature = "api-level-26")]
+            Self::S8_UINT | Self::R8_UNORM => SINGLE_8,
+            #[cfg(feature = "api-level-26")]
+            Self::Y8Cb8Cr8_420 => YUV_420_8,
+            #[cfg(feature = "api-level-26")]
+            Self::YCbCr_P010 => YUV_420_10,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "bitmap")]
+impl BitmapFormat {
+    /// Returns the layout of the single plane of this format, or [`None`] for
+    /// [`BitmapFormat::NONE`].
+    #[allow(deprecated)]
+    pub fn plane_layouts(self) -> Option<&'static [PlaneLayout]> {
+        Some(match self {
+            Self::RGBA_8888 => RGBA_8888,
+            Self::RGB_565 => RGB_565,
+            Self::RGBA_4444 => RGBA_4444,
+            Self::A_8 => SINGLE_8,
+            Self::RGBA_F16 => RGBA_F16,
+            Self::RGBA_1010102 => RGBA_1010102,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(all(feature = "media", feature = "api-level-24"))]
+impl ImageFormat {
+    /// Returns the layout of every plane of this format, or [`None`] for compressed, opaque and
+    /// device-specific formats such as [`ImageFormat::JPEG`] and [`ImageFormat::PRIVATE`].
+    pub fn plane_layouts(self) -> Option<&'static [PlaneLayout]> {
+        Some(match self {
+            Self::RGBA_8888 | Self::RGBX_8888 => RGBA_8888,
+            Self::RGB_888 => RGB_888,
+            Self::RGB_565 => RGB_565,
+            Self::RGBA_FP16 => RGBA_F16,
+            Self::YUV_420_888 => YUV_420_8,
+            Self::RAW16 | Self::DEPTH16 => SINGLE_16,
+            Self::Y8 => SINGLE_8,
+            _ => return None,
+        })
+    }
+}
+
+/// A plane of pixel data, as the input of the conversion functions in this module.
+///
+/// Sample `(x, y)` of the plane starts at byte `y * row_stride + x * pixel_stride` of `data`,
+/// where `x` and `y` are already divided by the subsampling of the plane.
+#[derive(Clone, Copy, Debug)]
+pub struct Plane<'a> {
+    pub data: &'a [u8],
+    /// The distance between the starts of two rows, in bytes.
+    pub row_stride: usize,
+    /// The distance between the starts of two samples in a row, in bytes.
+    pub pixel_stride: usize,
+}
+
+impl<'a> Plane<'a> {
+    pub fn new(data: &'a [u8], row_stride: usize, pixel_stride: usize) -> Self {
+        Self {
+            data,
+            row_stride,
+            pixel_stride,
+        }
+    }
+
+    /// Retrieves plane `index` of `image` through [`Image::plane_data()`],
+    /// [`Image::plane_row_stride()`] and [`Image::plane_pixel_stride()`].
+    #[cfg(all(feature = "media", feature = "api-level-24"))]
+    pub fn from_image(image: &'a Image, index: i32) -> crate::media_error::Result<Self> {
+        Ok(Self {
+            data: image.plane_data(index)?,
+            row_stride: image.plane_row_stride(index)? as usize,
+            pixel_stride: image.plane_pixel_stride(index)? as usize,
+        })
+    }
+
+    /// Wraps a plane returned by
+    /// [`HardwareBuffer::lock_planes()`][crate::hardware_buffer::HardwareBuffer::lock_planes()],
+    /// that holds `rows` rows of samples.
+    ///
+    /// # Safety
+    /// `plane` must be locked for reading for the lifetime `'a`, and hold at least `rows` rows of
+    /// [`LockedPlaneInfo::bytes_per_stride`] bytes.
+    #[cfg(feature = "api-level-26")]
+    pub unsafe fn from_locked(plane: &LockedPlaneInfo, rows: usize) -> Self {
+        let row_stride = plane.bytes_per_stride as usize;
+        Self {
+            data: std::slice::from_raw_parts(plane.virtual_address.cast(), row_stride * rows),
+            row_stride,
+            pixel_stride: plane.bytes_per_pixel as usize,
+        }
+    }
+
+    fn offset(&self, x: usize, y: usize) -> usize {
+        y * self.row_stride + x * self.pixel_stride
+    }
+
+    /// Checks that `width` * `height` samples of `bytes` each fit in `data`.
+    fn check(&self, plane: usize, width: usize, height: usize, bytes: usize) -> Result<()> {
+        if width == 0
+            || height == 0
+            || self.offset(width - 1, height - 1) + bytes <= self.data.len()
+        {
+            Ok(())
+        } else {
+            Err(PixelConversionError::PlaneTooSmall { plane })
+        }
+    }
+
+    fn u8_at(&self, x: usize, y: usize) -> u8 {
+        self.data[self.offset(x, y)]
+    }
+
+    fn u16_at(&self, x: usize, y: usize) -> u16 {
+        let offset = self.offset(x, y);
+        u16::from_le_bytes([self.data[offset], self.data[offset + 1]])
+    }
+}
+
+/// Errors returned by the conversion functions in this module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum PixelConversionError {
+    /// The input plane at this index does not hold all samples of the image.
+    #[error("Input plane {plane} is too small for the image size")]
+    PlaneTooSmall { plane: usize },
+    /// The destination buffer does not hold all pixels of the image.
+    #[error("Destination buffer is too small for the image size")]
+    DestinationTooSmall,
+}
+
+pub type Result<T, E = PixelConversionError> = std::result::Result<T, E>;
+
+/// The three planes of a YUV image with 2x2 subsampled chroma, such as
+/// [`ImageFormat::YUV_420_888`] and [`HardwareBufferFormat::YCbCr_P010`].
+///
+/// Interleaved chroma arrangements such as NV12 and NV21 are described by `u` and `v` planes that
+/// point into the same memory, with a `pixel_stride` of two samples.
+///
+/// [`ImageFormat::YUV_420_888`]: crate::media::image_reader::ImageFormat::YUV_420_888
+#[derive(Clone, Copy, Debug)]
+pub struct YuvPlanes<'a> {
+    pub y: Plane<'a>,
+    pub u: Plane<'a>,
+    pub v: Plane<'a>,
+}
+
+impl<'a> YuvPlanes<'a> {
+    /// Planes of a tightly packed 8-bit I420 (YU12) buffer: a `width` * `height` Y plane followed
+    /// by a U and a V plane of half the width and height each.
+    ///
+    /// A `data` buffer that is too small is reported by the conversion functions.
+    pub fn i420(data: &'a [u8], width: usize, height: usize) -> Self {
+        let (chroma_width, chroma_height) = ((width + 1) / 2, (height + 1) / 2);
+        let u_offset = width * height;
+        let v_offset = u_offset + chroma_width * chroma_height;
+        Self {
+            y: Plane::new(data, width, 1),
+            u: Plane::new(data.get(u_offset..).unwrap_or(&[]), chroma_width, 1),
+            v: Plane::new(data.get(v_offset..).unwrap_or(&[]), chroma_width, 1),
+        }
+    }
+
+    /// Planes of a tightly packed 8-bit NV12 buffer: a `width` * `height` Y plane followed by a
+    /// plane of interleaved U and V samples, starting with U.
+    ///
+    /// A `data` buffer that is too small is reported by the conversion functions.
+    pub fn nv12(data: &'a [u8], width: usize, height: usize) -> Self {
+        Self::interleaved(data, width, height, 0, 1)
+    }
+
+    /// Planes of a tightly packed 8-bit NV21 buffer: a `width` * `height` Y plane followed by a
+    /// plane of interleaved V and U samples, starting with V.
+    ///
+    /// A `data` buffer that is too small is reported by the conversion functions.
+    pub fn nv21(data: &'a [u8], width: usize, height: usize) -> Self {
+        Self::interleaved(data, width, height, 1, 0)
+    }
+
+    fn interleaved(data: &'a [u8], width: usize, height: usize, u: usize, v: usize) -> Self {
+        let row_stride = (width + 1) / 2 * 2;
+        let chroma_offset = width * height;
+        Self {
+            y: Plane::new(data, width, 1),
+            u: Plane::new(data.get(chroma_offset + u..).unwrap_or(&[]), row_stride, 2),
+            v: Plane::new(data.get(chroma_offset + v..).unwrap_or(&[]), row_stride, 2),
+        }
+    }
+
+    /// Retrieves the three planes of `image` with [`Plane::from_image()`].
+    #[cfg(all(feature = "media", feature = "api-level-24"))]
+    pub fn from_image(image: &'a Image) -> crate::media_error::Result<Self> {
+        Ok(Self {
+            y: Plane::from_image(image, 0)?,
+            u: Plane::from_image(image, 1)?,
+            v: Plane::from_image(image, 2)?,
+        })
+    }
+}
+
+/// A rectangular region of an image, in pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    /// The region covering a whole image of `width` * `height` pixels.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }
+    }
+}
+
+/// The matrix coefficients used to convert YCbCr to RGB, see [`YuvConversion`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum YuvMatrix {
+    /// ITU-R BT.601, used by JPEG and standard-definition video.
+    Bt601,
+    /// ITU-R BT.709, used by high-definition video.
+    Bt709,
+    /// ITU-R BT.2020 (non-constant luminance), used by HDR video.
+    Bt2020,
+}
+
+impl YuvMatrix {
+    /// The luma weights `(Kr, Kb)` of red and blue.
+    fn weights(self) -> (f32, f32) {
+        match self {
+            Self::Bt601 => (0.299, 0.114),
+            Self::Bt709 => (0.2126, 0.0722),
+            Self::Bt2020 => (0.2627, 0.0593),
+        }
+    }
+}
+
+/// Describes how YCbCr samples are converted to RGB: the [`YuvMatrix`], and whether the samples
+/// use the full range of their bit depth or the limited ("video") range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct YuvConversion {
+    pub matrix: YuvMatrix,
+    pub full_range: bool,
+}
+
+impl YuvConversion {
+    /// Full-range BT.601, as used by JPEG and produced by the camera for
+    /// [`ImageFormat::YUV_420_888`] images.
+    ///
+    /// [`ImageFormat::YUV_420_888`]: crate::media::image_reader::ImageFormat::YUV_420_888
+    pub const JFIF: Self = Self {
+        matrix: YuvMatrix::Bt601,
+        full_range: true,
+    };
+
+    /// Limited-range BT.2020, as used by HDR video in [`HardwareBufferFormat::YCbCr_P010`].
+    pub const BT2020_LIMITED: Self = Self {
+        matrix: YuvMatrix::Bt2020,
+        full_range: false,
+    };
+
+    /// Derives the conversion from the [`DataSpaceStandard`] and [`DataSpaceRange`] of
+    /// `data_space`, for an image of `width` * `height` pixels.
+    ///
+    /// Unspecified aspects follow the suggestions of [`DataSpaceStandard::Unspecified`] and
+    /// [`DataSpaceRange::Unspecified`] for YCbCr formats: BT.709 from 720p and up, BT.601 for
+    /// smaller images, and limited range.
+    ///
+    /// [`DataSpaceStandard`]: crate::data_space::DataSpaceStandard
+    /// [`DataSpaceRange`]: crate::data_space::DataSpaceRange
+    /// [`DataSpaceStandard::Unspecified`]: crate::data_space::DataSpaceStandard::Unspecified
+    /// [`DataSpaceRange::Unspecified`]: crate::data_space::DataSpaceRange::Unspecified
+    #[cfg(feature = "api-level-28")]
+    pub fn from_data_space(data_space: DataSpace, width: usize, height: usize) -> Self {
+        use crate::data_space::{DataSpaceRange, DataSpaceStandard};
+
+        let matrix = match data_space.standard() {
+            DataSpaceStandard::Bt601_525
+            | DataSpaceStandard::Bt601_525Unadjusted
+            | DataSpaceStandard::Bt601_625
+            | DataSpaceStandard::Bt601_625Unadjusted => YuvMatrix::Bt601,
+            DataSpaceStandard::Bt2020 | DataSpaceStandard::Bt2020ConstantLuminance => {
+                YuvMatrix::Bt2020
+            }
+            DataSpaceStandard::Unspecified if width < 1280 && height < 720 => YuvMatrix::Bt601,
+            _ => YuvMatrix::Bt709,
+        };
+        Self {
+            matrix,
+            full_range: data_space.range() == DataSpaceRange::Full,
+        }
+    }
+
+    /// Returns the factors of `(Y, Cr → R, Cb → G, Cr → G, Cb → B)` that convert samples,
+    /// normalized to `0..=1` (luma) and `-0.5..=0.5` (chroma) in full range, to RGB.
+    fn coefficients(self) -> [f32; 5] {
+        let (kr, kb) = self.matrix.weights();
+        let kg = 1.0 - kr - kb;
+        // Limited range maps 219 (luma) and 224 (chroma) steps of 256 to the nominal range
+        let (luma, chroma) = if self.full_range {
+            (1.0, 1.0)
+        } else {
+            (255.0 / 219.0, 255.0 / 224.0)
+        };
+        [
+            luma,
+            2.0 * (1.0 - kr) * chroma,
+            2.0 * kb * (1.0 - kb) / kg * chroma,
+            2.0 * kr * (1.0 - kr) / kg * chroma,
+            2.0 * (1.0 - kb) * chroma,
+        ]
+    }
+
+    /// The luma value that maps to black, for samples of `bits` bits.
+    fn luma_offset(self, bits: u32) -> i32 {
+        if self.full_range {
+            0
+        } else {
+            16 << (bits - 8)
+        }
+    }
+}
+
+/// Returns the destination rows of `width` pixels of `bytes_per_pixel` bytes each.
+fn dst_rows(
+    dst: &mut [u8],
+    dst_row_stride: usize,
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+) -> Result<impl Iterator<Item = &mut [u8]>> {
+    let row_bytes = width * bytes_per_pixel;
+    if height > 0
+        && (dst_row_stride < row_bytes || (height - 1) * dst_row_stride + row_bytes > dst.len())
+    {
+        return Err(PixelConversionError::DestinationTooSmall);
+    }
+    Ok(dst
+        .chunks_mut(dst_row_stride.max(1))
+        .take(height)
+        .map(move |row| &mut row[..row_bytes]))
+}
+
+/// Checks that the samples of all `planes` within `region` fit in their data.
+fn check_yuv_planes(planes: &YuvPlanes<'_>, region: Region, bytes: usize) -> Result<()> {
+    let (right, bottom) = (region.x + region.width, region.y + region.height);
+    if region.width == 0 || region.height == 0 {
+        return Ok(());
+    }
+    planes.y.check(0, right, bottom, bytes)?;
+    planes
+        .u
+        .check(1, (right + 1) / 2, (bottom + 1) / 2, bytes)?;
+    planes.v.check(2, (right + 1) / 2, (bottom + 1) / 2, bytes)
+}
+
+/// Converts `region` of an 8-bit YUV 4:2:0 image, such as [`ImageFormat::YUV_420_888`] or
+/// [`HardwareBufferFormat::Y8Cb8Cr8_420`], to [`BitmapFormat::RGBA_8888`] pixels in `dst`, whose
+/// rows start every `dst_row_stride` bytes.
+///
+/// The alpha channel is set to `255`.
+///
+/// ```
+/// # use ndk::pixel_format::{yuv_420_888_to_rgba8888, Region, YuvConversion, YuvPlanes};
+/// // A 2x2 I420 image with a single (grey) chroma sample
+/// let i420 = [0, 128, 255, 128, 128, 128];
+/// let mut rgba = [0; 16];
+/// yuv_420_888_to_rgba8888(
+///     &YuvPlanes::i420(&i420, 2, 2),
+///     Region::new(2, 2),
+///     YuvConversion::JFIF,
+///     &mut rgba,
+///     8,
+/// )
+/// .unwrap();
+/// assert_eq!(rgba[..8], [0, 0, 0, 255, 128, 128, 128, 255]);
+/// ```
+///
+/// [`ImageFormat::YUV_420_888`]: crate::media::image_reader::ImageFormat::YUV_420_888
+/// [`BitmapFormat::RGBA_8888`]: crate::bitmap::BitmapFormat::RGBA_8888
+pub fn yuv_420_888_to_rgba8888(
+    planes: &YuvPlanes<'_>,
+    region: Region,
+    conversion: YuvConversion,
+    dst: &mut [u8],
+    dst_row_stride: usize,
+) -> Result<()> {
+    check_yuv_planes(planes, region, 1)?;
+
+    // Fixed-point coefficients, scaled by 2^16
+    let [y_scale, cr_r, cb_g, cr_g, cb_b] = conversion.coefficients().map(|c| (c * 65536.0) as i32);
+    let luma_offset = conversion.luma_offset(8);
+
+    let rows = dst_rows(dst, dst_row_stride, region.width, region.height, 4)?;
+    for (row, dst) in (region.y..).zip(rows) {
+        for (col, dst) in (region.x..).zip(dst.chunks_exact_mut(4)) {
+            let luma = (planes.y.u8_at(col, row) as i32 - luma_offset) * y_scale + 32768;
+            let cb = planes.u.u8_at(col / 2, row / 2) as i32 - 128;
+            let cr = planes.v.u8_at(col / 2, row / 2) as i32 - 128;
+            let r = (luma + cr_r * cr) >> 16;
+            let g = (luma - cb_g * cb - cr_g * cr) >> 16;
+            let b = (luma + cb_b * cb) >> 16;
+            dst.copy_from_slice(&[
+                r.clamp(0, 255) as u8,
+                g.clamp(0, 255) as u8,
+                b.clamp(0, 255) as u8,
+                255,
+            ]);
+        }
+    }
+    Ok(())
+}
+
+/// Converts `width` * `height` [`BitmapFormat::RGB_565`] pixels (stored as native-endian `u16`
+/// with red in the most significant bits) to [`BitmapFormat::RGBA_8888`] pixels in `dst`, whose
+/// rows start every `dst_row_stride` bytes.
+///
+/// Components are expanded by replicating their most significant bits, so that the maximum value
+/// maps to `255`. The alpha channel is set to `255`.
+///
+/// ```
+/// # use ndk::pixel_format::{rgb565_to_rgba8888, Plane};
+/// let src = [0xf800u16.to_ne_bytes(), 0x07e0u16.to_ne_bytes()].concat();
+/// let mut rgba = [0; 8];
+/// rgb565_to_rgba8888(2, 1, &Plane::new(&src, 4, 2), &mut rgba, 8).unwrap();
+/// assert_eq!(rgba, [255, 0, 0, 255, 0, 255, 0, 255]);
+/// ```
+///
+/// [`BitmapFormat::RGB_565`]: crate::bitmap::BitmapFormat::RGB_565
+/// [`BitmapFormat::RGBA_8888`]: crate::bitmap::BitmapFormat::RGBA_8888
+pub fn rgb565_to_rgba8888(
+    width: usize,
+    height: usize,
+    src: &Plane<'_>,
+    dst: &mut [u8],
+    dst_row_stride: usize,
+) -> Result<()> {
+    src.check(0, width, height, 2)?;
+
+    for (row, dst) in dst_rows(dst, dst_row_stride, width, height, 4)?.enumerate() {
+        for (col, dst) in dst.chunks_exact_mut(4).enumerate() {
+            let offset = src.offset(col, row);
+            let pixel = u16::from_ne_bytes([src.data[offset], src.data[offset + 1]]);
+            let r = (pixel >> 11) as u8;
+            let g = ((pixel >> 5) & 0x3f) as u8;
+            let b = (pixel & 0x1f) as u8;
+            dst.copy_from_slice(&[
+                (r << 3) | (r >> 2),
+                (g << 2) | (g >> 4),
+                (b << 3) | (b >> 2),
+                255,
+            ]);
+        }
+    }
+    Ok(())
+}
+
+/// Converts `region` of a [`HardwareBufferFormat::YCbCr_P010`] image to
+/// [`BitmapFormat::RGBA_F16`] pixels (stored as native-endian half-precision floats) in `dst`,
+/// whose rows start every `dst_row_stride` bytes.
+///
+/// Every sample is a little-endian `u16` holding 10 bits in its most significant bits. P010 is
+/// typically [`YuvConversion::BT2020_LIMITED`]. The result is non-linear RGB in the nominal
+/// range `[0, 1]`: no transfer function (such as HLG or PQ) is applied. The alpha channel is set
+/// to `1.0`.
+///
+/// [`BitmapFormat::RGBA_F16`]: crate::bitmap::BitmapFormat::RGBA_F16
+pub fn p010_to_rgba16f(
+    planes: &YuvPlanes<'_>,
+    region: Region,
+    conversion: YuvConversion,
+    dst: &mut [u8],
+    dst_row_stride: usize,
+) -> Result<()> {
+    check_yuv_planes(planes, region, 2)?;
+
+    let [y_scale, cr_r, cb_g, cr_g, cb_b] = conversion.coefficients();
+    let luma_offset = conversion.luma_offset(10) as f32;
+
+    const ONE: u16 = 0x3c00;
+    let rows = dst_rows(dst, dst_row_stride, region.width, region.height, 8)?;
+    for (row, dst) in (region.y..).zip(rows) {
+        for (col, dst) in (region.x..).zip(dst.chunks_exact_mut(8)) {
+            let sample = |plane: &Plane<'_>, x, y| (plane.u16_at(x, y) >> 6) as f32;
+            let luma = (sample(&planes.y, col, row) - luma_offset) * y_scale / 1023.0;
+            let cb = (sample(&planes.u, col / 2, row / 2) - 512.0) / 1023.0;
+            let cr = (sample(&planes.v, col / 2, row / 2) - 512.0) / 1023.0;
+            let r = luma + cr_r * cr;
+            let g = luma - cb_g * cb - cr_g * cr;
+            let b = luma + cb_b * cb;
+            for (dst, value) in dst.chunks_exact_mut(2).zip([
+                f16_from_f32(r),
+                f16_from_f32(g),
+                f16_from_f32(b),
+                ONE,
+            ]) {
+                dst.copy_from_slice(&value.to_ne_bytes());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Converts `value` to the bits of the nearest IEEE 754 half-precision float.
+fn f16_from_f32(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        // Infinity or NaN
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        // Overflow to infinity
+        sign | 0x7c00
+    } else if exponent <= 0 {
+        // Subnormal or zero
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        let round = ((mantissa >> (shift - 1)) & 1) as u16;
+        sign | ((mantissa >> shift) as u16 + round)
+    } else {
+        // Rounding may carry into the exponent, which correctly rounds up to the next power of two
+        let round = ((mantissa >> 12) & 1) as u16;
+        (sign | ((exponent as u16) << 10) | (mantissa >> 13) as u16) + round
+    }
+}
@@ -4,7 +4,7 @@
 #![cfg(feature = "api-level-26")]
 
 use std::{
-    ffi::CStr,
+    ffi::{c_char, c_int, CStr},
     io::{Error, Result},
     // TODO: Import from std::os::fd::{} since Rust 1.66
     os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd},
@@ -14,6 +14,16 @@ use std::{
 #[cfg(feature = "api-level-27")]
 use jni_sys::{jobject, JNIEnv};
 
+use crate::api_level::{weak_fn, Unsupported};
+
+weak_fn! {
+    fn ASharedMemory_create(name: *const c_char, size: usize) -> c_int;
+    #[cfg(feature = "api-level-27")]
+    fn ASharedMemory_dupFromJava(env: *mut JNIEnv, shared_memory: jobject) -> c_int;
+    fn ASharedMemory_getSize(fd: c_int) -> usize;
+    fn ASharedMemory_setProt(fd: c_int, prot: c_int) -> c_int;
+}
+
 /// Enables the creation, mapping, and protection control over anonymous shared memory.
 #[derive(Debug)]
 #[doc(alias = "ASharedMemory")]
@@ -37,8 +47,7 @@ impl SharedMemory {
     /// [`android.os.ParcelFileDescriptor`]: https://developer.android.com/reference/android/os/ParcelFileDescriptor
     #[doc(alias = "ASharedMemory_create")]
     pub fn create(name: Option<&CStr>, size: usize) -> Result<Self> {
-        let fd =
-            unsafe { ffi::ASharedMemory_create(name.map_or(ptr::null(), |p| p.as_ptr()), size) };
+        let fd = unsafe { ASharedMemory_create(name.map_or(ptr::null(), |p| p.as_ptr()), size)? };
         if fd < 0 {
             Err(Error::last_os_error())
         } else {
@@ -57,7 +66,7 @@ impl SharedMemory {
     #[doc(alias = "ASharedMemory_dupFromJava")]
     #[allow(clippy::not_unsafe_ptr_arg_deref)]
     pub fn dup_from_java(env: *mut JNIEnv, shared_memory: jobject) -> Result<Self> {
-        let fd = unsafe { ffi::ASharedMemory_dupFromJava(env, shared_memory) };
+        let fd = unsafe { ASharedMemory_dupFromJava(env, shared_memory)? };
         if fd < 0 {
             Err(Error::last_os_error())
         } else {
@@ -67,8 +76,8 @@ impl SharedMemory {
 
     /// Get the size of the shared memory region.
     #[doc(alias = "ASharedMemory_getSize")]
-    pub fn size(&self) -> usize {
-        unsafe { ffi::ASharedMemory_getSize(self.as_raw_fd()) }
+    pub fn size(&self) -> std::result::Result<usize, Unsupported> {
+        unsafe { ASharedMemory_getSize(self.as_raw_fd()) }
     }
 
     /// Restrict access of shared memory region.
@@ -89,7 +98,7 @@ impl SharedMemory {
     /// # unsafe {
     /// let mem = SharedMemory::create(Some(CStr::from_bytes_with_nul_unchecked(b"memory\0")), 127).unwrap();
     /// // By default it has PROT_READ | PROT_WRITE | PROT_EXEC.
-    /// let size = mem.size();
+    /// let size = mem.size().unwrap();
     /// let buffer = libc::mmap(
     ///     std::ptr::null_mut(),
     ///     size,
@@ -115,7 +124,7 @@ impl SharedMemory {
     /// ```
     #[doc(alias = "ASharedMemory_setProt")]
     pub fn set_prot(&self, prot: i32) -> Result<()> {
-        let status = unsafe { ffi::ASharedMemory_setProt(self.as_raw_fd(), prot) };
+        let status = unsafe { ASharedMemory_setProt(self.as_raw_fd(), prot)? };
         if status < 0 {
             Err(Error::last_os_error())
         } else {
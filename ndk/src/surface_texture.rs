@@ -6,9 +6,32 @@
 //! [`ASurfaceTexture`]: https://developer.android.com/ndk/reference/group/surface-texture
 #![cfg(feature = "api-level-28")]
 
-use crate::{native_window::NativeWindow, utils::status_to_io_result};
+use crate::{
+    api_level::{weak_fn, SAME_API_LEVEL},
+    native_window::NativeWindow,
+    utils::status_to_io_result,
+};
 use jni_sys::{jobject, JNIEnv};
-use std::{io::Result, ptr::NonNull, time::Duration};
+use std::{ffi::c_int, io::Result, ptr::NonNull, time::Duration};
+
+weak_fn! {
+    fn ASurfaceTexture_release(st: *mut ffi::ASurfaceTexture);
+    fn ASurfaceTexture_fromSurfaceTexture(
+        env: *mut JNIEnv,
+        surface_texture: jobject,
+    ) -> *mut ffi::ASurfaceTexture;
+    fn ASurfaceTexture_acquireANativeWindow(
+        st: *mut ffi::ASurfaceTexture,
+    ) -> *mut ffi::ANativeWindow;
+    fn ASurfaceTexture_attachToGLContext(
+        st: *mut ffi::ASurfaceTexture,
+        tex_name: u32,
+    ) -> c_int;
+    fn ASurfaceTexture_detachFromGLContext(st: *mut ffi::ASurfaceTexture) -> c_int;
+    fn ASurfaceTexture_getTransformMatrix(st: *mut ffi::ASurfaceTexture, mtx: *mut f32);
+    fn ASurfaceTexture_getTimestamp(st: *mut ffi::ASurfaceTexture) -> i64;
+    fn ASurfaceTexture_updateTexImage(st: *mut ffi::ASurfaceTexture) -> c_int;
+}
 
 /// An opaque type to manage [`android.graphics.SurfaceTexture`] from native code
 ///
@@ -22,7 +45,7 @@ unsafe impl Send for SurfaceTexture {}
 
 impl Drop for SurfaceTexture {
     fn drop(&mut self) {
-        unsafe { ffi::ASurfaceTexture_release(self.ptr.as_ptr()) }
+        unsafe { ASurfaceTexture_release(self.ptr.as_ptr()) }.expect(SAME_API_LEVEL)
     }
 }
 
@@ -47,9 +70,13 @@ impl SurfaceTexture {
     /// [`SurfaceTexture`] to stop functioning properly once the Java object gets finalized.
     /// However, this will not result in program termination.
     ///
+    /// Returns [`None`] when the `weak-linking` feature is enabled and the device does not support
+    /// [`SurfaceTexture`].
+    ///
     /// [`android.graphics.SurfaceTexture`]: https://developer.android.com/reference/android/graphics/SurfaceTexture
     pub unsafe fn from_surface_texture(env: *mut JNIEnv, surface_texture: jobject) -> Option<Self> {
-        let a_surface_texture_ptr = ffi::ASurfaceTexture_fromSurfaceTexture(env, surface_texture);
+        let a_surface_texture_ptr =
+            ASurfaceTexture_fromSurfaceTexture(env, surface_texture).ok()?;
         let s = NonNull::new(a_surface_texture_ptr)?;
         Some(SurfaceTexture::from_ptr(s))
     }
@@ -66,7 +93,8 @@ impl SurfaceTexture {
     /// Surface sur = new Surface(surfaceTexture);
     /// ```
     pub fn acquire_native_window(&self) -> Option<NativeWindow> {
-        let native_window = unsafe { ffi::ASurfaceTexture_acquireANativeWindow(self.ptr.as_ptr()) };
+        let native_window = unsafe { ASurfaceTexture_acquireANativeWindow(self.ptr.as_ptr()) }
+            .expect(SAME_API_LEVEL);
         let n = NonNull::new(native_window)?;
         Some(unsafe { NativeWindow::from_ptr(n) })
     }
@@ -83,7 +111,7 @@ impl SurfaceTexture {
     /// contexts. Note, however, that the image contents are only accessible from one OpenGL ES
     /// context at a time.
     pub fn attach_to_gl_context(&self, tex_name: u32) -> Result<()> {
-        let status = unsafe { ffi::ASurfaceTexture_attachToGLContext(self.ptr.as_ptr(), tex_name) };
+        let status = unsafe { ASurfaceTexture_attachToGLContext(self.ptr.as_ptr(), tex_name)? };
         status_to_io_result(status)
     }
 
@@ -99,7 +127,7 @@ impl SurfaceTexture {
     /// contexts. Note, however, that the image contents are only accessible from one OpenGL ES
     /// context at a time.
     pub fn detach_from_gl_context(&self) -> Result<()> {
-        let status = unsafe { ffi::ASurfaceTexture_detachFromGLContext(self.ptr.as_ptr()) };
+        let status = unsafe { ASurfaceTexture_detachFromGLContext(self.ptr.as_ptr())? };
         status_to_io_result(status)
     }
 
@@ -118,7 +146,8 @@ impl SurfaceTexture {
     /// [`gluniformmatrix4fv()`]: https://www.khronos.org/registry/OpenGL-Refpages/es3.1/html/glUniform.xhtml
     pub fn transform_matrix(&self) -> [f32; 16] {
         let mut r = [0f32; 16];
-        unsafe { ffi::ASurfaceTexture_getTransformMatrix(self.ptr.as_ptr(), r.as_mut_ptr()) };
+        unsafe { ASurfaceTexture_getTransformMatrix(self.ptr.as_ptr(), r.as_mut_ptr()) }
+            .expect(SAME_API_LEVEL);
         r
     }
 
@@ -141,7 +170,8 @@ impl SurfaceTexture {
     /// [`VK_GOOGLE_display_timing`]: https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VK_GOOGLE_display_timing.html
     pub fn timestamp(&self) -> Duration {
         Duration::from_nanos(
-            unsafe { ffi::ASurfaceTexture_getTimestamp(self.ptr.as_ptr()) }
+            unsafe { ASurfaceTexture_getTimestamp(self.ptr.as_ptr()) }
+                .expect(SAME_API_LEVEL)
                 .try_into()
                 .expect("Timestamp cannot be negative"),
         )
@@ -153,7 +183,7 @@ impl SurfaceTexture {
     /// calling thread. It will implicitly bind its texture to the `GL_TEXTURE_EXTERNAL_OES`
     /// texture target.
     pub fn update_tex_image(&self) -> Result<()> {
-        let status = unsafe { ffi::ASurfaceTexture_updateTexImage(self.ptr.as_ptr()) };
+        let status = unsafe { ASurfaceTexture_updateTexImage(self.ptr.as_ptr())? };
         status_to_io_result(status)
     }
 }
@@ -1,12 +1,29 @@
 //! Bindings for the NDK tracing API.
 //!
 //! See also [the NDK docs](https://developer.android.com/ndk/reference/group/tracing)
+//!
+//! With the `weak-linking` feature, tracing does nothing on devices that do not support it, and
+//! [`is_trace_enabled()`] returns `false` there.
 #![cfg(feature = "api-level-23")]
-use std::ffi::{CString, NulError};
+use std::ffi::{c_char, CString, NulError};
 use std::marker::PhantomData;
 
+use crate::api_level::weak_fn;
+
+weak_fn! {
+    fn ATrace_isEnabled() -> bool;
+    fn ATrace_beginSection(section_name: *const c_char);
+    fn ATrace_endSection();
+    #[cfg(feature = "api-level-29")]
+    fn ATrace_beginAsyncSection(section_name: *const c_char, cookie: i32);
+    #[cfg(feature = "api-level-29")]
+    fn ATrace_endAsyncSection(section_name: *const c_char, cookie: i32);
+    #[cfg(feature = "api-level-29")]
+    fn ATrace_setCounter(counter_name: *const c_char, counter_value: i64);
+}
+
 pub fn is_trace_enabled() -> bool {
-    unsafe { ffi::ATrace_isEnabled() }
+    unsafe { ATrace_isEnabled() }.unwrap_or(false)
 }
 
 #[derive(Debug)]
@@ -18,7 +35,8 @@ pub struct Section {
 impl Section {
     pub fn new(name: &str) -> Result<Self, NulError> {
         let section_name = CString::new(name)?;
-        unsafe { ffi::ATrace_beginSection(section_name.as_ptr()) };
+        // Without tracing support, there is no section to end either
+        let _ = unsafe { ATrace_beginSection(section_name.as_ptr()) };
 
         Ok(Self { _pd: PhantomData })
     }
@@ -30,7 +48,7 @@ impl Section {
 
 impl Drop for Section {
     fn drop(&mut self) {
-        unsafe { ffi::ATrace_endSection() };
+        let _ = unsafe { ATrace_endSection() };
     }
 }
 
@@ -52,7 +70,7 @@ pub struct AsyncSection {
 impl AsyncSection {
     pub fn new(name: &str, cookie: Cookie) -> Result<Self, NulError> {
         let section_name = CString::new(name)?;
-        unsafe { ffi::ATrace_beginAsyncSection(section_name.as_ptr(), cookie.0) };
+        let _ = unsafe { ATrace_beginAsyncSection(section_name.as_ptr(), cookie.0) };
 
         Ok(Self {
             section_name,
@@ -69,7 +87,7 @@ impl AsyncSection {
 #[cfg(feature = "api-level-29")]
 impl Drop for AsyncSection {
     fn drop(&mut self) {
-        unsafe { ffi::ATrace_endAsyncSection(self.section_name.as_ptr(), self.cookie.0) };
+        let _ = unsafe { ATrace_endAsyncSection(self.section_name.as_ptr(), self.cookie.0) };
     }
 }
 
@@ -87,6 +105,6 @@ impl Counter {
     }
 
     pub fn set_value(&self, value: i64) {
-        unsafe { ffi::ATrace_setCounter(self.name.as_ptr(), value) }
+        let _ = unsafe { ATrace_setCounter(self.name.as_ptr(), value) };
     }
 }